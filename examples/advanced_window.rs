@@ -5,19 +5,23 @@ use shellui::{App, Alignment, Color, HStack, Text, VStack, View, window_group};
 struct ContentView;
 
 impl ContentView {
+    #[allow(clippy::new_ret_no_self)]
     fn new() -> View {
-        VStack::new([
+        VStack::new::<Vec<View>>(vec![
             Text::new("Fullscreen Example")
-                .size(56.0),
+                .size(56.0)
+                .into(),
             Text::new("Press Escape or Cmd+Q to exit fullscreen")
-                .size(18.0),
+                .size(18.0)
+                .into(),
             HStack::new([
                 Text::new("Window Size:"),
                 Text::new("1200x800"),
             ])
             .spacing(16.0)
             .padding(20.0)
-            .background(Color::new(0.2, 0.3, 0.5, 1.0)),
+            .background(Color::new(0.2, 0.3, 0.5, 1.0))
+            .into(),
             VStack::new([
                 Text::new("Configuration").size(24.0),
                 Text::new("• Min Size: 600x400"),
@@ -28,7 +32,8 @@ impl ContentView {
             .spacing(10.0)
             .padding(20.0)
             .background(Color::new(0.5, 0.2, 0.2, 1.0))
-            .alignment(Alignment::Center),
+            .alignment(Alignment::Center)
+            .into(),
         ])
         .spacing(20.0)
         .padding(32.0)
@@ -43,7 +48,7 @@ struct MyAdvancedApp;
 
 impl App for MyAdvancedApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| ContentView::new())
+        window_group(ContentView::new)
             .title("Advanced ShellUI App - Custom Sizing")
             .size(1200.0, 800.0)
             .min_size(600.0, 400.0)
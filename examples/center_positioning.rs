@@ -4,7 +4,7 @@ struct CenterPositioningApp;
 
 impl App for CenterPositioningApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Center Position Demo")
             .size(800.0, 600.0)
             .resizable(true)
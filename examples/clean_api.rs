@@ -4,7 +4,7 @@ struct CleanApp;
 
 impl App for CleanApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Clean API Demo") 
             .auto_size()
             .leading()
@@ -13,15 +13,16 @@ impl App for CleanApp {
 
 fn content_view() -> View {
     // Clean API - children in constructor, styling via builder pattern
-    VStack::new([
-        Text::new("hello").color(Color::new(0.2, 0.2, 0.8, 1.0)),
+    VStack::new::<Vec<View>>(vec![
+        Text::new("hello").color(Color::new(0.2, 0.2, 0.8, 1.0)).into(),
         HStack::new([
             Text::new("Left").color(Color::new(1.0, 0.2, 0.2, 1.0)),
             Text::new("Right").color(Color::new(0.2, 1.0, 0.2, 1.0)),
         ])
         .spacing(20.0)
-        .leading(), // Justify left
-        Text::new("Bottom text").color(Color::new(0.5, 0.5, 0.5, 1.0)),
+        .leading() // Justify left
+        .into(),
+        Text::new("Bottom text").color(Color::new(0.5, 0.5, 0.5, 1.0)).into(),
     ])
     .spacing(15.0)
     .padding(30.0)
@@ -4,7 +4,7 @@ struct ContentPositioningApp;
 
 impl App for ContentPositioningApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Content Positioning Demo")
             .size(800.0, 600.0)
             .resizable(true)
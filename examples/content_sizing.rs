@@ -1,15 +1,17 @@
 //! Example: Different content sizing modes and justify alignments.
 
-use shellui::{App, Alignment, Color, ContentSizing, HStack, Justify, Text, VStack, View, window_group};
+use shellui::{App, Color, HStack, Text, VStack, View, window_group};
 
 struct ContentView;
 
 impl ContentView {
+    #[allow(clippy::new_ret_no_self)]
     fn new() -> View {
-        VStack::new([
+        VStack::new::<Vec<View>>(vec![
             Text::new("Content Sizing Demo")
                 .size(36.0)
-                .color(Color::new(0.1, 0.1, 0.6, 1.0)),
+                .color(Color::new(0.1, 0.1, 0.6, 1.0))
+                .into(),
             HStack::new([
                 Text::new("Leading").color(Color::new(0.2, 0.2, 0.2, 1.0)),
                 Text::new("Justified").color(Color::new(0.2, 0.2, 0.2, 1.0)),
@@ -17,7 +19,8 @@ impl ContentView {
             .spacing(16.0)
             .padding(20.0)
             .background(Color::new(0.7, 0.9, 0.7, 1.0))
-            .leading(), // Justify to left for HStack
+            .leading() // Justify to left for HStack
+            .into(),
             HStack::new([
                 Text::new("Center").color(Color::new(0.2, 0.2, 0.2, 1.0)),
                 Text::new("Justified").color(Color::new(0.2, 0.2, 0.2, 1.0)),
@@ -25,7 +28,8 @@ impl ContentView {
             .spacing(16.0)
             .padding(20.0)
             .background(Color::new(0.9, 0.7, 0.7, 1.0))
-            .center_justify(), // Justify to center for HStack
+            .center_justify() // Justify to center for HStack
+            .into(),
             HStack::new([
                 Text::new("Trailing").color(Color::new(0.2, 0.2, 0.2, 1.0)),
                 Text::new("Justified").color(Color::new(0.2, 0.2, 0.2, 1.0)),
@@ -33,10 +37,12 @@ impl ContentView {
             .spacing(16.0)
             .padding(20.0)
             .background(Color::new(0.7, 0.7, 0.9, 1.0))
-            .trailing(), // Justify to right for HStack
+            .trailing() // Justify to right for HStack
+            .into(),
             Text::new("Fixed 400x300 content size")
                 .size(14.0)
-                .color(Color::new(0.5, 0.5, 0.5, 1.0)),
+                .color(Color::new(0.5, 0.5, 0.5, 1.0))
+                .into(),
         ])
         .spacing(20.0)
         .padding(32.0)
@@ -45,15 +51,12 @@ impl ContentView {
         .into()
     }
 }
-            .into()
-    }
-}
 
 struct SizingApp;
 
 impl App for SizingApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| ContentView::new())
+        window_group(ContentView::new)
             .title("Content Sizing: Fixed Size Demo")
             .size(800.0, 600.0)
             .resizable(true)
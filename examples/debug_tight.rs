@@ -4,7 +4,7 @@ struct DebugSpacingApp;
 
 impl App for DebugSpacingApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Debug Spacing - Should be TIGHT")
             .auto_size()
             .leading()
@@ -1,15 +1,17 @@
 //! Example: Fill window content sizing mode.
 
-use shellui::{App, Alignment, Color, HStack, Text, VStack, View, window_group};
+use shellui::{App, Color, HStack, Text, VStack, View, window_group};
 
 struct FullWindowView;
 
 impl FullWindowView {
+    #[allow(clippy::new_ret_no_self)]
     fn new() -> View {
-        VStack::new([
+        VStack::new::<Vec<View>>(vec![
             Text::new("Full Window Layout")
                 .size(42.0)
-                .color(Color::new(1.0, 1.0, 1.0, 1.0)),
+                .color(Color::new(1.0, 1.0, 1.0, 1.0))
+                .into(),
             HStack::new([
                 VStack::new([
                     Text::new("Left").color(Color::new(1.0, 1.0, 1.0, 1.0)),
@@ -31,16 +33,18 @@ impl FullWindowView {
                 .background(Color::new(0.3, 0.3, 0.8, 1.0)),
             ])
             .spacing(20.0)
-            .space_between(), // Distribute horizontally
+            .space_between() // Distribute horizontally
+            .into(),
             Text::new("Content fills entire window and resizes with it")
                 .size(16.0)
-                .color(Color::new(0.8, 0.8, 0.8, 1.0)),
+                .color(Color::new(0.8, 0.8, 0.8, 1.0))
+                .into(),
         ])
         .spacing(24.0)
         .padding(40.0)
         .background(Color::new(0.2, 0.2, 0.3, 1.0))
         .center_justify() // Center content vertically
-        .into())
+        .into()
     }
 }
 
@@ -48,7 +52,7 @@ struct FillWindowApp;
 
 impl App for FillWindowApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| FullWindowView::new())
+        window_group(FullWindowView::new)
             .title("Fill Window Demo")
             .size(900.0, 600.0)
             .resizable(true)
@@ -4,7 +4,7 @@ struct SimpleApp;
 
 impl App for SimpleApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("New API Demo")
             .auto_size()
             .leading()
@@ -4,7 +4,7 @@ struct NoPaddingTestApp;
 
 impl App for NoPaddingTestApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("No Padding Test")
             .auto_size()
             .leading()
@@ -4,7 +4,7 @@ struct PositioningApp;
 
 impl App for PositioningApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Positioning Example")
             .auto_size() // Content is auto-sized (not full window)
             .leading()   // Position content at top-left instead of centered
@@ -0,0 +1,30 @@
+//! Example: render a view to a PNG file without opening a window.
+//!
+//! Usage: `cargo run --example screenshot -- [output.png]`
+
+use shellui::*;
+
+fn main() {
+    let view: View = VStack::new::<Vec<View>>(vec![
+        Text::new("Screenshot")
+            .size(32.0)
+            .color(Color::new(0.2, 0.2, 0.8, 1.0))
+            .into(),
+        HStack::new::<Vec<View>>(vec![
+            Shape::circle().fill(Color::new(1.0, 0.3, 0.3, 1.0)).frame(24.0, 24.0).into(),
+            Button::new("Rendered headless").into(),
+        ])
+        .spacing(12.0)
+        .into(),
+    ])
+    .spacing(16.0)
+    .padding(20.0)
+    .into();
+
+    let path = std::env::args().nth(1).unwrap_or_else(|| "screenshot.png".to_string());
+    let image = render_to_image(&view, Size::new(320.0, 160.0), 2.0);
+    match image.save_png(&path) {
+        Ok(()) => println!("Wrote {}x{} image to {path}", image.width(), image.height()),
+        Err(error) => eprintln!("Failed to write {path}: {error}"),
+    }
+}
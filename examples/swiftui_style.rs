@@ -4,7 +4,7 @@ struct NewAPIApp;
 
 impl App for NewAPIApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("New SwiftUI-Style API")
             .auto_size()
             .leading()
@@ -4,7 +4,7 @@ struct TestPaddingApp;
 
 impl App for TestPaddingApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("Padding & Spacing Test")
             .auto_size()
             .leading()
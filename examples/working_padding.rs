@@ -4,7 +4,7 @@ struct WorkingPaddingApp;
 
 impl App for WorkingPaddingApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| content_view())
+        window_group(content_view)
             .title("✅ Padding & Spacing Working!")
             .auto_size()
             .leading()
@@ -1,16 +1,22 @@
 //! SwiftUI-style App and Scene system.
 
+use std::sync::Arc;
+
+use crate::clipboard::{self, Clipboard};
+use crate::core::font;
+use crate::layout::LayoutDirection;
+use crate::render::TextAntialiasing;
 use crate::View;
 
 /// Content positioning within the window when content is smaller than window.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ContentPosition {
-    /// Content positioned at top-left corner
+    /// Content positioned at the top leading corner: top-left, or top-right right to left
     Leading,
     /// Content centered in window (default)
     #[default]
     Center,
-    /// Content positioned at bottom-right corner
+    /// Content positioned at the bottom trailing corner: bottom-right, or bottom-left right to left
     Trailing,
     /// Content positioned at top-center
     TopCenter,
@@ -50,6 +56,14 @@ pub struct WindowConfiguration {
     pub content_sizing: ContentSizing,
     /// Content positioning within window
     pub content_position: ContentPosition,
+    /// Layout direction of the root view
+    pub layout_direction: LayoutDirection,
+    /// How text edges are anti-aliased
+    pub text_antialiasing: TextAntialiasing,
+    /// Font files and data registered before the window opens
+    pub fonts: Vec<font::Source>,
+    /// Where selected text is copied to
+    pub clipboard: Arc<dyn Clipboard>,
 }
 
 impl Default for WindowConfiguration {
@@ -63,6 +77,10 @@ impl Default for WindowConfiguration {
             resizable: true,
             content_sizing: ContentSizing::Auto,
             content_position: ContentPosition::Center,
+            layout_direction: LayoutDirection::LeftToRight,
+            text_antialiasing: TextAntialiasing::Grayscale,
+            fonts: Vec::new(),
+            clipboard: clipboard::shared(),
         }
     }
 }
@@ -157,6 +175,31 @@ impl WindowConfiguration {
         self.content_position = ContentPosition::BottomCenter;
         self
     }
+
+    /// Set the layout direction views inherit (right to left for Arabic or Hebrew)
+    pub fn layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.layout_direction = direction;
+        self
+    }
+
+    /// Set how text edges are anti-aliased (subpixel for LCD panels)
+    pub fn text_antialiasing(mut self, antialiasing: TextAntialiasing) -> Self {
+        self.text_antialiasing = antialiasing;
+        self
+    }
+
+    /// Register a font file or font data, so its families can be used by views. A font
+    /// that can't be loaded when the window starts is skipped with a `log` warning.
+    pub fn register_font(mut self, source: impl Into<font::Source>) -> Self {
+        self.fonts.push(source.into());
+        self
+    }
+
+    /// Set the clipboard selected text is copied to (the system clipboard by default)
+    pub fn clipboard(mut self, clipboard: impl Clipboard + 'static) -> Self {
+        self.clipboard = Arc::new(clipboard);
+        self
+    }
 }
 
 /// A scene in the app, similar to SwiftUI's Scene protocol.
@@ -199,12 +242,19 @@ pub trait App {
     /// The content and behavior of the app
     fn body(&self) -> impl IntoScene;
 
+    /// Font files and data the app uses, registered for every window
+    fn fonts(&self) -> Vec<font::Source> {
+        Vec::new()
+    }
+
     /// Run the application
     fn run(self) 
     where 
         Self: Sized + 'static
     {
-        let scene = self.body().into_scene();
+        let mut scene = self.body().into_scene();
+        let Scene::WindowGroup { config, .. } = &mut scene;
+        config.fonts.splice(0..0, self.fonts());
         crate::window::run_scene(scene);
     }
 }
@@ -333,6 +383,30 @@ where
         self.config = self.config.bottom_center();
         self
     }
+
+    /// Set the layout direction views inherit
+    pub fn layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.config = self.config.layout_direction(direction);
+        self
+    }
+
+    /// Set how text edges are anti-aliased
+    pub fn text_antialiasing(mut self, antialiasing: TextAntialiasing) -> Self {
+        self.config = self.config.text_antialiasing(antialiasing);
+        self
+    }
+
+    /// Register a font file or font data for this window
+    pub fn register_font(mut self, source: impl Into<font::Source>) -> Self {
+        self.config = self.config.register_font(source);
+        self
+    }
+
+    /// Set the clipboard selected text is copied to
+    pub fn clipboard(mut self, clipboard: impl Clipboard + 'static) -> Self {
+        self.config = self.config.clipboard(clipboard);
+        self
+    }
 }
 
 impl<F> IntoScene for WindowGroup<F>
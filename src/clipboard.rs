@@ -0,0 +1,118 @@
+//! Clipboard access for copying and pasting text.
+//!
+//! Windows copy to and paste from the [`SystemClipboard`], with the
+//! `system-clipboard` feature (on by default). Without the feature, or where the
+//! system clipboard can't be opened, such as on a machine without a display, they
+//! share a [`MemoryClipboard`], which is also what tests use. An app can provide any
+//! other [`Clipboard`].
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A place to copy text to and paste it from.
+pub trait Clipboard: Debug + Send + Sync {
+    /// Returns the text on the clipboard, if any.
+    fn read(&self) -> Option<String>;
+
+    /// Replaces the contents of the clipboard with `contents`.
+    fn write(&self, contents: String);
+}
+
+/// A clipboard that keeps its contents in memory.
+#[derive(Debug, Default)]
+pub struct MemoryClipboard {
+    contents: Mutex<Option<String>>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn read(&self) -> Option<String> {
+        self.contents.lock().unwrap().clone()
+    }
+
+    fn write(&self, contents: String) {
+        *self.contents.lock().unwrap() = Some(contents);
+    }
+}
+
+/// The clipboard of the platform, through the `arboard` crate.
+#[cfg(feature = "system-clipboard")]
+pub struct SystemClipboard {
+    clipboard: Mutex<arboard::Clipboard>,
+}
+
+#[cfg(feature = "system-clipboard")]
+impl SystemClipboard {
+    /// Opens the system clipboard. Fails where there is none, e.g. without a display.
+    pub fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            clipboard: Mutex::new(arboard::Clipboard::new()?),
+        })
+    }
+}
+
+#[cfg(feature = "system-clipboard")]
+impl Debug for SystemClipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemClipboard").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "system-clipboard")]
+impl Clipboard for SystemClipboard {
+    fn read(&self) -> Option<String> {
+        self.clipboard.lock().unwrap().get_text().ok()
+    }
+
+    fn write(&self, contents: String) {
+        // Nothing useful can be done when the platform refuses the text
+        let _ = self.clipboard.lock().unwrap().set_text(contents);
+    }
+}
+
+/// Returns the clipboard windows use unless configured otherwise. It is cheap to
+/// create: the system clipboard is only opened on the first copy or paste.
+pub(crate) fn shared() -> Arc<dyn Clipboard> {
+    Arc::new(Shared)
+}
+
+/// The system clipboard if it can be opened, else one in memory, opened once and
+/// shared by the windows of the process.
+#[derive(Debug)]
+struct Shared;
+
+impl Shared {
+    fn clipboard(&self) -> &'static dyn Clipboard {
+        static CLIPBOARD: OnceLock<Box<dyn Clipboard>> = OnceLock::new();
+
+        CLIPBOARD.get_or_init(open).as_ref()
+    }
+}
+
+impl Clipboard for Shared {
+    fn read(&self) -> Option<String> {
+        self.clipboard().read()
+    }
+
+    fn write(&self, contents: String) {
+        self.clipboard().write(contents);
+    }
+}
+
+#[cfg(feature = "system-clipboard")]
+fn open() -> Box<dyn Clipboard> {
+    match SystemClipboard::new() {
+        Ok(clipboard) => Box::new(clipboard),
+        Err(_) => Box::new(MemoryClipboard::new()),
+    }
+}
+
+#[cfg(not(feature = "system-clipboard"))]
+fn open() -> Box<dyn Clipboard> {
+    Box::new(MemoryClipboard::new())
+}
@@ -1,10 +1,18 @@
 //! Background fill for widgets.
 
+use crate::core::gradient::{AngularGradient, LinearGradient, RadialGradient};
+
 /// A background fill.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Background {
     /// Solid color background.
     Color(Color),
+    /// Colors interpolated along a line.
+    LinearGradient(LinearGradient),
+    /// Colors interpolated outwards from a center.
+    RadialGradient(RadialGradient),
+    /// Colors interpolated around a center.
+    AngularGradient(AngularGradient),
 }
 
 impl From<Color> for Background {
@@ -13,6 +21,24 @@ impl From<Color> for Background {
     }
 }
 
+impl From<LinearGradient> for Background {
+    fn from(gradient: LinearGradient) -> Self {
+        Background::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Background {
+    fn from(gradient: RadialGradient) -> Self {
+        Background::RadialGradient(gradient)
+    }
+}
+
+impl From<AngularGradient> for Background {
+    fn from(gradient: AngularGradient) -> Self {
+        Background::AngularGradient(gradient)
+    }
+}
+
 /// A color in RGBA format.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
@@ -31,6 +57,12 @@ impl Color {
     pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
     pub const TRANSPARENT: Self = Self::new(0.0, 0.0, 0.0, 0.0);
 
+    /// Create an opaque color from a u32 in 0x00RRGGBB format.
+    pub fn from_u32(rgb: u32) -> Self {
+        let channel = |shift: u32| ((rgb >> shift) & 0xFF) as f32 / 255.0;
+        Self::new(channel(16), channel(8), channel(0), 1.0)
+    }
+
     /// Convert to u32 in 0x00RRGGBB format.
     pub fn to_u32(self) -> u32 {
         let r = (self.r * 255.0).clamp(0.0, 255.0) as u32;
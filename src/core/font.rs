@@ -0,0 +1,170 @@
+//! Font descriptors and font files to register.
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+/// A font family: a name such as `"Roboto"`, or a generic family resolved by the
+/// font database.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Family {
+    Name(String),
+    Serif,
+    SansSerif,
+    Monospace,
+    Cursive,
+    Fantasy,
+}
+
+impl From<&str> for Family {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+impl From<String> for Family {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+/// The weight (boldness) of a font, from 100 (thin) to 900 (black).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Weight(pub u16);
+
+impl Weight {
+    pub const THIN: Self = Self(100);
+    pub const EXTRA_LIGHT: Self = Self(200);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMIBOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const EXTRA_BOLD: Self = Self(800);
+    pub const BLACK: Self = Self(900);
+}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// The slant of a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Style {
+    #[default]
+    Normal,
+    Italic,
+    Oblique,
+}
+
+/// The width of a font, relative to its normal width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Stretch {
+    UltraCondensed,
+    ExtraCondensed,
+    Condensed,
+    SemiCondensed,
+    #[default]
+    Normal,
+    SemiExpanded,
+    Expanded,
+    ExtraExpanded,
+    UltraExpanded,
+}
+
+/// Describes the font text is drawn with.
+///
+/// `families` is a fallback chain: the first family that is installed or
+/// registered is used, and an empty chain means the default sans-serif font.
+/// Characters missing from the chosen font still fall back to other fonts that
+/// have them. Italic is slanted synthetically when the family has no italic face.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Font {
+    pub families: Vec<Family>,
+    pub weight: Weight,
+    pub style: Style,
+    pub stretch: Stretch,
+}
+
+impl Font {
+    /// The default font: regular sans-serif.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A font of the given family.
+    pub fn family(family: impl Into<Family>) -> Self {
+        Self {
+            families: vec![family.into()],
+            ..Self::default()
+        }
+    }
+
+    /// A monospaced font.
+    pub fn monospace() -> Self {
+        Self::family(Family::Monospace)
+    }
+
+    /// Adds a family to try when none of the previous ones is available.
+    pub fn fallback(mut self, family: impl Into<Family>) -> Self {
+        self.families.push(family.into());
+        self
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(Weight::BOLD)
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn italic(self) -> Self {
+        self.style(Style::Italic)
+    }
+
+    pub fn stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = stretch;
+        self
+    }
+}
+
+/// A font file to register, so its families can be used in a [`Font`].
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// A TrueType, OpenType or collection file read when the window opens.
+    File(PathBuf),
+    /// Font data, e.g. from `include_bytes!`.
+    Bytes(Cow<'static, [u8]>),
+}
+
+impl From<&'static [u8]> for Source {
+    fn from(bytes: &'static [u8]) -> Self {
+        Self::Bytes(Cow::Borrowed(bytes))
+    }
+}
+
+impl From<Vec<u8>> for Source {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(Cow::Owned(bytes))
+    }
+}
+
+impl From<&str> for Source {
+    fn from(path: &str) -> Self {
+        Self::File(PathBuf::from(path))
+    }
+}
+
+impl From<PathBuf> for Source {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
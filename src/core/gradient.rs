@@ -0,0 +1,173 @@
+//! Gradient fills for backgrounds.
+
+use std::sync::Arc;
+
+use crate::core::background::Color;
+use crate::core::point::Point;
+
+/// A color at a position along a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// Position along the gradient, from 0.0 to 1.0.
+    pub offset: f32,
+    /// Color at this position.
+    pub color: Color,
+}
+
+impl ColorStop {
+    pub const fn new(offset: f32, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// An ordered list of color stops.
+///
+/// The stops are shared, so cloning a gradient (and the [`Background`] holding it) is cheap.
+///
+/// [`Background`]: crate::core::background::Background
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub stops: Arc<[ColorStop]>,
+}
+
+impl Gradient {
+    /// Create a gradient from explicit stops. Stops are sorted by offset.
+    pub fn new(stops: impl IntoIterator<Item = ColorStop>) -> Self {
+        let mut stops: Vec<ColorStop> = stops.into_iter().collect();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Self { stops: stops.into() }
+    }
+
+    /// Create a gradient with the colors spread evenly from 0.0 to 1.0.
+    pub fn colors(colors: impl IntoIterator<Item = Color>) -> Self {
+        let colors: Vec<Color> = colors.into_iter().collect();
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        Self {
+            stops: colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| ColorStop::new(i as f32 / last, color))
+                .collect(),
+        }
+    }
+
+    /// Samples the gradient at `offset` (clamped to 0.0..=1.0), interpolating in straight alpha.
+    pub fn sample(&self, offset: f32) -> Color {
+        let Some(first) = self.stops.first() else {
+            return Color::TRANSPARENT;
+        };
+        let offset = offset.clamp(0.0, 1.0);
+        if offset <= first.offset {
+            return first.color;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if offset <= b.offset {
+                let span = b.offset - a.offset;
+                let t = if span > 0.0 { (offset - a.offset) / span } else { 1.0 };
+                return Color::new(
+                    a.color.r + (b.color.r - a.color.r) * t,
+                    a.color.g + (b.color.g - a.color.g) * t,
+                    a.color.b + (b.color.b - a.color.b) * t,
+                    a.color.a + (b.color.a - a.color.a) * t,
+                );
+            }
+        }
+
+        self.stops[self.stops.len() - 1].color
+    }
+}
+
+impl From<Vec<Color>> for Gradient {
+    fn from(colors: Vec<Color>) -> Self {
+        Self::colors(colors)
+    }
+}
+
+impl From<Vec<ColorStop>> for Gradient {
+    fn from(stops: Vec<ColorStop>) -> Self {
+        Self::new(stops)
+    }
+}
+
+/// A gradient along a line.
+///
+/// `start` and `end` are in unit space: (0, 0) is the top-left and (1, 1) the
+/// bottom-right corner of the filled bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    pub gradient: Gradient,
+    pub start: Point,
+    pub end: Point,
+}
+
+impl LinearGradient {
+    pub fn new(gradient: impl Into<Gradient>, start: Point, end: Point) -> Self {
+        Self {
+            gradient: gradient.into(),
+            start,
+            end,
+        }
+    }
+
+    /// A gradient from the top edge to the bottom edge.
+    pub fn vertical(gradient: impl Into<Gradient>) -> Self {
+        Self::new(gradient, Point::new(0.5, 0.0), Point::new(0.5, 1.0))
+    }
+
+    /// A gradient from the leading edge to the trailing edge.
+    pub fn horizontal(gradient: impl Into<Gradient>) -> Self {
+        Self::new(gradient, Point::new(0.0, 0.5), Point::new(1.0, 0.5))
+    }
+}
+
+/// A gradient radiating from a center point.
+///
+/// `center` is in unit space; the radii are in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    pub gradient: Gradient,
+    pub center: Point,
+    pub start_radius: f32,
+    pub end_radius: f32,
+}
+
+impl RadialGradient {
+    pub fn new(gradient: impl Into<Gradient>, center: Point, start_radius: f32, end_radius: f32) -> Self {
+        Self {
+            gradient: gradient.into(),
+            center,
+            start_radius,
+            end_radius,
+        }
+    }
+}
+
+/// A gradient sweeping around a center point.
+///
+/// `center` is in unit space. Angles are in radians, starting at the trailing
+/// edge and increasing clockwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AngularGradient {
+    pub gradient: Gradient,
+    pub center: Point,
+    pub start_angle: f32,
+    pub end_angle: f32,
+}
+
+impl AngularGradient {
+    pub fn new(gradient: impl Into<Gradient>, center: Point, start_angle: f32, end_angle: f32) -> Self {
+        Self {
+            gradient: gradient.into(),
+            center,
+            start_angle,
+            end_angle,
+        }
+    }
+
+    /// A full turn around the center.
+    pub fn full(gradient: impl Into<Gradient>, center: Point) -> Self {
+        Self::new(gradient, center, 0.0, std::f32::consts::TAU)
+    }
+}
@@ -0,0 +1,97 @@
+//! Load and draw raster images.
+
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::layout::{Rectangle, Size};
+
+/// A reference to an encoded image (PNG or JPEG), loaded from a path or bytes.
+///
+/// Handles are cheap to clone. Decoded pixels are cached by the renderer per
+/// [`Handle::id`], so rebuilding the view tree every frame does not decode again.
+#[derive(Debug, Clone)]
+pub struct Handle {
+    id: u64,
+    data: Arc<Data>,
+}
+
+/// The source of an image [`Handle`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Data {
+    /// An image file on disk.
+    Path(PathBuf),
+    /// An encoded image in memory.
+    Bytes(Arc<[u8]>),
+}
+
+impl Handle {
+    /// Creates a handle to the image file at `path`.
+    ///
+    /// Handles to the same path share their decoded pixels.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        path.hash(&mut hasher);
+        Self::from_data(hasher.finish(), Data::Path(path))
+    }
+
+    /// Creates a handle to an encoded image in memory.
+    ///
+    /// The image is identified by its allocation, not its contents: keep the bytes in
+    /// an `Arc` and pass clones of it (or clone the handle) so each frame reuses the
+    /// decoded pixels. Bytes copied into a new allocation are a new image.
+    pub fn from_bytes(bytes: impl Into<Arc<[u8]>>) -> Self {
+        let bytes = bytes.into();
+        Self::from_data(Arc::as_ptr(&bytes).cast::<u8>() as usize as u64, Data::Bytes(bytes))
+    }
+
+    fn from_data(id: u64, data: Data) -> Self {
+        Self { id, data: Arc::new(data) }
+    }
+
+    /// Returns the identifier of the image: a hash of its path, or the address of its bytes.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the source of the image.
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data) || self.data == other.data
+    }
+}
+
+impl<T: AsRef<Path>> From<T> for Handle {
+    fn from(path: T) -> Self {
+        Self::from_path(path)
+    }
+}
+
+/// How an image is sampled when it is scaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Nearest neighbor; keeps pixel art crisp.
+    None,
+    /// Bilinear filtering.
+    Low,
+    /// Bilinear filtering.
+    #[default]
+    Medium,
+    /// Bicubic filtering.
+    High,
+}
+
+/// A [`Renderer`](crate::core::Renderer) that can draw raster images.
+pub trait Renderer: crate::core::renderer::Renderer {
+    /// Returns the size of the image in pixels, or `None` if it cannot be decoded.
+    fn measure_image(&self, handle: &Handle) -> Option<Size>;
+
+    /// Draws the image stretched to `bounds`.
+    fn draw_image(&mut self, handle: Handle, interpolation: Interpolation, bounds: Rectangle);
+}
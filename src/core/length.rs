@@ -1,9 +1,10 @@
 //! Length units for widget sizing.
 
 /// A length unit for widget dimensions.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Length {
     /// Shrink to fit content.
+    #[default]
     Shrink,
     /// Fill available space.
     Fill,
@@ -22,12 +23,6 @@ impl Length {
     }
 }
 
-impl Default for Length {
-    fn default() -> Self {
-        Length::Shrink
-    }
-}
-
 impl From<f32> for Length {
     fn from(value: f32) -> Self {
         Length::Fixed(value)
@@ -15,8 +15,13 @@ pub mod point;
 pub mod vector;
 pub mod transformation;
 pub mod background;
+pub mod gradient;
+pub mod image;
+pub mod path;
 pub mod border;
 pub mod shadow;
+pub mod text;
+pub mod font;
 
 pub use widget::Widget;
 pub use element::Element;
@@ -35,6 +40,7 @@ pub use point::Point;
 pub use vector::Vector;
 pub use transformation::Transformation;
 pub use background::{Background, Color};
+pub use gradient::{AngularGradient, ColorStop, Gradient, LinearGradient, RadialGradient};
 pub use border::Border;
 pub use shadow::Shadow;
 
@@ -24,9 +24,10 @@ impl Cursor {
 }
 
 /// The visual interaction of the mouse cursor.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Interaction {
     /// Default cursor.
+    #[default]
     Idle,
     /// Pointer cursor (hand).
     Pointer,
@@ -38,12 +39,6 @@ pub enum Interaction {
     Text,
 }
 
-impl Default for Interaction {
-    fn default() -> Self {
-        Interaction::Idle
-    }
-}
-
 /// A mouse event.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Event {
@@ -0,0 +1,229 @@
+//! Vector paths for shapes.
+
+use crate::core::background::{Background, Color};
+use crate::core::point::Point;
+use crate::core::vector::Vector;
+use crate::layout::Rectangle;
+
+/// Control point distance for approximating a quarter circle with a cubic Bézier.
+const KAPPA: f32 = 0.552_284_8;
+
+/// A single drawing command of a [`Path`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    /// Starts a new subpath at the point.
+    MoveTo(Point),
+    /// A straight line to the point.
+    LineTo(Point),
+    /// A quadratic Bézier curve to `to`.
+    QuadTo { control: Point, to: Point },
+    /// A cubic Bézier curve to `to`.
+    CubicTo { control_a: Point, control_b: Point, to: Point },
+    /// A circular arc. Angles are in radians, starting at the trailing edge and
+    /// increasing clockwise; the arc sweeps counterclockwise if `end_angle < start_angle`.
+    Arc {
+        center: Point,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    },
+    /// Closes the current subpath with a line to its start.
+    Close,
+}
+
+/// An outline made of lines and curves, in logical pixels, built with chained calls.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+    commands: Vec<Command>,
+}
+
+impl Path {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new subpath at `point`.
+    pub fn move_to(self, point: Point) -> Self {
+        self.push(Command::MoveTo(point))
+    }
+
+    /// Adds a straight line to `point`.
+    pub fn line_to(self, point: Point) -> Self {
+        self.push(Command::LineTo(point))
+    }
+
+    /// Adds a quadratic Bézier curve to `to`.
+    pub fn quad_to(self, control: Point, to: Point) -> Self {
+        self.push(Command::QuadTo { control, to })
+    }
+
+    /// Adds a cubic Bézier curve to `to`.
+    pub fn cubic_to(self, control_a: Point, control_b: Point, to: Point) -> Self {
+        self.push(Command::CubicTo { control_a, control_b, to })
+    }
+
+    /// Adds a circular arc around `center`.
+    ///
+    /// Angles are in radians, starting at the trailing edge and increasing clockwise.
+    /// If the subpath already has a current point, a line connects it to the start of the arc.
+    pub fn arc(self, center: Point, radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        self.push(Command::Arc {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+        })
+    }
+
+    /// Closes the current subpath.
+    pub fn close(self) -> Self {
+        self.push(Command::Close)
+    }
+
+    /// A rectangle covering `bounds`.
+    pub fn rectangle(bounds: Rectangle) -> Self {
+        let Rectangle { x, y, width, height } = bounds;
+        Self::new()
+            .move_to(Point::new(x, y))
+            .line_to(Point::new(x + width, y))
+            .line_to(Point::new(x + width, y + height))
+            .line_to(Point::new(x, y + height))
+            .close()
+    }
+
+    /// A rectangle covering `bounds` with corners rounded by `radius`.
+    pub fn rounded_rectangle(bounds: Rectangle, radius: f32) -> Self {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let Rectangle { x, y, width, height } = bounds;
+        let r = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+        if r == 0.0 {
+            return Self::rectangle(bounds);
+        }
+        let (right, bottom) = (x + width, y + height);
+
+        Self::new()
+            .move_to(Point::new(x + r, y))
+            .arc(Point::new(right - r, y + r), r, -FRAC_PI_2, 0.0)
+            .arc(Point::new(right - r, bottom - r), r, 0.0, FRAC_PI_2)
+            .arc(Point::new(x + r, bottom - r), r, FRAC_PI_2, PI)
+            .arc(Point::new(x + r, y + r), r, PI, PI + FRAC_PI_2)
+            .close()
+    }
+
+    /// An ellipse inscribed in `bounds`.
+    pub fn ellipse(bounds: Rectangle) -> Self {
+        let (rx, ry) = (bounds.width / 2.0, bounds.height / 2.0);
+        let (cx, cy) = (bounds.x + rx, bounds.y + ry);
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+
+        Self::new()
+            .move_to(Point::new(cx + rx, cy))
+            .cubic_to(Point::new(cx + rx, cy + ky), Point::new(cx + kx, cy + ry), Point::new(cx, cy + ry))
+            .cubic_to(Point::new(cx - kx, cy + ry), Point::new(cx - rx, cy + ky), Point::new(cx - rx, cy))
+            .cubic_to(Point::new(cx - rx, cy - ky), Point::new(cx - kx, cy - ry), Point::new(cx, cy - ry))
+            .cubic_to(Point::new(cx + kx, cy - ry), Point::new(cx + rx, cy - ky), Point::new(cx + rx, cy))
+            .close()
+    }
+
+    /// A circle around `center`.
+    pub fn circle(center: Point, radius: f32) -> Self {
+        Self::ellipse(Rectangle::new(
+            center.x - radius,
+            center.y - radius,
+            radius * 2.0,
+            radius * 2.0,
+        ))
+    }
+
+    /// Returns the path moved by `translation`.
+    pub fn translate(mut self, translation: Vector) -> Self {
+        for command in &mut self.commands {
+            match command {
+                Command::MoveTo(point) | Command::LineTo(point) => *point = *point + translation,
+                Command::QuadTo { control, to } => {
+                    *control = *control + translation;
+                    *to = *to + translation;
+                }
+                Command::CubicTo { control_a, control_b, to } => {
+                    *control_a = *control_a + translation;
+                    *control_b = *control_b + translation;
+                    *to = *to + translation;
+                }
+                Command::Arc { center, .. } => *center = *center + translation,
+                Command::Close => {}
+            }
+        }
+        self
+    }
+
+    /// Returns the commands of the path in order.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Returns whether the path has no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn push(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+}
+
+/// How the outline of a [`Path`] is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    /// The color of the line.
+    pub color: Color,
+    /// The width of the line in logical pixels, centered on the outline.
+    pub width: f32,
+    /// Alternating lengths of dashes and gaps. Empty for a solid line.
+    pub dash: Vec<f32>,
+}
+
+impl Stroke {
+    pub fn new(color: Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            dash: Vec::new(),
+        }
+    }
+
+    /// Use a dash pattern of alternating dash and gap lengths.
+    pub fn dash(mut self, dash: &[f32]) -> Self {
+        self.dash = dash.to_vec();
+        self
+    }
+}
+
+/// A [`Renderer`](crate::core::Renderer) that can draw paths.
+pub trait Renderer: crate::core::renderer::Renderer {
+    /// Fills the inside of the path (nonzero winding).
+    ///
+    /// Gradients are positioned relative to the bounding box of the path.
+    fn fill_path(&mut self, path: &Path, background: impl Into<Background>);
+
+    /// Draws the outline of the path.
+    fn stroke_path(&mut self, path: &Path, stroke: Stroke);
+
+    /// Starts clipping everything drawn to the inside of the path, until
+    /// [`end_clip`](Self::end_clip).
+    ///
+    /// Clips nest with each other and with [layers](crate::core::Renderer::start_layer).
+    fn start_clip(&mut self, path: &Path);
+
+    /// Ends the last clip started with [`start_clip`](Self::start_clip).
+    fn end_clip(&mut self);
+
+    /// Draws the primitives recorded in the given closure clipped to the inside of the path.
+    fn with_clip(&mut self, path: &Path, f: impl FnOnce(&mut Self)) {
+        self.start_clip(path);
+        f(self);
+        self.end_clip();
+    }
+}
@@ -43,6 +43,26 @@ pub trait Renderer {
         self.with_transformation(Transformation::translate(translation.x, translation.y), f);
     }
 
+    /// Starts recording a group of primitives that is composited as a whole.
+    ///
+    /// By default, it does nothing and the group is drawn opaque.
+    fn start_opacity(&mut self, _opacity: f32) {}
+
+    /// Ends recording a group of primitives.
+    ///
+    /// The group is blended onto what is below with the opacity it was started with.
+    /// By default, it does nothing.
+    fn end_opacity(&mut self) {}
+
+    /// Draws the primitives recorded in the given closure with a group opacity.
+    ///
+    /// Overlapping primitives inside the group do not show through each other.
+    fn with_opacity(&mut self, opacity: f32, f: impl FnOnce(&mut Self)) {
+        self.start_opacity(opacity);
+        f(self);
+        self.end_opacity();
+    }
+
     /// Fills a [`Quad`] with the provided [`Background`].
     fn fill_quad(&mut self, quad: Quad, background: impl Into<Background>);
 
@@ -0,0 +1,335 @@
+//! Draw text through a renderer.
+
+use std::ops::Range;
+
+use crate::core::background::Color;
+use crate::core::font::{Font, Weight};
+use crate::layout::{LayoutDirection, Rectangle};
+
+/// A single paragraph of text to be drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Text {
+    /// The content of the paragraph.
+    pub content: String,
+
+    /// Styled runs of the content. Text outside every span uses `size` and `color`.
+    pub spans: Vec<Span>,
+
+    /// The bounds the paragraph is laid out in. Text starts at the top-left corner.
+    pub bounds: Rectangle,
+
+    /// The font of the paragraph. Spans may change its weight and style.
+    pub font: Font,
+
+    /// The font size in logical pixels.
+    pub size: f32,
+
+    /// The color of the glyphs.
+    pub color: Color,
+
+    /// The maximum number of lines; text beyond it is truncated.
+    pub line_limit: Option<usize>,
+
+    /// Where text is cut when it exceeds `line_limit`.
+    pub truncation: Truncation,
+
+    /// How lines are aligned to each other.
+    pub alignment: Alignment,
+
+    /// The side leading and trailing alignment refer to.
+    ///
+    /// It doesn't set the base direction of the bidirectional text: each line is still
+    /// shaped in the direction of its first strong character, so a right-to-left
+    /// paragraph that starts with a Latin word reads left to right, aligned right.
+    pub direction: LayoutDirection,
+
+    /// Line height, letter spacing and OpenType features.
+    pub typography: Typography,
+}
+
+impl Text {
+    /// Creates a paragraph showing `content` in `bounds` with the default style.
+    pub fn new(content: impl Into<String>, bounds: Rectangle) -> Self {
+        Self {
+            content: content.into(),
+            bounds,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Text {
+    /// Empty black text at [`DEFAULT_FONT_SIZE`](crate::render::DEFAULT_FONT_SIZE) in the
+    /// default font, laid out left to right.
+    fn default() -> Self {
+        Self {
+            content: String::new(),
+            spans: Vec::new(),
+            bounds: Rectangle::new(0.0, 0.0, 0.0, 0.0),
+            font: Font::default(),
+            size: crate::render::DEFAULT_FONT_SIZE,
+            color: Color::BLACK,
+            line_limit: None,
+            truncation: Truncation::default(),
+            alignment: Alignment::default(),
+            direction: LayoutDirection::default(),
+            typography: Typography::default(),
+        }
+    }
+}
+
+/// Where text that doesn't fit its line limit is cut and replaced by an ellipsis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Truncation {
+    /// Keep the end: "…of the text".
+    Head,
+    /// Keep both ends: "The st…text".
+    Middle,
+    /// Keep the start: "The start of…".
+    #[default]
+    Tail,
+}
+
+/// The horizontal alignment of the lines of a paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Alignment {
+    #[default]
+    Leading,
+    Center,
+    Trailing,
+    /// Stretch the spaces of every line but the last to fill the width.
+    Justified,
+}
+
+/// The distance between the baselines of consecutive lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineHeight {
+    /// A multiple of the font size.
+    Relative(f32),
+    /// Logical pixels, whatever the font size.
+    Absolute(f32),
+}
+
+impl LineHeight {
+    /// Returns the line height in logical pixels for text of the given size.
+    pub fn resolve(self, size: f32) -> f32 {
+        match self {
+            LineHeight::Relative(factor) => size * factor,
+            LineHeight::Absolute(height) => height,
+        }
+    }
+}
+
+impl Default for LineHeight {
+    fn default() -> Self {
+        Self::Relative(1.2)
+    }
+}
+
+/// An OpenType feature setting, such as `kern` off or `tnum` on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Feature {
+    pub tag: [u8; 4],
+    /// 0 turns the feature off, 1 turns it on; some features take other values, e.g.
+    /// the alternate to use.
+    pub value: u32,
+}
+
+impl Feature {
+    /// Pair kerning.
+    pub const KERNING: [u8; 4] = *b"kern";
+    /// Standard ligatures such as fi and fl.
+    pub const STANDARD_LIGATURES: [u8; 4] = *b"liga";
+    /// Ligatures that depend on the surrounding characters.
+    pub const CONTEXTUAL_LIGATURES: [u8; 4] = *b"clig";
+    /// Optional, decorative ligatures.
+    pub const DISCRETIONARY_LIGATURES: [u8; 4] = *b"dlig";
+    /// Digits of equal width, so columns of numbers line up.
+    pub const TABULAR_NUMBERS: [u8; 4] = *b"tnum";
+    /// Lowercase letters as small capitals.
+    pub const SMALL_CAPS: [u8; 4] = *b"smcp";
+
+    pub const fn new(tag: &[u8; 4], value: u32) -> Self {
+        Self { tag: *tag, value }
+    }
+
+    pub const fn enable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 1)
+    }
+
+    pub const fn disable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 0)
+    }
+}
+
+/// Typographic settings of a paragraph, used both to measure and to draw it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Typography {
+    pub line_height: LineHeight,
+    /// Extra space after every character, in logical pixels. Negative values tighten.
+    pub tracking: f32,
+    /// OpenType features, applied on top of the font's defaults in order.
+    pub features: Vec<Feature>,
+}
+
+impl Typography {
+    /// Sets a feature, replacing an earlier setting of the same tag.
+    pub fn set_feature(&mut self, feature: Feature) {
+        self.features.retain(|existing| existing.tag != feature.tag);
+        self.features.push(feature);
+    }
+}
+
+/// Styling of a run of text. Unset fields inherit from the paragraph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attributes {
+    pub weight: Option<Weight>,
+    pub italic: bool,
+    pub color: Option<Color>,
+    /// Font size in logical pixels.
+    pub size: Option<f32>,
+    pub underline: bool,
+    pub strikethrough: bool,
+    /// Color highlighting the line box behind the run.
+    pub background: Option<Color>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(Weight::BOLD)
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+}
+
+/// A styled byte range of a paragraph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub range: Range<usize>,
+    pub attributes: Attributes,
+}
+
+/// A string made of styled runs, shaped and wrapped as one paragraph, e.g.
+/// `AttributedString::new().plain("Status: ").styled("FAILED", Attributes::new().bold())`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttributedString {
+    text: String,
+    spans: Vec<Span>,
+}
+
+impl AttributedString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends text with the paragraph's own style.
+    pub fn plain(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    /// Appends text with the given attributes.
+    pub fn styled(mut self, text: &str, attributes: Attributes) -> Self {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.spans.push(Span {
+            range: start..self.text.len(),
+            attributes,
+        });
+        self
+    }
+
+    /// Returns the whole text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the styled runs, in order.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Splits into the text and its styled runs.
+    pub fn into_parts(self) -> (String, Vec<Span>) {
+        (self.text, self.spans)
+    }
+}
+
+impl From<&str> for AttributedString {
+    fn from(text: &str) -> Self {
+        Self::new().plain(text)
+    }
+}
+
+impl From<String> for AttributedString {
+    fn from(text: String) -> Self {
+        Self { text, spans: Vec::new() }
+    }
+}
+
+/// A [`Renderer`](crate::core::Renderer) that can draw text.
+pub trait Renderer: crate::core::renderer::Renderer {
+    /// Draws the given [`Text`].
+    fn fill_text(&mut self, text: Text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributed_string_records_the_range_of_each_span() {
+        let rich = AttributedString::new()
+            .plain("Status: ")
+            .styled("FAILED", Attributes::new().bold())
+            .plain(" now");
+        assert_eq!(rich.text(), "Status: FAILED now");
+        assert_eq!(rich.spans().len(), 1);
+        assert_eq!(rich.spans()[0].range, 8..14);
+    }
+
+    #[test]
+    fn test_a_feature_is_set_once() {
+        let mut typography = Typography::default();
+        typography.set_feature(Feature::disable(&Feature::STANDARD_LIGATURES));
+        typography.set_feature(Feature::enable(&Feature::STANDARD_LIGATURES));
+        assert_eq!(typography.features, vec![Feature::enable(&Feature::STANDARD_LIGATURES)]);
+    }
+}
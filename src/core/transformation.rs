@@ -1,5 +1,8 @@
 //! 2D transformation matrix.
 
+use crate::core::point::Point;
+use crate::layout::Rectangle;
+
 /// A 2D transformation.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Transformation {
@@ -66,6 +69,85 @@ impl Transformation {
     pub fn scale_factor(&self) -> f32 {
         (self.a * self.a + self.b * self.b).sqrt()
     }
+
+    /// Translation component of this transformation.
+    pub fn translation(&self) -> (f32, f32) {
+        (self.e, self.f)
+    }
+
+    /// Returns whether this transformation only translates and scales
+    /// (i.e. keeps rectangles axis-aligned).
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0
+    }
+
+    /// Applies `self` first, then `other`.
+    pub fn then(self, other: Transformation) -> Self {
+        Self {
+            a: self.a * other.a + self.b * other.c,
+            b: self.a * other.b + self.b * other.d,
+            c: self.c * other.a + self.d * other.c,
+            d: self.c * other.b + self.d * other.d,
+            e: self.e * other.a + self.f * other.c + other.e,
+            f: self.e * other.b + self.f * other.d + other.f,
+        }
+    }
+
+    /// Returns the transformation that undoes this one, or `None` if it collapses
+    /// the plane (e.g. a zero scale).
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.a * self.d - self.b * self.c;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.d / determinant,
+            b: -self.b / determinant,
+            c: -self.c / determinant,
+            d: self.a / determinant,
+            e: (self.c * self.f - self.d * self.e) / determinant,
+            f: (self.b * self.e - self.a * self.f) / determinant,
+        })
+    }
+
+    /// Maps a point through this transformation.
+    pub fn transform_point(&self, point: Point) -> Point {
+        Point::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+
+    /// Maps a rectangle through this transformation and returns the
+    /// axis-aligned bounding box of the result.
+    pub fn transform_rectangle(&self, rect: Rectangle) -> Rectangle {
+        let corners = [
+            self.transform_point(Point::new(rect.x, rect.y)),
+            self.transform_point(Point::new(rect.x + rect.width, rect.y)),
+            self.transform_point(Point::new(rect.x, rect.y + rect.height)),
+            self.transform_point(Point::new(rect.x + rect.width, rect.y + rect.height)),
+        ];
+
+        let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+        let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for corner in corners {
+            min_x = min_x.min(corner.x);
+            min_y = min_y.min(corner.y);
+            max_x = max_x.max(corner.x);
+            max_y = max_y.max(corner.y);
+        }
+
+        Rectangle::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+}
+
+impl std::ops::Mul for Transformation {
+    type Output = Transformation;
+
+    /// Composes two transformations; `a * b` applies `b` first, then `a`.
+    fn mul(self, other: Transformation) -> Self::Output {
+        other.then(self)
+    }
 }
 
 impl Default for Transformation {
@@ -78,6 +78,7 @@ where
     /// Processes a runtime [`Event`].
     ///
     /// By default, it does nothing.
+    #[allow(clippy::too_many_arguments)]
     fn update(
         &mut self,
         tree: &mut Tree,
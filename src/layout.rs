@@ -1,5 +1,7 @@
 //! Layout: Limits, Node, and layout pass for View tree.
 
+use crate::core::font::Font;
+use crate::core::text::{LineHeight, Span, Truncation, Typography};
 use crate::view::{Alignment, View};
 
 /// 2D size in logical pixels or length units.
@@ -44,6 +46,49 @@ impl Rectangle {
     pub fn size(&self) -> Size<f32> {
         Size::new(self.width, self.height)
     }
+
+    /// Returns whether the point lies inside the rectangle.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Returns the overlapping area of two rectangles, if any.
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        if right > x && bottom > y {
+            Some(Rectangle::new(x, y, right - x, bottom - y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Rectangle::new(x, y, right - x, bottom - y)
+    }
+
+    /// Returns the rectangle grown by `amount` on every side.
+    pub fn expand(&self, amount: f32) -> Rectangle {
+        Rectangle::new(
+            self.x - amount,
+            self.y - amount,
+            self.width + amount * 2.0,
+            self.height + amount * 2.0,
+        )
+    }
+
+    /// Returns the area of the rectangle.
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
 }
 
 /// Min/max size constraints for layout.
@@ -88,12 +133,27 @@ impl Limits {
     }
 }
 
+/// The direction content flows in horizontally: where stacks put their first child
+/// and which side leading and trailing alignment refer to.
+///
+/// An environment value: views are laid out in the direction of their parent, unless
+/// a stack sets another for itself and its descendants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LayoutDirection {
+    #[default]
+    LeftToRight,
+    /// For right-to-left scripts such as Arabic and Hebrew.
+    RightToLeft,
+}
+
 /// Result of layout: size and position of this node and its children.
 #[derive(Debug, Clone)]
 pub struct Node {
     pub bounds: Rectangle,
     pub children: Vec<Node>,
     pub view_id: Option<usize>, // For tracking clickable elements
+    /// The direction the view was laid out in.
+    pub direction: LayoutDirection,
 }
 
 impl Node {
@@ -102,6 +162,7 @@ impl Node {
             bounds,
             children: Vec::new(),
             view_id: None,
+            direction: LayoutDirection::LeftToRight,
         }
     }
     
@@ -110,6 +171,7 @@ impl Node {
             bounds,
             children: Vec::new(),
             view_id: Some(view_id),
+            direction: LayoutDirection::LeftToRight,
         }
     }
     
@@ -118,6 +180,7 @@ impl Node {
             bounds, 
             children, 
             view_id: None,
+            direction: LayoutDirection::LeftToRight,
         }
     }
     
@@ -126,6 +189,7 @@ impl Node {
             bounds, 
             children, 
             view_id: Some(view_id),
+            direction: LayoutDirection::LeftToRight,
         }
     }
     
@@ -137,8 +201,64 @@ impl Node {
 /// Measures text for layout (intrinsic size). Implemented by renderer; placeholder for layout-only.
 pub trait TextMeasurer: Send + Sync {
     fn measure(&self, text: &str, font_size: f32) -> Size;
+
+    /// Measures a paragraph with its font, styled runs and line limit. Defaults to
+    /// measuring it as plain text on one line.
+    fn measure_paragraph(&self, paragraph: &Paragraph<'_>) -> Size {
+        self.measure(paragraph.content, paragraph.size)
+    }
+
+    /// Returns the size of an image in pixels, or `None` if it cannot be decoded.
+    /// Defaults to decoding it on every call.
+    fn measure_image(&self, handle: &crate::core::image::Handle) -> Option<Size> {
+        crate::render::image_size(handle)
+    }
 }
 
+static DEFAULT_TYPOGRAPHY: Typography = Typography {
+    line_height: LineHeight::Relative(1.2),
+    tracking: 0.0,
+    features: Vec::new(),
+};
+
+/// A paragraph of text to measure.
+#[derive(Debug, Clone, Copy)]
+pub struct Paragraph<'a> {
+    pub content: &'a str,
+    /// Styled runs of `content`, which may change weight, style or size.
+    pub spans: &'a [Span],
+    pub font: &'a Font,
+    pub size: f32,
+    /// The width lines wrap at; infinite to only break at newlines.
+    pub max_width: f32,
+    /// The maximum number of lines; text beyond it is truncated.
+    pub line_limit: Option<usize>,
+    pub truncation: Truncation,
+    pub typography: &'a Typography,
+}
+
+impl<'a> Paragraph<'a> {
+    /// A plain paragraph on as few lines as possible.
+    pub fn new(content: &'a str, font: &'a Font, size: f32) -> Self {
+        Self {
+            content,
+            spans: &[],
+            font,
+            size,
+            max_width: f32::INFINITY,
+            line_limit: None,
+            truncation: Truncation::default(),
+            typography: &DEFAULT_TYPOGRAPHY,
+        }
+    }
+}
+
+/// Width of a text field or editor when neither it nor its parent decides one.
+const DEFAULT_FIELD_WIDTH: f32 = 200.0;
+
+/// Height of a text editor when neither it nor its parent decides one.
+const DEFAULT_EDITOR_HEIGHT: f32 = 120.0;
+
 /// Placeholder measurer (fixed size per char) when no font is loaded yet.
 #[derive(Debug, Default)]
 pub struct PlaceholderMeasurer;
@@ -152,26 +272,91 @@ impl TextMeasurer for PlaceholderMeasurer {
 
 /// Runs layout on the view tree; returns root node with bounds and children.
 pub fn layout(view: &View, limits: Limits, measurer: &dyn TextMeasurer) -> Node {
-    match view {
+    layout_with_direction(view, limits, measurer, LayoutDirection::LeftToRight)
+}
+
+/// Runs layout on the view tree with `direction` as the layout direction of the root.
+pub fn layout_with_direction(view: &View, limits: Limits, measurer: &dyn TextMeasurer, direction: LayoutDirection) -> Node {
+    let direction = match view {
+        View::VStack(v) => v.layout_direction.unwrap_or(direction),
+        View::HStack(h) => h.layout_direction.unwrap_or(direction),
+        _ => direction,
+    };
+    let mut node = match view {
         View::Text(t) => {
             // Use DEFAULT_FONT_SIZE from render module if not specified
             use crate::render::DEFAULT_FONT_SIZE;
             let font_size = t.size.unwrap_or(DEFAULT_FONT_SIZE);
-            let size = measurer.measure(&t.string, font_size);
+            // Wrap to the proposed width
+            let size = measurer.measure_paragraph(&Paragraph {
+                content: &t.string,
+                spans: &t.spans,
+                font: &t.font,
+                size: font_size,
+                max_width: limits.max_width,
+                line_limit: t.line_limit,
+                truncation: t.truncation,
+                typography: &t.typography,
+            });
             let w = size.width.min(limits.max_width).max(limits.min_width);
             let h = size.height.min(limits.max_height).max(limits.min_height);
             Node::new(Rectangle::new(0.0, 0.0, w, h))
         }
+        View::Image(i) => {
+            let intrinsic = measurer.measure_image(&i.handle).unwrap_or(Size::ZERO);
+            let (w, h) = if let Some(frame) = i.frame {
+                frame
+            } else if !i.resizable {
+                (intrinsic.width, intrinsic.height)
+            } else {
+                // Resizable images take the proposed size; unbounded axes fall back to the pixel size.
+                let proposed_w = if limits.max_width.is_finite() { limits.max_width } else { intrinsic.width };
+                let proposed_h = if limits.max_height.is_finite() { limits.max_height } else { intrinsic.height };
+                match (i.content_mode, i.ratio(intrinsic)) {
+                    (Some(crate::view::ContentMode::Fit), Some(ratio)) => {
+                        let w = proposed_w.min(proposed_h * ratio);
+                        (w, w / ratio)
+                    }
+                    _ => (proposed_w, proposed_h),
+                }
+            };
+            let w = w.min(limits.max_width).max(limits.min_width);
+            let h = h.min(limits.max_height).max(limits.min_height);
+            Node::new(Rectangle::new(0.0, 0.0, w, h))
+        }
+        View::Shape(s) => flexible(s.frame, limits),
+        View::Canvas(c) => flexible(c.frame, limits),
         View::Button(b) => {
             // Button layout: measure text + padding
             use crate::render::DEFAULT_FONT_SIZE;
             let font_size = b.text_size.unwrap_or(DEFAULT_FONT_SIZE);
-            let text_size = measurer.measure(&b.label, font_size);
+            let text_size = measurer.measure_paragraph(&Paragraph::new(&b.label, &b.font, font_size));
             let padding = b.padding;
             let w = (text_size.width + padding * 2.0).min(limits.max_width).max(limits.min_width);
             let h = (text_size.height + padding * 2.0).min(limits.max_height).max(limits.min_height);
             Node::new(Rectangle::new(0.0, 0.0, w, h))
         }
+        View::TextField(f) => {
+            // One line of text plus padding; as wide as proposed unless given a width
+            use crate::render::DEFAULT_FONT_SIZE;
+            let font_size = f.size.unwrap_or(DEFAULT_FONT_SIZE);
+            let line = measurer.measure_paragraph(&Paragraph::new(" ", &f.font, font_size));
+            let proposed = if limits.max_width.is_finite() { limits.max_width } else { DEFAULT_FIELD_WIDTH };
+            let w = f.width.unwrap_or(proposed).min(limits.max_width).max(limits.min_width);
+            let h = (line.height + f.padding * 2.0).min(limits.max_height).max(limits.min_height);
+            Node::new(Rectangle::new(0.0, 0.0, w, h))
+        }
+        View::TextEditor(e) => {
+            // Takes the proposed size; unbounded axes fall back to a few lines of text
+            let proposed = |max: f32, default: f32| if max.is_finite() { max } else { default };
+            let (w, h) = e.frame.unwrap_or((
+                proposed(limits.max_width, DEFAULT_FIELD_WIDTH),
+                proposed(limits.max_height, DEFAULT_EDITOR_HEIGHT),
+            ));
+            let w = w.min(limits.max_width).max(limits.min_width);
+            let h = h.min(limits.max_height).max(limits.min_height);
+            Node::new(Rectangle::new(0.0, 0.0, w, h))
+        }
         View::VStack(v) => {
             let padding = v.padding;
             if padding > 0.0 {
@@ -191,6 +376,7 @@ pub fn layout(view: &View, limits: Limits, measurer: &dyn TextMeasurer) -> Node
                     true, // vertical
                     content_limits,
                     measurer,
+                    direction,
                 );
                 
                 // Move content to account for padding and expand bounds
@@ -217,6 +403,7 @@ pub fn layout(view: &View, limits: Limits, measurer: &dyn TextMeasurer) -> Node
                     true, // vertical
                     limits,
                     measurer,
+                    direction,
                 )
             }
         },
@@ -239,6 +426,7 @@ pub fn layout(view: &View, limits: Limits, measurer: &dyn TextMeasurer) -> Node
                     false, // horizontal
                     content_limits,
                     measurer,
+                    direction,
                 );
                 
                 // Move content to account for padding and expand bounds
@@ -265,12 +453,27 @@ pub fn layout(view: &View, limits: Limits, measurer: &dyn TextMeasurer) -> Node
                     false, // horizontal
                     limits,
                     measurer,
+                    direction,
                 )
             }
         },
-    }
+    };
+    node.direction = direction;
+    node
 }
 
+/// Layout for views that take the proposed size; unbounded axes collapse to the minimum.
+fn flexible(frame: Option<(f32, f32)>, limits: Limits) -> Node {
+    let (w, h) = frame.unwrap_or((
+        if limits.max_width.is_finite() { limits.max_width } else { limits.min_width },
+        if limits.max_height.is_finite() { limits.max_height } else { limits.min_height },
+    ));
+    let w = w.min(limits.max_width).max(limits.min_width);
+    let h = h.min(limits.max_height).max(limits.min_height);
+    Node::new(Rectangle::new(0.0, 0.0, w, h))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn layout_stack(
     view: &View,
     spacing: f32,
@@ -279,6 +482,7 @@ fn layout_stack(
     vertical: bool,
     limits: Limits,
     measurer: &dyn TextMeasurer,
+    direction: LayoutDirection,
 ) -> Node {
     let (children_views, _) = match view {
         View::VStack(v) => (&v.children, ()),
@@ -296,7 +500,7 @@ fn layout_stack(
     let mut cross_max = 0.0f32;
 
     for child in children_views {
-        let node = layout(child, limits, measurer);
+        let node = layout_with_direction(child, limits, measurer, direction);
         let s = node.size();
         if vertical {
             main_sum += s.height;
@@ -327,7 +531,6 @@ fn layout_stack(
     let extra_space = available_main_space - content_main_size;
 
     let align = alignment.factor();
-    let mut main_cursor = 0.0f32;
     
     // Calculate justify positioning
     let (start_offset, item_spacing_extra) = match justify {
@@ -359,7 +562,7 @@ fn layout_stack(
         },
     };
 
-    main_cursor = start_offset;
+    let mut main_cursor = start_offset;
 
     let positioned: Vec<Node> = child_nodes
         .into_iter()
@@ -385,6 +588,11 @@ fn layout_stack(
                 }
                 (x, cross_offset)
             };
+            // Right to left, the stack is mirrored: the first child and leading edge are on the right
+            let x = match direction {
+                LayoutDirection::LeftToRight => x,
+                LayoutDirection::RightToLeft => total_width - x - mw,
+            };
             node.bounds = Rectangle::new(x, y, mw, mh);
             node
         })
@@ -395,3 +603,89 @@ fn layout_stack(
         positioned,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Renderer;
+    use crate::view::{HStack, Shape, Text as TextView, VStack};
+
+    const FOX: &str = "The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn test_text_wraps_to_the_proposed_width() {
+        let wrapped = layout(&View::text(FOX), Limits::loose(120.0, 400.0), &Renderer::with_bundled_fonts());
+        assert!(wrapped.bounds.width <= 120.0 && wrapped.bounds.height > 40.0, "{:?}", wrapped.bounds);
+    }
+
+    #[test]
+    fn test_line_limit_caps_the_height_of_wrapped_text() {
+        let view = View::from(TextView::new(FOX).line_limit(1));
+        let limited = layout(&view, Limits::loose(120.0, 400.0), &Renderer::with_bundled_fonts());
+        assert!(limited.bounds.height < 25.0, "{:?}", limited.bounds);
+    }
+
+    /// Lays out `text` at 20px and returns its bounds.
+    fn measure(text: TextView) -> Rectangle {
+        layout(&View::from(text.size(20.0)), Limits::loose(400.0, 400.0), &Renderer::with_bundled_fonts()).bounds
+    }
+
+    #[test]
+    fn test_absolute_line_height_sets_the_height_of_each_line() {
+        assert_eq!(measure(TextView::new("a\nb").line_height(LineHeight::Absolute(40.0))).height, 80.0);
+    }
+
+    #[test]
+    fn test_relative_line_height_scales_the_font_size() {
+        assert_eq!(measure(TextView::new("a\nb").line_height(LineHeight::Relative(1.5))).height, 60.0);
+    }
+
+    #[test]
+    fn test_tracking_is_added_after_each_character() {
+        let (plain, tracked) = (measure(TextView::new("abcd")), measure(TextView::new("abcd").tracking(5.0)));
+        assert!((tracked.width - plain.width - 20.0).abs() < 0.5, "{} vs {}", tracked.width, plain.width);
+    }
+
+    #[test]
+    fn test_kerning_can_be_turned_off() {
+        let (kerned, unkerned) = (measure(TextView::new("AVAV")), measure(TextView::new("AVAV").kerning(false)));
+        assert!(unkerned.width > kerned.width + 1.0, "{} vs {}", unkerned.width, kerned.width);
+    }
+
+    const RTL: LayoutDirection = LayoutDirection::RightToLeft;
+
+    fn limits() -> Limits {
+        Limits::loose(200.0, 100.0)
+    }
+
+    /// A 20px and a 30px wide rectangle, 10px apart.
+    fn row() -> HStack {
+        HStack::new(vec![Shape::rectangle().frame(20.0, 10.0), Shape::rectangle().frame(30.0, 10.0)]).spacing(10.0)
+    }
+
+    fn xs(node: &Node) -> Vec<f32> {
+        node.children.iter().map(|child| child.bounds.x).collect()
+    }
+
+    #[test]
+    fn test_right_to_left_hstack_starts_on_the_right() {
+        let renderer = Renderer::with_bundled_fonts();
+        assert_eq!(xs(&layout(&View::from(row()), limits(), &renderer)), vec![0.0, 30.0]);
+        assert_eq!(xs(&layout_with_direction(&View::from(row()), limits(), &renderer, RTL)), vec![40.0, 0.0]);
+    }
+
+    #[test]
+    fn test_leading_alignment_is_the_right_edge_right_to_left() {
+        let column = VStack::new(vec![Shape::rectangle().frame(20.0, 10.0), Shape::rectangle().frame(100.0, 10.0)]).alignment(Alignment::Start);
+        let node = layout_with_direction(&View::from(column), limits(), &Renderer::with_bundled_fonts(), RTL);
+        assert_eq!(xs(&node), vec![80.0, 0.0]);
+    }
+
+    #[test]
+    fn test_stacks_set_the_direction_of_their_subtree() {
+        let view = View::from(VStack::new(vec![row().layout_direction(RTL)]));
+        let nested = layout(&view, limits(), &Renderer::with_bundled_fonts());
+        assert_eq!((nested.direction, nested.children[0].direction), (LayoutDirection::LeftToRight, RTL));
+        assert_eq!(xs(&nested.children[0]), vec![40.0, 0.0]);
+    }
+}
@@ -1,34 +1,44 @@
 //! shellui: SwiftUI-like GUI framework prototype.
 //!
-//! Window, text rendering, and stacks (VStack, HStack) only.
+//! Window, text, text fields and editors, images, shapes, canvases, and stacks (VStack, HStack).
 
 pub mod app;
+pub mod clipboard;
 pub mod core;
 pub mod layout;
 pub mod render;
 pub mod runtime;
+pub mod snapshot;
 pub mod view;
 pub mod widget;
 pub mod window;
 
 // Core exports
 pub use core::{
-    Background, Border, Color, Cursor, Element, Event, Interaction, Layout, Length, Limits, Node,
-    Point, RedrawRequest, Renderer as RendererTrait, Shell, Shadow, Size as CoreSize, Status, Tag,
+    AngularGradient, Background, Border, Color, ColorStop, Cursor, Element, Event, Gradient,
+    Interaction, Layout, Length, Limits, LinearGradient, Node, Point, RadialGradient,
+    RedrawRequest, Renderer as RendererTrait, Shell, Shadow, Size as CoreSize, Status, Tag,
     Tree, Transformation, Vector, WindowEvent,
 };
 
 // Layout exports
-pub use layout::{layout, PlaceholderMeasurer, Rectangle, Size, TextMeasurer};
+pub use layout::{layout, layout_with_direction, LayoutDirection, Paragraph, PlaceholderMeasurer, Rectangle, Size, TextMeasurer};
 
 // Render exports
-pub use render::Renderer;
+pub use render::{render_to_image, GlyphCacheStats, GradientCacheStats, PixelBuffer, PixelFormat, Renderer, RgbaImage, TextAntialiasing, TextCacheStats};
 
 // Runtime exports
 pub use runtime::{Cache, UserInterface};
 
 // View exports (main API)
-pub use view::{Alignment, Justify, Button, HStack, Text, VStack, View};
+pub use view::{Alignment, Justify, Binding, Button, Canvas, ContentMode, Frame, GeometryEffects, HStack, Image, Shape, ShapeKind, Text, TextEditor, TextField, UnitPoint, VStack, View};
+pub use core::image::{Handle as ImageHandle, Interpolation};
+pub use core::path::{Path, Stroke};
+pub use core::font::{Family as FontFamily, Font, Source as FontSource, Stretch as FontStretch, Style as FontStyle, Weight as FontWeight};
+pub use core::text::{
+    Alignment as TextAlignment, AttributedString, Attributes as TextAttributes, Feature as FontFeature, LineHeight,
+    Span as TextSpan, Truncation, Typography,
+};
 
 // Widget exports (alternative widget-based API)
 pub use widget::{HStack as HStackWidget, VStack as VStackWidget};
@@ -36,5 +46,10 @@ pub use widget::{HStack as HStackWidget, VStack as VStackWidget};
 // App exports
 pub use app::{App, ContentPosition, ContentSizing, IntoScene, Scene, WindowConfiguration, WindowGroup, window_group};
 
+// Clipboard exports
+pub use clipboard::{Clipboard, MemoryClipboard};
+#[cfg(feature = "system-clipboard")]
+pub use clipboard::SystemClipboard;
+
 // Window exports
 pub use window::{run, run_scene};
@@ -1,10 +1,11 @@
 //! Example: SwiftUI-style App with justify alignment and content sizing.
 
-use shellui::{App, Alignment, Color, ContentSizing, HStack, Justify, Text, VStack, View, window_group};
+use shellui::{App, Alignment, Color, HStack, Text, VStack, View, window_group};
 
 struct ContentView;
 
 impl ContentView {
+    #[allow(clippy::new_ret_no_self)]
     fn new() -> View {
         VStack::new::<Vec<View>>(vec![
             Text::new("ShellUI")
@@ -52,7 +53,7 @@ struct MyApp;
 
 impl App for MyApp {
     fn body(&self) -> impl shellui::IntoScene {
-        window_group(|| ContentView::new())
+        window_group(ContentView::new)
             .title("ShellUI - Justify & Leading Position Demo")
             .size(600.0, 500.0)
             .min_size(400.0, 300.0)
@@ -1,17 +1,56 @@
 //! Render view tree + layout to a pixel buffer (text and background).
 
-use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping, SwashCache};
+use cosmic_text::{FontSystem, LineIter};
+use std::ops::Range;
 use std::path::Path;
-use std::sync::{RwLock, OnceLock};
-use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, RwLock, OnceLock};
+use std::collections::{HashSet, VecDeque};
 use std::borrow::Cow;
 
-use crate::layout::{Node, Rectangle, Size, TextMeasurer};
-use crate::view::View;
+use crate::layout::{LayoutDirection, Node, Paragraph, Rectangle, Size, TextMeasurer};
+use crate::view::{self, Editing, Frame, ShapeKind, View};
 use crate::core::background::{Background, Color};
+use crate::core::border::Border;
+use crate::core::image::{Handle, Interpolation, Renderer as ImageRenderer};
+use crate::core::path::{Renderer as PathRenderer, Stroke};
+use crate::core::point::Point;
+use crate::core::shadow::Shadow;
+use crate::core::vector::Vector;
 use crate::core::renderer::{Quad, Renderer as RendererTrait};
+use crate::core::font::{self, Font};
+use crate::core::text::{
+    Alignment, AttributedString, Attributes as TextAttributes, LineHeight, Renderer as TextRenderer, Span, Text,
+};
 use crate::core::transformation::Transformation;
 
+pub mod display_list;
+mod atlas;
+mod background;
+mod composite;
+mod damage;
+mod image;
+mod offscreen;
+mod path;
+mod quad;
+mod target;
+mod text_cache;
+
+use atlas::{GlyphAtlas, Kind as GlyphKind};
+use background::GradientCache;
+pub use background::GradientCacheStats;
+use image::ImageCache;
+use composite::Premultiplied;
+use text_cache::{Key as TextKey, TextCache};
+pub(crate) use path::contains as path_contains;
+pub(crate) use text_cache::attrs;
+
+pub use atlas::{GlyphCacheStats, TextAntialiasing, DEFAULT_GLYPH_CACHE_BUDGET};
+pub use display_list::{ClipPath, DisplayList, Item, Primitive};
+pub use image::{decode as decode_image, image_size, DEFAULT_IMAGE_CACHE_BUDGET};
+pub use offscreen::{render_to_image, RgbaImage};
+pub use target::{PixelBuffer, PixelFormat};
+pub use text_cache::TextCacheStats;
+
 /// Default font bundled in the crate (Roboto Regular).
 pub static DEFAULT_FONT: &[u8] =
     include_bytes!("../assets/fonts/Roboto-Regular.ttf");
@@ -19,6 +58,25 @@ pub static DEFAULT_FONT: &[u8] =
 /// Default text size in logical pixels.
 pub const DEFAULT_FONT_SIZE: f32 = 16.0;
 
+/// Default window background (0x00RRGGBB, light gray).
+pub const DEFAULT_BACKGROUND: u32 = 0x00_EE_EE_EE;
+
+/// Color behind selected text (light blue).
+pub const SELECTION_COLOR: Color = Color::new(0.71, 0.84, 1.0, 1.0);
+
+/// Border color of the focused text field (the default button blue).
+pub const FOCUS_COLOR: Color = Color::new(0.2, 0.5, 1.0, 1.0);
+
+/// Color of the hint in an empty text field.
+const PLACEHOLDER_COLOR: Color = Color::new(0.55, 0.55, 0.55, 1.0);
+
+/// Width of the text cursor in logical pixels.
+pub(crate) const CARET_WIDTH: f32 = 1.5;
+
+/// Oldest buffer, in presented frames, that is updated region by region instead of
+/// written whole. Swap chains rarely hold more than three buffers.
+const MAX_BUFFER_AGE: usize = 4;
+
 /// Returns the global [`FontSystem`].
 fn font_system() -> &'static RwLock<FontSystem> {
     static FONT_SYSTEM: OnceLock<RwLock<FontSystem>> = OnceLock::new();
@@ -28,23 +86,96 @@ fn font_system() -> &'static RwLock<FontSystem> {
     })
 }
 
+/// Returns the global [`FontSystem`] that only knows the bundled font and fonts
+/// loaded explicitly, so text renders the same on every machine.
+fn bundled_font_system() -> &'static RwLock<FontSystem> {
+    static FONT_SYSTEM: OnceLock<RwLock<FontSystem>> = OnceLock::new();
+
+    FONT_SYSTEM.get_or_init(|| {
+        let mut db = cosmic_text::fontdb::Database::new();
+        db.load_font_data(DEFAULT_FONT.to_vec());
+        db.set_sans_serif_family("Roboto");
+        db.set_serif_family("Roboto");
+        db.set_monospace_family("Roboto");
+        RwLock::new(FontSystem::new_with_locale_and_db("en-US".to_string(), db))
+    })
+}
+
 /// Renders the view tree using the layout tree. Handles font loading, text measurement, and drawing.
+///
+/// Drawing happens in two steps: primitives are first recorded into a [`DisplayList`]
+/// (through [`Renderer::draw`] or the `core::Renderer` trait), then rasterized into a
+/// pixel buffer by [`Renderer::present`]. Rasterization goes through an anti-aliased
+/// tiny-skia canvas that is kept between frames.
+///
+/// Layout happens in logical pixels. The scale factor given to
+/// [`hint`](RendererTrait::hint) maps them to the physical pixels of the buffer, and
+/// glyphs are rasterized at their physical size so text stays sharp on HiDPI displays.
 pub struct Renderer {
     loaded_fonts: HashSet<usize>,
-    glyph_cache: GlyphCache,
+    glyph_atlas: GlyphAtlas,
+    /// Shaped text, shared by measuring and drawing. Behind a mutex because measuring
+    /// only borrows the renderer.
+    text_cache: Mutex<TextCache>,
+    /// Decoded images, also behind a mutex for measuring.
+    images: Mutex<ImageCache>,
+    gradients: GradientCache,
+    display_list: DisplayList,
+    canvas: Option<tiny_skia::Pixmap>,
+    system_fonts: bool,
+    scale_factor: Option<f32>,
+    /// What the canvas currently shows: the display list and background of the last frame.
+    previous: Option<(DisplayList, Color)>,
+    damage: Vec<Rectangle>,
+    /// Damage of recent frames that changed anything, newest first.
+    damage_history: VecDeque<Vec<Rectangle>>,
+    /// Frames since the next buffer was last presented, 0 if unknown.
+    buffer_age: u8,
 }
 
 impl Renderer {
     pub fn new() -> Self {
         Self { 
             loaded_fonts: HashSet::new(),
-            glyph_cache: GlyphCache::new(),
+            glyph_atlas: GlyphAtlas::new(DEFAULT_GLYPH_CACHE_BUDGET),
+            text_cache: Mutex::new(TextCache::default()),
+            images: Mutex::new(ImageCache::new(DEFAULT_IMAGE_CACHE_BUDGET)),
+            gradients: GradientCache::default(),
+            display_list: DisplayList::default(),
+            canvas: None,
+            system_fonts: true,
+            scale_factor: None,
+            previous: None,
+            damage: Vec::new(),
+            damage_history: VecDeque::new(),
+            buffer_age: 0,
+        }
+    }
+
+    /// Creates a renderer that ignores system fonts and only uses the bundled default
+    /// font plus fonts loaded with [`load_font`](Self::load_font).
+    ///
+    /// Text looks the same on every machine, which snapshot tests rely on.
+    pub fn with_bundled_fonts() -> Self {
+        let mut renderer = Self::new();
+        renderer.system_fonts = false;
+        // The bundled font system starts with the default font.
+        renderer.loaded_fonts.insert(DEFAULT_FONT.as_ptr() as usize);
+        renderer
+    }
+
+    /// Returns the font system text is shaped with.
+    pub(crate) fn font_system(&self) -> &'static RwLock<FontSystem> {
+        if self.system_fonts {
+            font_system()
+        } else {
+            bundled_font_system()
         }
     }
 
     /// Load the bundled default font (Roboto Regular). Text works out of the box without adding a TTF.
     pub fn load_default_font(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        self.load_font_bytes(DEFAULT_FONT.into())
+        self.load_font_bytes(DEFAULT_FONT)
     }
 
     /// Try to load font from a path (e.g. "assets/font.ttf" or env FONT_PATH).
@@ -52,12 +183,22 @@ impl Renderer {
         &mut self,
         path: impl AsRef<Path>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let bytes = std::fs::read(path.as_ref())?;
-        self.load_font_bytes(bytes.into())
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        self.load_font_bytes(bytes)
     }
 
-    /// Load font from bytes.
-    fn load_font_bytes(&mut self, bytes: Cow<'static, [u8]>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Load a font file or font data, so its families can be used by [`Font`].
+    pub fn load_font_source(&mut self, source: &font::Source) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match source {
+            font::Source::File(path) => self.load_font(path),
+            font::Source::Bytes(bytes) => self.load_font_bytes(bytes.clone()),
+        }
+    }
+
+    /// Load font from bytes, e.g. from `include_bytes!`.
+    pub fn load_font_bytes(&mut self, bytes: impl Into<Cow<'static, [u8]>>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let bytes = bytes.into();
         if let Cow::Borrowed(bytes) = bytes {
             let address = bytes.as_ptr() as usize;
             if !self.loaded_fonts.insert(address) {
@@ -65,7 +206,7 @@ impl Renderer {
             }
         }
 
-        let mut font_system = font_system().write().unwrap();
+        let mut font_system = self.font_system().write().unwrap();
         font_system.db_mut().load_font_source(cosmic_text::fontdb::Source::Binary(std::sync::Arc::new(
             bytes.into_owned(),
         )));
@@ -73,24 +214,286 @@ impl Renderer {
     }
 
     /// Measure text for layout using cosmic-text.
+    ///
+    /// The shaped text is cached and reused when the text is drawn.
     pub fn measure_text(&self, text: &str, font_size: f32) -> Size {
-        let mut font_system = font_system().write().unwrap();
-        let metrics = Metrics::new(font_size, font_size * 1.2);
-        let mut buffer = Buffer::new(&mut font_system, metrics);
-        buffer.set_size(&mut font_system, Some(f32::MAX), Some(f32::MAX));
-        buffer.set_text(&mut font_system, text, &Attrs::new(), Shaping::Advanced, None);
-        
-        let (width, height) = buffer
+        self.measure_text_with_font(text, &Font::default(), font_size)
+    }
+
+    /// Measures text drawn with `font`; see [`measure_text`](Self::measure_text).
+    pub fn measure_text_with_font(&self, text: &str, font: &Font, font_size: f32) -> Size {
+        self.measure_layout(&Paragraph::new(text, font, font_size))
+    }
+
+    /// Measures a paragraph with styled runs; see [`measure_text`](Self::measure_text).
+    pub fn measure_attributed_text(&self, text: &AttributedString, font_size: f32) -> Size {
+        self.measure_layout(&Paragraph {
+            spans: text.spans(),
+            ..Paragraph::new(text.text(), &Font::default(), font_size)
+        })
+    }
+
+    fn measure_layout(&self, paragraph: &Paragraph<'_>) -> Size {
+        let mut font_system = self.font_system().write().unwrap();
+        let mut text_cache = self.text_cache.lock().unwrap();
+        let key = TextKey {
+            content: paragraph.content,
+            spans: paragraph.spans,
+            font: paragraph.font,
+            size: paragraph.size,
+            width: None,
+            line_limit: paragraph.line_limit,
+            truncation: paragraph.truncation,
+            alignment: Alignment::Leading,
+            direction: LayoutDirection::LeftToRight,
+            typography: paragraph.typography,
+        };
+        let entry = if paragraph.max_width.is_finite() {
+            text_cache.allocate_for_width(&mut font_system, key, paragraph.max_width)
+        } else {
+            text_cache.allocate(&mut font_system, key)
+        };
+
+        Size::new(entry.min_bounds.width.max(1.0), entry.min_bounds.height.max(paragraph.size))
+    }
+
+    /// Returns the byte offset in `text.content` of the character boundary closest to
+    /// (`x`, `y`), given relative to the top-left corner of its bounds.
+    pub(crate) fn hit_text(&self, text: &Text, x: f32, y: f32) -> usize {
+        let mut font_system = self.font_system().write().unwrap();
+        let mut text_cache = self.text_cache.lock().unwrap();
+        let buffer = &text_cache.allocate_for_text(&mut font_system, text).buffer;
+
+        let offsets = line_offsets(buffer);
+        buffer
+            .hit(x, y)
+            .map_or(0, |cursor| offsets.get(cursor.line).map_or(0, |start| start + cursor.index))
+            .min(text.content.len())
+    }
+
+    /// Returns one rectangle per line covering the characters of `text.content` in
+    /// `range`, in the coordinates of its bounds.
+    pub(crate) fn selection_bounds(&self, text: &Text, range: std::ops::Range<usize>) -> Vec<Rectangle> {
+        let mut font_system = self.font_system().write().unwrap();
+        let mut text_cache = self.text_cache.lock().unwrap();
+        let buffer = &text_cache.allocate_for_text(&mut font_system, text).buffer;
+
+        let offsets = line_offsets(buffer);
+        buffer
             .layout_runs()
-            .fold((0.0, 0.0), |(width, height), run| {
-                (run.line_w.max(width), height + run.line_height)
+            .filter_map(|run| {
+                let start = offsets[run.line_i];
+                let (left, right) = run
+                    .glyphs
+                    .iter()
+                    .filter(|glyph| start + glyph.start < range.end && range.start < start + glyph.end)
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(left, right), glyph| {
+                        (left.min(glyph.x), right.max(glyph.x + glyph.w))
+                    });
+                (left < right).then(|| {
+                    Rectangle::new(text.bounds.x + left, text.bounds.y + run.line_top, right - left, run.line_height)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the cursor before byte `offset` of `text.content`: a rectangle without
+    /// width, as tall as its line.
+    pub(crate) fn caret_bounds(&self, text: &Text, offset: usize) -> Rectangle {
+        let mut font_system = self.font_system().write().unwrap();
+        let mut text_cache = self.text_cache.lock().unwrap();
+        let buffer = &text_cache.allocate_for_text(&mut font_system, text).buffer;
+
+        let offsets = line_offsets(buffer);
+        let line = offsets.iter().rposition(|start| *start <= offset).unwrap_or(0);
+        let index = offset - offsets.get(line).copied().unwrap_or(0);
+        let caret = |x: f32, top: f32, height: f32| Rectangle::new(text.bounds.x + x, text.bounds.y + top, 0.0, height);
+
+        // A wrapped line has several runs; past the glyphs of one, try the next
+        let mut end = None;
+        for run in buffer.layout_runs().filter(|run| run.line_i == line) {
+            // Glyphs are in visual order, so right-to-left text is found by its range
+            let glyph = run.glyphs.iter().find(|glyph| glyph.start <= index && index < glyph.end);
+            let glyph = glyph.or_else(|| run.glyphs.iter().find(|glyph| glyph.end > index).filter(|_| !run.rtl));
+            let x = glyph.map(|glyph| {
+                // Inside a ligature, move a share of its advance, from the right if it reads right to left
+                let inside = index.saturating_sub(glyph.start) as f32 / (glyph.end - glyph.start).max(1) as f32;
+                let inside = if glyph.level.is_rtl() { 1.0 - inside } else { inside };
+                glyph.x + glyph.w * inside
             });
-            
-        Size::new(width.max(1.0), height.max(font_size))
+            if let Some(x) = x {
+                return caret(x, run.line_top, run.line_height);
+            }
+            // A line ends past its last glyph, which for right-to-left text is on its left
+            let x = if run.rtl {
+                run.glyphs.iter().map(|glyph| glyph.x).reduce(f32::min)
+            } else {
+                run.glyphs.iter().map(|glyph| glyph.x + glyph.w).reduce(f32::max)
+            };
+            let x = x.unwrap_or(run.line_w);
+            end = Some(caret(x, run.line_top, run.line_height));
+        }
+        end.unwrap_or_else(|| caret(0.0, 0.0, text.typography.line_height.resolve(text.size)))
+    }
+
+    /// Returns the primitive showing `value` in a text field whose text goes in `inner`
+    /// and is laid out in `direction`: one line however long, shifted left by the
+    /// field's scroll. Right to left, text that fits sits at the right edge.
+    pub(crate) fn field_text(&self, field: &view::TextField, value: &str, inner: Rectangle, direction: LayoutDirection) -> Text {
+        let (content, spans) = field.display(value);
+        let size = field.size.unwrap_or(DEFAULT_FONT_SIZE);
+        let width = self.measure_layout(&Paragraph { spans: &spans, ..Paragraph::new(&content, &field.font, size) }).width + CARET_WIDTH;
+        let x = match direction {
+            LayoutDirection::LeftToRight => inner.x,
+            LayoutDirection::RightToLeft => inner.x + (inner.width - width).max(0.0),
+        };
+        Text {
+            spans,
+            font: field.font.clone(),
+            size,
+            color: field.color.unwrap_or(Color::BLACK),
+            direction,
+            ..Text::new(content, Rectangle::new(x - field.scroll, inner.y, width, inner.height))
+        }
+    }
+
+    /// Returns the paragraphs of a text editor whose text goes in `inner` and is laid
+    /// out in `direction`, each with the byte range of `value` it shows, wrapped at the
+    /// width of `inner` and moved up by the editor's scroll.
+    ///
+    /// `value` is split at line breaks the way cosmic-text splits a buffer, and each
+    /// paragraph is shaped on its own through the text cache, so an edit only shapes the
+    /// paragraph it changes again. Splitting, copying and looking up every paragraph is
+    /// still done on each call and takes time in proportion to the whole text.
+    pub(crate) fn editor_paragraphs(
+        &self,
+        editor: &view::TextEditor,
+        value: &str,
+        inner: Rectangle,
+        direction: LayoutDirection,
+    ) -> Vec<(Range<usize>, Text)> {
+        let size = editor.size.unwrap_or(DEFAULT_FONT_SIZE);
+        let line_height = LineHeight::default().resolve(size);
+        let editing = editor.editing.as_ref();
+
+        let mut ranges: Vec<Range<usize>> = LineIter::new(value).map(|(range, _)| range).collect();
+        // A trailing line break starts an empty last line
+        if ranges.last().is_none_or(|last| last.end < value.len()) {
+            ranges.push(value.len()..value.len());
+        }
+
+        let mut y = inner.y - editor.scroll;
+        ranges
+            .into_iter()
+            .map(|range| {
+                let mut content = value[range.clone()].to_string();
+                let mut spans = Vec::new();
+                if let Some(Editing { cursor, preedit: Some(preedit), .. }) = editing {
+                    if range.start <= *cursor && *cursor <= range.end {
+                        let at = cursor - range.start;
+                        content.insert_str(at, preedit);
+                        spans.push(Span {
+                            range: at..at + preedit.len(),
+                            attributes: TextAttributes::new().underline(),
+                        });
+                    }
+                }
+                let paragraph = Paragraph { spans: &spans, max_width: inner.width, ..Paragraph::new(&content, &editor.font, size) };
+                let height = self.measure_layout(&paragraph).height.max(line_height);
+                let text = Text {
+                    spans,
+                    font: editor.font.clone(),
+                    size,
+                    color: editor.color.unwrap_or(Color::BLACK),
+                    direction,
+                    ..Text::new(content, Rectangle::new(inner.x, y, inner.width, height))
+                };
+                y += height;
+                (range, text)
+            })
+            .collect()
+    }
+
+    /// Draws the box of a text field or editor, outlined in the accent color while it
+    /// has focus.
+    fn fill_input_box(&mut self, bounds: Rectangle, background: Option<&Background>, border: Border, focused: bool) {
+        let Some(background) = background else {
+            return;
+        };
+        let border = if focused {
+            Border { color: FOCUS_COLOR, width: border.width.max(2.0), ..border }
+        } else {
+            border
+        };
+        self.fill_quad(styled_quad(bounds, border, None), background.clone());
+    }
+
+    /// Draws text being edited: the `highlight` of its selection behind the glyphs, and
+    /// the caret before byte `caret` of `text.content` in front of them.
+    fn fill_edited_text(&mut self, text: Text, highlight: Vec<Rectangle>, caret: Option<usize>) {
+        for bounds in highlight {
+            self.fill_quad(styled_quad(bounds, Border::default(), None), Background::Color(SELECTION_COLOR));
+        }
+        let caret = caret.map(|offset| self.caret_bounds(&text, offset));
+        let color = text.color;
+        self.fill_text(text);
+        if let Some(caret) = caret {
+            let bounds = Rectangle::new(caret.x, caret.y, CARET_WIDTH, caret.height);
+            self.fill_quad(styled_quad(bounds, Border::default(), None), Background::Color(color));
+        }
+    }
+
+    /// Returns how many shaped paragraphs are cached and how often they were reused.
+    pub fn text_cache_stats(&self) -> TextCacheStats {
+        self.text_cache.lock().unwrap().stats()
+    }
+
+    /// Returns how many angular gradients are kept rasterized and how often they were reused.
+    pub fn gradient_cache_stats(&self) -> GradientCacheStats {
+        self.gradients.stats()
+    }
+
+    /// Returns the memory usage and hit rate of the glyph cache.
+    pub fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        self.glyph_atlas.stats()
+    }
+
+    /// Sets how many bytes of atlas pages hold rasterized glyphs before the least
+    /// recently used ones are evicted. Defaults to [`DEFAULT_GLYPH_CACHE_BUDGET`].
+    pub fn set_glyph_cache_budget(&mut self, bytes: usize) {
+        self.glyph_atlas.set_budget(bytes);
+    }
+
+    /// Sets how many bytes of decoded images are kept before the least recently used
+    /// ones are evicted. Defaults to [`DEFAULT_IMAGE_CACHE_BUDGET`].
+    ///
+    /// Images neither measured nor drawn during a frame are dropped regardless.
+    pub fn set_image_cache_budget(&mut self, bytes: usize) {
+        self.images.get_mut().unwrap().set_budget(bytes);
+    }
+
+    /// Sets how glyph edges are anti-aliased. Defaults to [`TextAntialiasing::Grayscale`].
+    ///
+    /// Changing it drops the cached glyphs, and the next [`present`](Self::present)
+    /// repaints everything.
+    pub fn set_text_antialiasing(&mut self, antialiasing: TextAntialiasing) {
+        if antialiasing != self.glyph_atlas.antialiasing() {
+            self.glyph_atlas.set_antialiasing(antialiasing);
+            self.previous = None;
+        }
+    }
+
+    /// Returns the primitives recorded since the last [`reset`](RendererTrait::reset).
+    pub fn display_list(&self) -> &DisplayList {
+        &self.display_list
     }
 
     /// Draw the view tree into a buffer (0x00RRGGBB u32, row-major, width * height).
     /// Background is cleared to background_color. Offset (e.g. for centering) is added to all positions.
+    ///
+    /// `width` and `height` are in physical pixels; the layout and the offset are in logical
+    /// pixels and get scaled by the last [`hint`](RendererTrait::hint)ed scale factor.
+    #[allow(clippy::too_many_arguments)]
     pub fn draw(
         &mut self,
         view: &View,
@@ -105,22 +508,254 @@ impl Renderer {
         if (width as usize) * (height as usize) != buffer.len() {
             return;
         }
-        for p in buffer.iter_mut() {
-            *p = background_color;
-        }
-        let rect = Rectangle::new(offset_x, offset_y, width as f32, height as f32);
-        self.draw_view(view, layout_root, buffer, width, height, rect);
+        self.record(view, layout_root, width, height, offset_x, offset_y);
+        self.present(buffer, width, height, background_color);
     }
 
-    fn draw_view(
+    /// Draw the view tree into a byte buffer in any [`PixelFormat`].
+    ///
+    /// Like [`draw`](Self::draw), but the buffer is cleared to `background`, which may be
+    /// translucent or [`Color::TRANSPARENT`] to composite the output over something else.
+    pub fn draw_to(
         &mut self,
         view: &View,
-        node: &Node,
-        buffer: &mut [u32],
-        width: u32,
-        height: u32,
-        parent_rect: Rectangle,
+        layout_root: &Node,
+        target: &mut PixelBuffer<'_>,
+        background: Color,
+        offset_x: f32,
+        offset_y: f32,
     ) {
+        self.record(view, layout_root, target.width(), target.height(), offset_x, offset_y);
+        self.present_to(target, background);
+    }
+
+    /// Records the view tree into the display list for a `width` x `height` target.
+    fn record(&mut self, view: &View, layout_root: &Node, width: u32, height: u32, offset_x: f32, offset_y: f32) {
+        self.reset(Rectangle::new(0.0, 0.0, width as f32, height as f32));
+        let scale = self.scale_factor.unwrap_or(1.0);
+        let rect = Rectangle::new(offset_x, offset_y, width as f32 / scale, height as f32 / scale);
+        self.with_transformation(Transformation::scale(scale, scale), |renderer| {
+            renderer.draw_view(view, layout_root, rect);
+        });
+    }
+
+    /// Rasterize the recorded display list into a buffer (0x00RRGGBB u32, row-major, width * height).
+    /// The buffer is cleared to background_color first.
+    ///
+    /// Only the regions where the display list differs from the previous frame are
+    /// repainted on the canvas; see [`damage`](Self::damage). The whole buffer is written
+    /// unless [`hint_buffer_age`](Self::hint_buffer_age) says what it already holds.
+    pub fn present(&mut self, buffer: &mut [u32], width: u32, height: u32, background_color: u32) {
+        let age = std::mem::take(&mut self.buffer_age);
+        if (width as usize) * (height as usize) != buffer.len() {
+            return;
+        }
+        if self.repaint(width, height, Color::from_u32(background_color)).is_none() {
+            return;
+        }
+        let Some(canvas) = &self.canvas else { return };
+
+        let convert = |pixel: &[u8]| {
+            let [r, g, b, _] = composite::demultiply([pixel[0], pixel[1], pixel[2], pixel[3]]);
+            ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+        };
+        let Some(regions) = self.buffer_damage(age) else {
+            for (pixel, out) in canvas.data().chunks_exact(4).zip(buffer.iter_mut()) {
+                *out = convert(pixel);
+            }
+            return;
+        };
+        let viewport = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+        for region in damage::group(regions, viewport) {
+            let (x, width_px) = (region.x as usize, region.width as usize);
+            for y in region.y as usize..(region.y + region.height) as usize {
+                let start = y * width as usize + x;
+                let pixels = canvas.data()[start * 4..(start + width_px) * 4].chunks_exact(4);
+                for (pixel, out) in pixels.zip(&mut buffer[start..start + width_px]) {
+                    *out = convert(pixel);
+                }
+            }
+        }
+    }
+
+    /// Tells the renderer that the buffer handed to the next [`present`](Self::present)
+    /// still shows the frame presented `age` frames ago, as reported by
+    /// `softbuffer::Buffer::age`. Only the regions changed since are written then.
+    ///
+    /// 0, the default, means the contents are unknown and the whole buffer is written.
+    /// The hint applies to the next present only.
+    pub fn hint_buffer_age(&mut self, age: u8) {
+        self.buffer_age = age;
+    }
+
+    /// Returns the regions that changed over the last `age` frames, or `None` if a
+    /// buffer that old has to be written whole.
+    fn buffer_damage(&self, age: u8) -> Option<Vec<Rectangle>> {
+        let age = usize::from(age);
+        if age == 0 || age > self.damage_history.len() {
+            return None;
+        }
+        Some(self.damage_history.iter().take(age).flatten().copied().collect())
+    }
+
+    /// Rasterize the recorded display list into a byte buffer, cleared to `background` first.
+    ///
+    /// Damage tracking works as in [`present`](Self::present); the whole buffer is written.
+    pub fn present_to(&mut self, target: &mut PixelBuffer<'_>, background: Color) {
+        if let Some(canvas) = self.repaint(target.width(), target.height(), background) {
+            target.write(canvas);
+        }
+    }
+
+    /// Brings the canvas up to date with the display list, repainting only damaged
+    /// regions when the size and background did not change.
+    fn repaint(&mut self, width: u32, height: u32, background: Color) -> Option<&tiny_skia::Pixmap> {
+        let viewport = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+        let display_list = std::mem::take(&mut self.display_list);
+        let items = display_list.items();
+        let previous = self.previous.take();
+
+        let (mut canvas, damage) = match (self.canvas.take(), &previous) {
+            (Some(canvas), Some((previous, previous_background)))
+                if canvas.width() == width && canvas.height() == height && *previous_background == background =>
+            {
+                let damage = damage::group(damage::diff(previous.items(), items), viewport);
+                // Repainting most of the canvas region by region costs more than a full repaint.
+                let area: f32 = damage.iter().map(Rectangle::area).sum();
+                if area > viewport.area() * 0.5 {
+                    (canvas, vec![viewport])
+                } else {
+                    (canvas, damage)
+                }
+            }
+            (canvas, _) => {
+                let canvas = match canvas {
+                    Some(canvas) if canvas.width() == width && canvas.height() == height => canvas,
+                    _ => {
+                        let Some(canvas) = tiny_skia::Pixmap::new(width, height) else {
+                            self.display_list = display_list;
+                            self.damage_history.clear();
+                            return None;
+                        };
+                        canvas
+                    }
+                };
+                (canvas, vec![viewport])
+            }
+        };
+
+        // The source blend mode replaces pixels, so a translucent background clears them.
+        let clear = tiny_skia::Paint {
+            shader: tiny_skia::Shader::SolidColor(quad::to_color(background)),
+            blend_mode: tiny_skia::BlendMode::Source,
+            anti_alias: false,
+            ..Default::default()
+        };
+        for region in &damage {
+            let Some(rect) = tiny_skia::Rect::from_xywh(region.x, region.y, region.width, region.height) else {
+                continue;
+            };
+            canvas.fill_rect(rect, &clear, tiny_skia::Transform::identity(), None);
+            if *region == viewport {
+                self.rasterize(items, &mut canvas);
+            } else {
+                self.rasterize(&damage::clip_items(items, *region), &mut canvas);
+            }
+        }
+
+        // Text that was neither measured nor drawn since the last frame has gone away.
+        self.text_cache.get_mut().unwrap().trim();
+        self.images.get_mut().unwrap().trim();
+        self.gradients.trim();
+
+        // A frame that changed nothing is not presented, so it does not age buffers
+        if !damage.is_empty() {
+            self.damage_history.push_front(damage.clone());
+            self.damage_history.truncate(MAX_BUFFER_AGE);
+        }
+        self.damage = damage;
+        // The drawn list is kept to diff the next frame against; the older one records the next frame
+        let bounds = display_list.bounds();
+        self.display_list = previous.map(|(list, _)| list).unwrap_or_default();
+        self.display_list.reset(bounds);
+        self.previous = Some((display_list, background));
+        Some(self.canvas.insert(canvas))
+    }
+
+    /// Returns the regions repainted by the last [`present`](Self::present), in physical pixels.
+    ///
+    /// Empty if the frame did not change. The regions can be handed to the compositor
+    /// (e.g. `softbuffer::Buffer::present_with_damage`).
+    pub fn damage(&self) -> &[Rectangle] {
+        &self.damage
+    }
+
+    /// Rasterizes items in order onto the premultiplied canvas.
+    fn rasterize(&mut self, items: &[Item], canvas: &mut tiny_skia::Pixmap) {
+        // Consecutive items usually share a clip, so the mask is only rebuilt when it changes.
+        let mut clip: Option<(Rectangle, Vec<ClipPath>, Option<tiny_skia::Mask>)> = None;
+
+        for item in items {
+            // Text clips itself to the rectangle while blitting and only needs a mask for clip paths.
+            let needs_mask = match item.primitive {
+                Primitive::Group { .. } => false,
+                Primitive::Text(_) => !item.clip_paths.is_empty() || !blits_text(item.transformation),
+                _ => true,
+            };
+            if !needs_mask {
+                clip = None;
+            } else if clip.as_ref().is_none_or(|(rect, paths, _)| (rect, paths) != (&item.clip, &item.clip_paths)) {
+                let mask = quad::clip_mask(canvas, item.clip, &item.clip_paths);
+                clip = Some((item.clip, item.clip_paths.clone(), mask));
+            }
+            let mask = clip.as_ref().and_then(|(_, _, mask)| mask.as_ref());
+
+            match &item.primitive {
+                Primitive::Quad { quad, background } => {
+                    quad::fill_quad(canvas, quad, background, item.transformation, mask, &mut self.gradients);
+                }
+                Primitive::Text(text) if blits_text(item.transformation) => {
+                    self.draw_text(text, item.transformation, item.clip, mask, canvas);
+                }
+                Primitive::Text(text) => {
+                    self.draw_transformed_text(text, item.transformation, mask, canvas);
+                }
+                Primitive::Image { handle, interpolation, bounds } => {
+                    let Some(pixmap) = self.images.get_mut().unwrap().load(handle) else { continue };
+                    draw_image(canvas, &pixmap, *interpolation, *bounds, item.transformation, mask);
+                }
+                Primitive::Fill { path, background } => {
+                    path::fill_path(canvas, path, background, item.transformation, mask, &mut self.gradients);
+                }
+                Primitive::Stroke { path, stroke } => {
+                    path::stroke_path(canvas, path, stroke, item.transformation, mask);
+                }
+                Primitive::Group { opacity, items } => {
+                    if *opacity <= 0.0 {
+                        continue;
+                    }
+                    let Some(mut layer) = tiny_skia::Pixmap::new(canvas.width(), canvas.height()) else {
+                        continue;
+                    };
+                    self.rasterize(items, &mut layer);
+                    canvas.draw_pixmap(
+                        0,
+                        0,
+                        layer.as_ref(),
+                        &tiny_skia::PixmapPaint {
+                            opacity: opacity.min(1.0),
+                            ..Default::default()
+                        },
+                        tiny_skia::Transform::identity(),
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Records the view tree into the display list.
+    fn draw_view(&mut self, view: &View, node: &Node, parent_rect: Rectangle) {
         let bounds = node.bounds;
         let abs_rect = Rectangle::new(
             parent_rect.x + bounds.x,
@@ -129,16 +764,69 @@ impl Renderer {
             bounds.height,
         );
 
+        // Rotation, scale and offset move the drawing, not the layout.
+        let effects = view.effects();
+        let transformed = !effects.is_identity();
+        if transformed {
+            self.start_transformation(effects.transformation(abs_rect));
+        }
+
+        // Translucent subtrees are drawn offscreen so overlapping children don't show through.
+        let opacity = view.opacity();
+        if opacity < 1.0 {
+            self.start_opacity(opacity);
+        }
+
+        // Rectangular clips stay layers; other shapes clip with their outline.
+        let clip = view.clip_shape();
+        match clip {
+            Some(ShapeKind::Rectangle) => self.start_layer(abs_rect),
+            Some(shape) => self.start_clip(&shape.path(abs_rect)),
+            None => {}
+        }
+
         match view {
             View::Text(t) => {
-                let font_size = t.size.unwrap_or(DEFAULT_FONT_SIZE);
-                let text_color = t.color.unwrap_or(Color::new(0.0, 0.0, 0.0, 1.0)); // Default to black
-                self.draw_text(&t.string, abs_rect, font_size, text_color, buffer, width, height);
+                let text = text_primitive(t, abs_rect, node.direction);
+                // The selection is highlighted behind the glyphs
+                if let Some(selection) = t.selection.clone().filter(|selection| !selection.is_empty()) {
+                    for bounds in self.selection_bounds(&text, selection) {
+                        self.fill_quad(styled_quad(bounds, Border::default(), None), Background::Color(SELECTION_COLOR));
+                    }
+                }
+                self.fill_text(text);
+            }
+            View::Image(i) => {
+                if let Some(intrinsic) = ImageRenderer::measure_image(self, &i.handle) {
+                    let content = i.content_bounds(abs_rect, intrinsic);
+                    // Filled or oversized images must not spill out of their frame.
+                    self.with_layer(abs_rect, |renderer| {
+                        renderer.draw_image(i.handle.clone(), i.interpolation, content);
+                    });
+                }
+            }
+            View::Shape(shape) => {
+                let path = shape.kind.path(abs_rect);
+                if let Some(fill) = shape.effective_fill() {
+                    self.fill_path(&path, fill);
+                }
+                if let Some(stroke) = &shape.stroke {
+                    self.stroke_path(&path, stroke.clone());
+                }
+            }
+            View::Canvas(canvas) => {
+                // The closure draws in canvas coordinates, clipped to the canvas bounds.
+                self.with_layer(abs_rect, |renderer| {
+                    renderer.with_translation(Vector::new(abs_rect.x, abs_rect.y), |renderer| {
+                        let mut frame = Frame::new(renderer, abs_rect.size(), node.direction);
+                        (canvas.draw)(&mut frame);
+                    });
+                });
             }
             View::Button(b) => {
                 // Draw button background if present
                 if let Some(background) = &b.background {
-                    self.draw_background(background, abs_rect, buffer, width, height);
+                    self.fill_quad(styled_quad(abs_rect, b.border, b.shadow), background.clone());
                 }
                 // Draw button text (centered within the button bounds)
                 let font_size = b.text_size.unwrap_or(DEFAULT_FONT_SIZE);
@@ -150,144 +838,463 @@ impl Renderer {
                     abs_rect.width - b.padding * 2.0,
                     abs_rect.height - b.padding * 2.0,
                 );
-                self.draw_text(&b.label, text_rect, font_size, text_color, buffer, width, height);
+                self.fill_text(Text {
+                    font: b.font.clone(),
+                    size: font_size,
+                    color: text_color,
+                    direction: node.direction,
+                    ..Text::new(b.label.clone(), text_rect)
+                });
+            }
+            View::TextField(f) => {
+                self.fill_input_box(abs_rect, f.background.as_ref(), f.border, f.editing.is_some());
+                let value = f.text.get();
+                let inner = f.inner_bounds(abs_rect);
+                let text = self.field_text(f, &value, inner, node.direction);
+                self.with_layer(inner, |renderer| {
+                    if text.content.is_empty() {
+                        renderer.fill_text(Text {
+                            content: f.placeholder.clone(),
+                            bounds: inner,
+                            color: PLACEHOLDER_COLOR,
+                            ..text.clone()
+                        });
+                    }
+                    let Some(editing) = &f.editing else {
+                        renderer.fill_text(text);
+                        return;
+                    };
+                    let selection = editing.selection.clone().filter(|selection| !selection.is_empty());
+                    let highlight = selection.as_ref().map_or_else(Vec::new, |selection| {
+                        let range = f.display_offset(&value, selection.start)..f.display_offset(&value, selection.end);
+                        renderer.selection_bounds(&text, range)
+                    });
+                    let caret = (editing.caret_visible && selection.is_none())
+                        .then(|| f.display_offset(&value, editing.cursor) + editing.preedit.as_ref().map_or(0, String::len));
+                    renderer.fill_edited_text(text, highlight, caret);
+                });
+            }
+            View::TextEditor(e) => {
+                self.fill_input_box(abs_rect, e.background.as_ref(), e.border, e.editing.is_some());
+                let value = e.text.get();
+                let inner = e.inner_bounds(abs_rect);
+                let paragraphs = self.editor_paragraphs(e, &value, inner, node.direction);
+                let editing = e.editing.as_ref();
+                let selection = editing.and_then(|editing| editing.selection.clone()).filter(|selection| !selection.is_empty());
+                let caret = editing.filter(|editing| editing.caret_visible && selection.is_none());
+                self.with_layer(inner, |renderer| {
+                    for (range, text) in paragraphs {
+                        // Only the paragraphs scrolled into view are drawn
+                        if text.bounds.y > inner.y + inner.height || text.bounds.y + text.bounds.height < inner.y {
+                            continue;
+                        }
+                        let mut highlight = Vec::new();
+                        if let Some(selection) = &selection {
+                            let (start, end) = (selection.start.max(range.start), selection.end.min(range.end));
+                            if start < end {
+                                highlight = renderer.selection_bounds(&text, start - range.start..end - range.start);
+                            } else if range.is_empty() && selection.start <= range.start && range.end < selection.end {
+                                // A selected empty line shows as a sliver
+                                let caret = renderer.caret_bounds(&text, 0);
+                                highlight.push(Rectangle::new(caret.x, caret.y, text.size / 3.0, caret.height));
+                            }
+                        }
+                        let caret = caret
+                            .filter(|editing| range.start <= editing.cursor && editing.cursor <= range.end)
+                            .map(|editing| editing.cursor - range.start + editing.preedit.as_ref().map_or(0, String::len));
+                        renderer.fill_edited_text(text, highlight, caret);
+                    }
+                });
             }
             View::VStack(v) => {
                 // Draw background if present
                 if let Some(background) = &v.background {
-                    self.draw_background(background, abs_rect, buffer, width, height);
+                    self.fill_quad(styled_quad(abs_rect, v.border, v.shadow), background.clone());
                 }
                 // Draw children
                 for (child_view, child_node) in v.children.iter().zip(node.children.iter()) {
-                    self.draw_view(child_view, child_node, buffer, width, height, abs_rect);
+                    self.draw_view(child_view, child_node, abs_rect);
                 }
             }
             View::HStack(h) => {
                 // Draw background if present
                 if let Some(background) = &h.background {
-                    self.draw_background(background, abs_rect, buffer, width, height);
+                    self.fill_quad(styled_quad(abs_rect, h.border, h.shadow), background.clone());
                 }
                 // Draw children
                 for (child_view, child_node) in h.children.iter().zip(node.children.iter()) {
-                    self.draw_view(child_view, child_node, buffer, width, height, abs_rect);
+                    self.draw_view(child_view, child_node, abs_rect);
                 }
             }
         }
+
+        match clip {
+            Some(ShapeKind::Rectangle) => self.end_layer(),
+            Some(_) => self.end_clip(),
+            None => {}
+        }
+
+        if opacity < 1.0 {
+            self.end_opacity();
+        }
+
+        if transformed {
+            self.end_transformation();
+        }
+    }
+
+    /// Draws text under a rotation or non-uniform scale: the glyphs are blitted upright
+    /// into a scratch pixmap, which is then drawn with the transformation.
+    fn draw_transformed_text(
+        &mut self,
+        text: &Text,
+        transformation: Transformation,
+        mask: Option<&tiny_skia::Mask>,
+        canvas: &mut tiny_skia::Pixmap,
+    ) {
+        let Transformation { a, b, c, d, .. } = transformation;
+        let scale = (a * a + b * b).sqrt().max((c * c + d * d).sqrt());
+        if scale <= 0.0 || !scale.is_finite() {
+            return;
+        }
+        // Room for glyphs that overhang the bounds
+        let margin = (text.size * scale / 2.0).ceil();
+        let width = (text.bounds.width * scale + margin * 2.0).ceil() as u32;
+        let height = (text.bounds.height * scale + margin * 2.0).ceil() as u32;
+        let Some(mut pixmap) = tiny_skia::Pixmap::new(width, height) else {
+            return;
+        };
+
+        let local = Transformation::translate(-text.bounds.x, -text.bounds.y)
+            .then(Transformation::scale(scale, scale))
+            .then(Transformation::translate(margin, margin));
+        let clip = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+        self.draw_text(text, local, clip, None, &mut pixmap);
+
+        let to_target = Transformation::translate(-margin, -margin)
+            .then(Transformation::scale(1.0 / scale, 1.0 / scale))
+            .then(Transformation::translate(text.bounds.x, text.bounds.y))
+            .then(transformation);
+        canvas.draw_pixmap(
+            0,
+            0,
+            pixmap.as_ref(),
+            &tiny_skia::PixmapPaint {
+                quality: tiny_skia::FilterQuality::Bilinear,
+                ..Default::default()
+            },
+            quad::to_transform(to_target),
+            mask,
+        );
     }
 
     fn draw_text(
         &mut self,
-        text: &str,
-        rect: Rectangle,
-        font_size: f32,
-        color: Color,
-        buffer: &mut [u32],
-        buf_width: u32,
-        buf_height: u32,
+        text: &Text,
+        transformation: Transformation,
+        clip: Rectangle,
+        mask: Option<&tiny_skia::Mask>,
+        canvas: &mut tiny_skia::Pixmap,
     ) {
-        let mut font_system = font_system().write().unwrap();
-        let metrics = Metrics::new(font_size, font_size * 1.2);
-        let mut text_buffer = Buffer::new(&mut font_system, metrics);
-        text_buffer.set_size(&mut font_system, Some(rect.width), Some(rect.height));
-        text_buffer.set_text(&mut font_system, text, &Attrs::new(), Shaping::Advanced, None);
-        
-        let mut swash = SwashCache::new();
-        let color_rgb = [
-            (color.r * 255.0) as u8,
-            (color.g * 255.0) as u8, 
-            (color.b * 255.0) as u8
-        ];
-        
+        let (buf_width, buf_height) = (canvas.width(), canvas.height());
+        let buffer = canvas.data_mut();
+        let scale = transformation.scale_factor();
+        let origin = transformation.transform_point(Point::new(text.bounds.x, text.bounds.y));
+
+        // Text is shaped in logical pixels and its glyphs are rasterized at the physical size
+        let mut font_system = self.font_system().write().unwrap();
+        let text_buffer = &self.text_cache.get_mut().unwrap().allocate_for_text(&mut font_system, text).buffer;
+
+        // Styled runs carry the index of their span plus one as glyph metadata
+        let attributes = |metadata: usize| {
+            metadata
+                .checked_sub(1)
+                .and_then(|index| text.spans.get(index))
+                .map(|span| &span.attributes)
+        };
+
+        // Only touch pixels inside both the clip rectangle and the buffer
+        let x_min = clip.x.max(0.0) as i32;
+        let y_min = clip.y.max(0.0) as i32;
+        let x_max = (clip.x + clip.width).min(buf_width as f32) as i32;
+        let y_max = (clip.y + clip.height).min(buf_height as f32) as i32;
+        let visible = (x_min, y_min, x_max, y_max);
+
         for run in text_buffer.layout_runs() {
+            // Lines below the bounds are not drawn
+            if run.line_top > text.bounds.height {
+                break;
+            }
+            let line_y = (run.line_y * scale).round() as i32;
+            let segments = segments(run.glyphs);
+
+            // Highlights go behind the glyphs and cover the whole line box
+            let line_top = (origin.y + run.line_top * scale).round() as i32;
+            let line_bottom = (origin.y + (run.line_top + run.line_height) * scale).round() as i32;
+            for segment in &segments {
+                if let Some(background) = attributes(segment.metadata).and_then(|attributes| attributes.background) {
+                    let (x0, x1) = segment.physical_x(origin.x, scale);
+                    fill_rect(buffer, buf_width, (x0, line_top, x1, line_bottom), visible, background, mask);
+                }
+            }
+
             for glyph in run.glyphs.iter() {
-                let physical_glyph = glyph.physical((rect.x, rect.y), 1.0);
-                
-                if let Some((glyph_buffer, placement)) = self.glyph_cache.allocate(
-                    physical_glyph.cache_key,
-                    color_rgb,
-                    &mut font_system,
-                    &mut swash,
-                ) {
-                    // Draw the rasterized glyph with proper alpha blending
-                    let glyph_x = physical_glyph.x + placement.left;
-                    let glyph_y = physical_glyph.y - placement.top + run.line_y as i32;
-                    
-                    for y in 0..placement.height as i32 {
-                        for x in 0..placement.width as i32 {
-                            let pixel_x = glyph_x + x;
-                            let pixel_y = glyph_y + y;
-                            
-                            if pixel_x >= 0 && pixel_x < buf_width as i32 && 
-                               pixel_y >= 0 && pixel_y < buf_height as i32 {
-                                let glyph_idx = (y * placement.width as i32 + x) as usize;
-                                let buffer_idx = (pixel_y as u32 * buf_width + pixel_x as u32) as usize;
-                                
-                                if glyph_idx < glyph_buffer.len() && buffer_idx < buffer.len() {
-                                    let glyph_pixel = glyph_buffer[glyph_idx];
-                                    if glyph_pixel >> 24 > 0 { // Check alpha channel
-                                        // Simple alpha blending
-                                        let alpha = (glyph_pixel >> 24) as f32 / 255.0;
-                                        let inv_alpha = 1.0 - alpha;
-                                        
-                                        let bg = buffer[buffer_idx];
-                                        let bg_r = ((bg >> 16) & 0xFF) as f32;
-                                        let bg_g = ((bg >> 8) & 0xFF) as f32;
-                                        let bg_b = (bg & 0xFF) as f32;
-                                        
-                                        let fg_r = ((glyph_pixel >> 16) & 0xFF) as f32;
-                                        let fg_g = ((glyph_pixel >> 8) & 0xFF) as f32;
-                                        let fg_b = (glyph_pixel & 0xFF) as f32;
-                                        
-                                        let r = (fg_r * alpha + bg_r * inv_alpha) as u32;
-                                        let g = (fg_g * alpha + bg_g * inv_alpha) as u32;
-                                        let b = (fg_b * alpha + bg_b * inv_alpha) as u32;
-                                        
-                                        buffer[buffer_idx] = (r << 16) | (g << 8) | b;
-                                    }
+                let physical_glyph = glyph.physical((origin.x, origin.y), scale);
+                let Some(image) = self.glyph_atlas.get(physical_glyph.cache_key, &mut font_system) else {
+                    continue;
+                };
+                let color = attributes(glyph.metadata)
+                    .and_then(|attributes| attributes.color)
+                    .unwrap_or(text.color);
+                let alpha = color.a.clamp(0.0, 1.0);
+                let color_rgb = [
+                    (color.r.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (color.g.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (color.b.clamp(0.0, 1.0) * 255.0).round() as u8,
+                ];
+                let placement = image.placement;
+                let glyph_x = physical_glyph.x + placement.left;
+                let glyph_y = physical_glyph.y - placement.top + line_y;
+
+                // Visible part of the glyph, in glyph coordinates
+                let (x_start, x_end) = ((x_min - glyph_x).max(0), (x_max - glyph_x).min(placement.width as i32));
+                let (y_start, y_end) = ((y_min - glyph_y).max(0), (y_max - glyph_y).min(placement.height as i32));
+
+                for y in y_start..y_end {
+                    for x in x_start..x_end {
+                        let pixel = image.pixel(x as u32, y as u32);
+                        let index = (glyph_y + y) as usize * buf_width as usize + (glyph_x + x) as usize;
+                        // Coverage of clip paths at this pixel
+                        let alpha = alpha * mask.map_or(1.0, |mask| mask.data()[index] as f32 / 255.0);
+                        if alpha <= 0.0 {
+                            continue;
+                        }
+                        let dst = &mut buffer[index * 4..index * 4 + 4];
+                        // Masks are tinted with the text color; color glyphs keep their own colors
+                        match image.kind {
+                            GlyphKind::Mask => {
+                                composite::blend_text(dst, color_rgb, [pixel[0] as f32 / 255.0 * alpha; 3]);
+                            }
+                            GlyphKind::Subpixel => {
+                                let coverage = [0, 1, 2].map(|i| pixel[i] as f32 / 255.0 * alpha);
+                                composite::blend_text(dst, color_rgb, coverage);
+                            }
+                            GlyphKind::Color => {
+                                let src = Premultiplied::from_premultiplied([pixel[0], pixel[1], pixel[2], pixel[3]], alpha);
+                                if !src.is_transparent() {
+                                    composite::source_over(dst, src);
                                 }
                             }
                         }
                     }
                 }
             }
+
+            // Underlines and strikethroughs go over the glyphs, placed by the font's metrics
+            let baseline = origin.y + run.line_y * scale;
+            for segment in &segments {
+                let Some(attributes) = attributes(segment.metadata) else { continue };
+                if !attributes.underline && !attributes.strikethrough {
+                    continue;
+                }
+                let color = attributes.color.unwrap_or(text.color);
+                let (x0, x1) = segment.physical_x(origin.x, scale);
+                let decorations = Decorations::of(&mut font_system, segment, scale);
+                for (enabled, offset) in [
+                    (attributes.underline, decorations.underline_offset),
+                    (attributes.strikethrough, decorations.strikeout_offset),
+                ] {
+                    if enabled {
+                        let top = (baseline - offset).round() as i32;
+                        let bottom = top + decorations.thickness;
+                        fill_rect(buffer, buf_width, (x0, top, x1, bottom), visible, color, mask);
+                    }
+                }
+            }
         }
-        
-        self.glyph_cache.trim();
     }
+}
 
-    fn draw_background(
-        &self,
-        background: &Background,
-        rect: Rectangle,
-        buffer: &mut [u32],
-        buf_width: u32,
-        buf_height: u32,
-    ) {
-        match background {
-            Background::Color(color) => {
-                let color_u32 = ((color.a * 255.0) as u32) << 24
-                    | ((color.r * 255.0) as u32) << 16
-                    | ((color.g * 255.0) as u32) << 8
-                    | ((color.b * 255.0) as u32);
-
-                let x_start = rect.x.max(0.0) as u32;
-                let y_start = rect.y.max(0.0) as u32;
-                let x_end = (rect.x + rect.width).min(buf_width as f32) as u32;
-                let y_end = (rect.y + rect.height).min(buf_height as f32) as u32;
+/// Consecutive glyphs of a line that belong to the same span.
+#[derive(Debug)]
+struct Segment {
+    metadata: usize,
+    /// Horizontal extent in logical pixels, relative to the paragraph.
+    x: (f32, f32),
+    font_id: cosmic_text::fontdb::ID,
+    font_weight: cosmic_text::fontdb::Weight,
+    font_size: f32,
+}
 
-                for y in y_start..y_end {
-                    for x in x_start..x_end {
-                        let idx = (y * buf_width + x) as usize;
-                        if idx < buffer.len() {
-                            buffer[idx] = color_u32;
-                        }
-                    }
-                }
+impl Segment {
+    /// Returns the horizontal extent in physical pixels, snapped to whole pixels.
+    fn physical_x(&self, origin_x: f32, scale: f32) -> (i32, i32) {
+        (
+            (origin_x + self.x.0 * scale).round() as i32,
+            (origin_x + self.x.1 * scale).round() as i32,
+        )
+    }
+}
+
+fn segments(glyphs: &[cosmic_text::LayoutGlyph]) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for glyph in glyphs {
+        let (start, end) = (glyph.x, glyph.x + glyph.w);
+        match segments.last_mut() {
+            Some(segment) if segment.metadata == glyph.metadata => {
+                segment.x = (segment.x.0.min(start), segment.x.1.max(end));
+            }
+            _ => segments.push(Segment {
+                metadata: glyph.metadata,
+                x: (start, end),
+                font_id: glyph.font_id,
+                font_weight: glyph.font_weight,
+                font_size: glyph.font_size,
+            }),
+        }
+    }
+    segments
+}
+
+/// Placement of underlines and strikethroughs in physical pixels. Offsets are
+/// measured upwards from the baseline to the top of the stroke.
+#[derive(Debug)]
+struct Decorations {
+    underline_offset: f32,
+    strikeout_offset: f32,
+    thickness: i32,
+}
+
+impl Decorations {
+    fn of(font_system: &mut FontSystem, segment: &Segment, scale: f32) -> Self {
+        let size = segment.font_size * scale;
+        let metrics = font_system
+            .get_font(segment.font_id, segment.font_weight)
+            .map(|font| font.as_swash().metrics(&[]).scale(size));
+
+        match metrics {
+            Some(metrics) if metrics.stroke_size > 0.0 => Self {
+                underline_offset: metrics.underline_offset,
+                strikeout_offset: metrics.strikeout_offset,
+                thickness: metrics.stroke_size.round().max(1.0) as i32,
+            },
+            // Fonts without the metrics get the usual proportions
+            _ => Self {
+                underline_offset: -size * 0.1,
+                strikeout_offset: size * 0.3,
+                thickness: (size / 14.0).round().max(1.0) as i32,
+            },
+        }
+    }
+}
+
+/// Composites `color` over the rectangle `(x0, y0, x1, y1)` of the canvas, limited to
+/// `visible` and weighted by the clip `mask`.
+fn fill_rect(
+    buffer: &mut [u8],
+    buf_width: u32,
+    (x0, y0, x1, y1): (i32, i32, i32, i32),
+    (x_min, y_min, x_max, y_max): (i32, i32, i32, i32),
+    color: Color,
+    mask: Option<&tiny_skia::Mask>,
+) {
+    let rgba = [color.r, color.g, color.b, 1.0].map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+    for y in y0.max(y_min)..y1.min(y_max) {
+        for x in x0.max(x_min)..x1.min(x_max) {
+            let index = y as usize * buf_width as usize + x as usize;
+            let coverage = mask.map_or(1.0, |mask| mask.data()[index] as f32 / 255.0);
+            let src = Premultiplied::from_straight(rgba, color.a * coverage);
+            if !src.is_transparent() {
+                composite::source_over(&mut buffer[index * 4..index * 4 + 4], src);
             }
         }
     }
 }
 
+/// Returns whether glyphs can be blitted straight onto the canvas, which needs an
+/// upright, uniformly scaled transformation.
+fn blits_text(transformation: Transformation) -> bool {
+    transformation.is_axis_aligned() && transformation.a == transformation.d && transformation.a > 0.0
+}
+
+/// Draws an image stretched to `bounds` with the given sampling quality.
+fn draw_image(
+    canvas: &mut tiny_skia::Pixmap,
+    pixmap: &tiny_skia::Pixmap,
+    interpolation: Interpolation,
+    bounds: Rectangle,
+    transformation: Transformation,
+    clip: Option<&tiny_skia::Mask>,
+) {
+    let Some(rect) = tiny_skia::Rect::from_xywh(bounds.x, bounds.y, bounds.width, bounds.height) else {
+        return;
+    };
+
+    let quality = match interpolation {
+        Interpolation::None => tiny_skia::FilterQuality::Nearest,
+        Interpolation::Low | Interpolation::Medium => tiny_skia::FilterQuality::Bilinear,
+        Interpolation::High => tiny_skia::FilterQuality::Bicubic,
+    };
+    let paint = tiny_skia::Paint {
+        shader: tiny_skia::Pattern::new(
+            pixmap.as_ref(),
+            tiny_skia::SpreadMode::Pad,
+            quality,
+            1.0,
+            tiny_skia::Transform::from_row(
+                bounds.width / pixmap.width() as f32,
+                0.0,
+                0.0,
+                bounds.height / pixmap.height() as f32,
+                bounds.x,
+                bounds.y,
+            ),
+        ),
+        ..Default::default()
+    };
+    canvas.fill_rect(rect, &paint, quad::to_transform(transformation), clip);
+}
+
+/// Returns the primitive that draws the text view `t` in `bounds`.
+pub(crate) fn text_primitive(t: &crate::view::Text, bounds: Rectangle, direction: LayoutDirection) -> Text {
+    Text {
+        spans: t.spans.clone(),
+        font: t.font.clone(),
+        size: t.size.unwrap_or(DEFAULT_FONT_SIZE),
+        color: t.color.unwrap_or(Color::BLACK),
+        line_limit: t.line_limit,
+        truncation: t.truncation,
+        alignment: t.alignment,
+        direction,
+        typography: t.typography.clone(),
+        ..Text::new(t.string.clone(), bounds)
+    }
+}
+
+/// Returns the byte offset in the content where each line of the buffer starts.
+fn line_offsets(buffer: &cosmic_text::Buffer) -> Vec<usize> {
+    let mut offset = 0;
+    buffer
+        .lines
+        .iter()
+        .map(|line| {
+            let start = offset;
+            offset += line.text().len() + line.ending().as_str().len();
+            start
+        })
+        .collect()
+}
+
+/// Builds the background [`Quad`] of a styled view.
+fn styled_quad(bounds: Rectangle, border: Border, shadow: Option<Shadow>) -> Quad {
+    Quad {
+        bounds,
+        border,
+        shadow: shadow.unwrap_or(Shadow::new(Color::TRANSPARENT, Vector::ZERO, 0.0)),
+        snap: false,
+    }
+}
+
 impl Default for Renderer {
     fn default() -> Self {
         Self::new()
@@ -298,43 +1305,61 @@ impl TextMeasurer for Renderer {
     fn measure(&self, text: &str, font_size: f32) -> Size {
         self.measure_text(text, font_size)
     }
+
+    fn measure_paragraph(&self, paragraph: &Paragraph<'_>) -> Size {
+        self.measure_layout(paragraph)
+    }
+
+    fn measure_image(&self, handle: &Handle) -> Option<Size> {
+        let pixmap = self.images.lock().unwrap().load(handle)?;
+        Some(Size::new(pixmap.width() as f32, pixmap.height() as f32))
+    }
 }
 
 impl RendererTrait for Renderer {
-    fn start_layer(&mut self, _bounds: Rectangle) {
-        // For now, layers are not implemented
-        // This would require maintaining a stack of clip regions
+    fn start_layer(&mut self, bounds: Rectangle) {
+        self.display_list.push_layer(bounds);
     }
 
     fn end_layer(&mut self) {
-        // For now, layers are not implemented
+        self.display_list.pop_layer();
     }
 
-    fn start_transformation(&mut self, _transformation: Transformation) {
-        // For now, transformations are not implemented
-        // This would require maintaining a transformation stack
+    fn start_transformation(&mut self, transformation: Transformation) {
+        self.display_list.push_transformation(transformation);
     }
 
     fn end_transformation(&mut self) {
-        // For now, transformations are not implemented
+        self.display_list.pop_transformation();
+    }
+
+    fn start_opacity(&mut self, opacity: f32) {
+        self.display_list.push_group(opacity);
+    }
+
+    fn end_opacity(&mut self) {
+        self.display_list.pop_group();
     }
 
     fn fill_quad(&mut self, quad: Quad, background: impl Into<Background>) {
-        // For now, quads are not implemented
-        // This would require drawing rectangles with borders and shadows
-        let _ = (quad, background);
+        self.display_list.push(Primitive::Quad {
+            quad,
+            background: background.into(),
+        });
     }
 
-    fn reset(&mut self, _new_bounds: Rectangle) {
-        // Reset is handled by the draw() method which clears the buffer
+    fn reset(&mut self, new_bounds: Rectangle) {
+        self.display_list.reset(new_bounds);
     }
 
-    fn hint(&mut self, _scale_factor: f32) {
-        // Scale factor hinting is not implemented yet
+    fn hint(&mut self, scale_factor: f32) {
+        if scale_factor.is_finite() && scale_factor > 0.0 {
+            self.scale_factor = Some(scale_factor);
+        }
     }
 
     fn scale_factor(&self) -> Option<f32> {
-        None
+        self.scale_factor
     }
 
     fn tick(&mut self) {
@@ -342,173 +1367,172 @@ impl RendererTrait for Renderer {
     }
 }
 
+impl TextRenderer for Renderer {
+    fn fill_text(&mut self, text: Text) {
+        self.display_list.push(Primitive::Text(text));
+    }
+}
+
+impl ImageRenderer for Renderer {
+    fn measure_image(&self, handle: &Handle) -> Option<Size> {
+        TextMeasurer::measure_image(self, handle)
+    }
+
+    fn draw_image(&mut self, handle: Handle, interpolation: Interpolation, bounds: Rectangle) {
+        // Recording keeps the image cached even when its region is not repainted
+        self.images.get_mut().unwrap().load(&handle);
+        self.display_list.push(Primitive::Image {
+            handle,
+            interpolation,
+            bounds,
+        });
+    }
+}
+
+impl PathRenderer for Renderer {
+    fn fill_path(&mut self, path: &crate::core::path::Path, background: impl Into<Background>) {
+        self.display_list.push(Primitive::Fill {
+            path: path.clone(),
+            background: background.into(),
+        });
+    }
+
+    fn stroke_path(&mut self, path: &crate::core::path::Path, stroke: Stroke) {
+        self.display_list.push(Primitive::Stroke {
+            path: path.clone(),
+            stroke,
+        });
+    }
+
+    fn start_clip(&mut self, path: &crate::core::path::Path) {
+        self.display_list.push_clip_path(path.clone());
+    }
+
+    fn end_clip(&mut self) {
+        self.display_list.pop_layer();
+    }
+}
+
 // Extension methods for drawing with Layout
 impl Renderer {
-    /// Draw text at the given layout position.
+    /// Record text at the given layout position.
     /// This is a helper method for widgets that use the Layout system.
     pub fn draw_text_at_layout(
         &mut self,
         text: &str,
         font_size: f32,
         layout: crate::core::Layout<'_>,
-        buffer: &mut [u32],
-        buf_width: u32,
-        buf_height: u32,
     ) {
-        let bounds = layout.bounds();
-        self.draw_text(text, bounds, font_size, Color::new(0.0, 0.0, 0.0, 1.0), buffer, buf_width, buf_height);
+        self.fill_text(Text {
+            size: font_size,
+            ..Text::new(text, layout.bounds())
+        });
     }
 }
 
-#[derive(Debug, Clone, Default)]
-struct GlyphCache {
-    entries: HashMap<(cosmic_text::CacheKey, [u8; 3]), (Vec<u32>, cosmic_text::Placement)>,
-    recently_used: HashSet<(cosmic_text::CacheKey, [u8; 3])>,
-    trim_count: usize,
-}
-
-impl GlyphCache {
-    const TRIM_INTERVAL: usize = 300;
-    const CAPACITY_LIMIT: usize = 16 * 1024;
-
-    fn new() -> Self {
-        GlyphCache::default()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_measured_text_is_one_line_as_wide_as_its_content() {
+        let renderer = Renderer::with_bundled_fonts();
+        let short = renderer.measure_text("Hello", 16.0);
+        let long = renderer.measure_text("Hello World", 16.0);
+        assert!(long.width > short.width && short.width > 0.0, "{short:?} vs {long:?}");
+        assert_eq!(long.height, LineHeight::default().resolve(16.0));
+        assert_eq!(renderer.measure_text("Hello", 32.0).height, LineHeight::default().resolve(32.0));
     }
 
-    fn allocate(
-        &mut self,
-        cache_key: cosmic_text::CacheKey,
-        color: [u8; 3],
-        font_system: &mut cosmic_text::FontSystem,
-        swash: &mut cosmic_text::SwashCache,
-    ) -> Option<(&[u32], cosmic_text::Placement)> {
-        let key = (cache_key, color);
-        self.recently_used.insert(key);
-
-        // Check if already cached
-        if self.entries.contains_key(&key) {
-            let (buffer, placement) = self.entries.get(&key).unwrap();
-            return Some((buffer, *placement));
-        }
-
-        // Not cached, need to rasterize
-        let image = swash.get_image_uncached(font_system, cache_key)?;
-        let glyph_size = image.placement.width as usize * image.placement.height as usize;
-
-        if glyph_size == 0 {
-            return None;
-        }
-
-        let mut buffer = vec![0u32; glyph_size];
-        let [r, g, b] = color;
+    #[test]
+    fn test_fill_quad_is_rasterized_and_clipped() {
+        let mut renderer = Renderer::new();
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        renderer.reset(Rectangle::new(0.0, 0.0, 8.0, 8.0));
+        renderer.with_layer(Rectangle::new(0.0, 0.0, 4.0, 8.0), |renderer| {
+            renderer.with_translation(crate::core::Vector::new(2.0, 2.0), |renderer| {
+                renderer.fill_quad(Quad::new(Rectangle::new(0.0, 0.0, 4.0, 4.0)), red);
+            });
+        });
+        assert_eq!(renderer.display_list().items().len(), 1);
 
-        match image.content {
-            cosmic_text::SwashContent::Mask => {
-                let mut i = 0;
-                for _y in 0..image.placement.height {
-                    for _x in 0..image.placement.width {
-                        let alpha = image.data[i];
-                        if alpha > 0 {
-                            buffer[i] = ((alpha as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
-                        }
-                        i += 1;
-                    }
-                }
-            }
-            cosmic_text::SwashContent::Color => {
-                let mut i = 0;
-                for _y in 0..image.placement.height {
-                    for _x in 0..image.placement.width {
-                        buffer[i >> 2] = ((image.data[i + 3] as u32) << 24) |
-                                       ((image.data[i + 2] as u32) << 16) |
-                                       ((image.data[i + 1] as u32) << 8) |
-                                        (image.data[i] as u32);
-                        i += 4;
-                    }
-                }
-            }
-            cosmic_text::SwashContent::SubpixelMask => {
-                let mut i = 0;
-                for _y in 0..image.placement.height {
-                    for _x in 0..image.placement.width {
-                        let alpha = image.data[i];
-                        if alpha > 0 {
-                            buffer[i] = ((alpha as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
-                        }
-                        i += 1;
-                    }
-                }
-            }
-        }
+        let mut buffer = vec![0u32; 64];
+        renderer.present(&mut buffer, 8, 8, 0);
 
-        let placement = image.placement;
-        
-        // Insert and return reference
-        self.entries.insert(key, (buffer, placement));
-        let (buffer, placement) = self.entries.get(&key).unwrap();
-        Some((buffer, *placement))
+        let pixel = |x: usize, y: usize| buffer[y * 8 + x] & 0x00FF_FFFF;
+        assert_eq!(pixel(2, 2), 0xFF0000);
+        assert_eq!(pixel(3, 5), 0xFF0000);
+        assert_eq!(pixel(1, 2), 0, "outside the translated quad");
+        assert_eq!(pixel(4, 2), 0, "clipped by the layer");
     }
 
-    fn trim(&mut self) {
-        self.trim_count += 1;
-        if self.trim_count % Self::TRIM_INTERVAL != 0 {
-            return;
-        }
+    #[test]
+    fn test_fill_text_draws_glyphs() {
+        let mut renderer = Renderer::new();
+        let _ = renderer.load_default_font();
+        renderer.reset(Rectangle::new(0.0, 0.0, 64.0, 32.0));
+        renderer.fill_text(Text {
+            size: 20.0,
+            ..Text::new("Hi", Rectangle::new(0.0, 0.0, 64.0, 32.0))
+        });
 
-        let mut to_remove = Vec::new();
-        for key in self.entries.keys() {
-            if !self.recently_used.contains(key) {
-                to_remove.push(*key);
-            }
-        }
+        let mut buffer = vec![0x00FF_FFFFu32; 64 * 32];
+        renderer.present(&mut buffer, 64, 32, 0x00FF_FFFF);
+        assert!(buffer.iter().any(|&p| p != 0x00FF_FFFF));
+    }
 
-        for key in to_remove {
-            self.entries.remove(&key);
-        }
+    #[test]
+    fn test_font_source_that_cannot_be_read_is_reported_with_its_path() {
+        let mut renderer = Renderer::new();
+        let error = renderer.load_font_source(&font::Source::from("missing/Font.ttf")).unwrap_err();
+        assert!(error.to_string().contains("missing/Font.ttf"), "{error}");
+        assert!(renderer.load_font_source(&font::Source::from(DEFAULT_FONT)).is_ok());
+    }
 
-        self.recently_used.clear();
+    /// Draws a red 10x5 rectangle above "Hi" in a 40x40 logical space at `scale`, and
+    /// returns the pixels and the width of a row.
+    fn draw_scaled(scale: f32) -> (Vec<u32>, usize) {
+        use crate::layout::{layout, Limits};
+        use crate::view::{Shape, Text as TextView, VStack, View};
 
-        if self.entries.len() > Self::CAPACITY_LIMIT {
-            let excess = self.entries.len() - Self::CAPACITY_LIMIT;
-            let keys: Vec<_> = self.entries.keys().take(excess).cloned().collect();
-            for key in keys {
-                self.entries.remove(&key);
-            }
-        }
+        let view = View::from(VStack::new(vec![
+            View::from(Shape::rectangle().fill(Color::new(1.0, 0.0, 0.0, 1.0)).frame(10.0, 5.0)),
+            View::from(TextView::new("Hi").size(12.0)),
+        ]));
+        let mut renderer = Renderer::new();
+        renderer.load_default_font().unwrap();
+        renderer.hint(scale);
+        let node = layout(&view, Limits::loose(40.0, 40.0), &renderer);
+        let size = (40.0 * scale) as u32;
+        let mut buffer = vec![0u32; (size * size) as usize];
+        renderer.draw(&view, &node, &mut buffer, size, size, 0x00FF_FFFF, 0.0, 0.0);
+        (buffer, size as usize)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
     #[test]
-    fn test_cosmic_text_integration() {
+    fn test_hinted_scale_factor_is_reported() {
         let mut renderer = Renderer::new();
-        let _ = renderer.load_default_font();
-        
-        // Test text measurement
-        let size = renderer.measure_text("Hello World", 16.0);
-        assert!(size.width > 0.0);
-        assert!(size.height > 0.0);
-        println!("Measured text size: {}x{}", size.width, size.height);
-        
-        // Test that the font system can be accessed
-        let font_system = font_system();
-        let _fs = font_system.read().unwrap();
-        println!("Font system initialized successfully");
+        assert_eq!(renderer.scale_factor(), None);
+        renderer.hint(2.0);
+        assert_eq!(renderer.scale_factor(), Some(2.0));
     }
-    
+
     #[test]
-    fn test_text_buffer_creation() {
-        let mut font_system = font_system().write().unwrap();
-        let metrics = Metrics::new(16.0, 19.2);
-        let mut buffer = Buffer::new(&mut font_system, metrics);
-        buffer.set_size(&mut font_system, Some(200.0), Some(100.0));
-        buffer.set_text(&mut font_system, "Test", &Attrs::new(), Shaping::Advanced, None);
-        
-        let runs: Vec<_> = buffer.layout_runs().collect();
-        assert!(!runs.is_empty());
-        println!("Text buffer created with {} layout runs", runs.len());
-    }
-}
\ No newline at end of file
+    fn test_logical_layout_is_drawn_in_physical_pixels() {
+        let red = |scale: f32| draw_scaled(scale).0.iter().filter(|pixel| **pixel == 0xFF0000).count();
+        assert_eq!(red(1.0), 50);
+        assert_eq!(red(2.0), 200);
+    }
+
+    #[test]
+    fn test_glyphs_are_rasterized_at_the_physical_size() {
+        let text_columns = |scale: f32| {
+            let (buffer, size) = draw_scaled(scale);
+            let dark = |pixel: u32| pixel & 0xFF < 0x80 && pixel != 0xFF0000;
+            (0..size).filter(|x| (0..size).any(|y| dark(buffer[y * size + x]))).count()
+        };
+        let (text_1x, text_2x) = (text_columns(1.0), text_columns(2.0));
+        assert!(text_1x > 0);
+        assert!((text_2x as f32 / text_1x as f32 - 2.0).abs() < 0.5, "{text_1x} vs {text_2x} columns");
+    }
+}
@@ -0,0 +1,615 @@
+//! Glyph atlas: rasterized glyphs packed into shared pages, evicted least recently used.
+//!
+//! Glyphs are cached by their [`CacheKey`] only. Alpha masks store coverage and are
+//! tinted with the text color when they are blitted, so one rasterization serves
+//! every color.
+
+use std::collections::{BTreeMap, HashMap};
+
+use cosmic_text::{CacheKey, CacheKeyFlags, FontSystem, Placement};
+use swash::scale::image::{Content, Image};
+use swash::scale::{Render, ScaleContext, Source, StrikeWith};
+use swash::zeno::{Angle, Format, Transform, Vector};
+
+/// Width and height of an atlas page in pixels.
+const PAGE_SIZE: u32 = 512;
+
+/// Default memory budget for the pages of the glyph atlas: 4 MiB.
+pub const DEFAULT_GLYPH_CACHE_BUDGET: usize = 4 * 1024 * 1024;
+
+/// Memory usage and effectiveness of the glyph cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GlyphCacheStats {
+    /// Number of cached glyphs.
+    pub glyphs: usize,
+    /// Bytes of the atlas pages holding the glyph bitmaps.
+    pub bytes: usize,
+    /// Bytes of glyph bitmaps packed in the pages.
+    pub glyph_bytes: usize,
+    /// Maximum bytes of atlas pages before the least recently used glyphs are evicted.
+    pub budget: usize,
+    /// Number of atlas pages.
+    pub pages: usize,
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups that had to rasterize the glyph.
+    pub misses: u64,
+    /// Glyphs evicted to stay within the budget.
+    pub evictions: u64,
+}
+
+/// How glyph outlines are anti-aliased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAntialiasing {
+    /// One coverage value per pixel. Looks right on any display and under any transform.
+    #[default]
+    Grayscale,
+    /// One coverage value per red, green and blue subpixel (horizontal RGB stripes), for
+    /// sharper text on LCD panels. Falls back to grayscale over translucent pixels.
+    Subpixel,
+}
+
+/// How the pixels of a cached glyph are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// One coverage byte per pixel, tinted with the text color.
+    Mask,
+    /// Premultiplied RGBA (color emoji, COLR layers); not tinted.
+    Color,
+    /// One coverage byte per RGB subpixel plus padding, tinted with the text color.
+    Subpixel,
+}
+
+impl Kind {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            Kind::Mask => 1,
+            Kind::Color | Kind::Subpixel => 4,
+        }
+    }
+}
+
+/// A cached glyph, borrowed from its atlas page.
+pub(crate) struct Glyph<'a> {
+    pub kind: Kind,
+    pub placement: Placement,
+    page: &'a Page,
+    x: u32,
+    y: u32,
+}
+
+impl Glyph<'_> {
+    /// Returns the bytes of pixel (`x`, `y`) of the glyph.
+    pub fn pixel(&self, x: u32, y: u32) -> &[u8] {
+        let bpp = self.kind.bytes_per_pixel();
+        let start = ((self.y + y) as usize * self.page.width as usize + (self.x + x) as usize) * bpp;
+        &self.page.data[start..start + bpp]
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    kind: Kind,
+    placement: Placement,
+    /// `None` for glyphs without pixels, like spaces.
+    slot: Option<Slot>,
+    last_used: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    page: usize,
+    shelf: usize,
+    x: u32,
+    width: u32,
+}
+
+/// A page of the atlas, packed with rows ("shelves") of glyphs of similar height.
+#[derive(Debug)]
+struct Page {
+    bytes_per_pixel: usize,
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    shelves: Vec<Shelf>,
+    glyphs: usize,
+}
+
+#[derive(Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    cursor: u32,
+    /// Freed horizontal spans (x, width) below the cursor that can be reused.
+    free: Vec<(u32, u32)>,
+}
+
+impl Page {
+    fn new(bytes_per_pixel: usize, width: u32, height: u32) -> Self {
+        Self {
+            bytes_per_pixel,
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * bytes_per_pixel],
+            shelves: Vec::new(),
+            glyphs: 0,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Finds room for a `width` x `height` glyph, returning (shelf, x).
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(usize, u32)> {
+        // Reuse a shelf that is tall enough without wasting too much height.
+        let fits = |shelf: &Shelf| shelf.height >= height && shelf.height <= height + height / 2 + 2;
+
+        for (index, shelf) in self.shelves.iter_mut().enumerate().filter(|(_, shelf)| fits(shelf)) {
+            if let Some(i) = shelf.free.iter().position(|(_, free)| *free >= width) {
+                let (x, free) = shelf.free.swap_remove(i);
+                if free > width {
+                    shelf.free.push((x + width, free - width));
+                }
+                self.glyphs += 1;
+                return Some((index, x));
+            }
+            if shelf.cursor + width <= self.width {
+                let x = shelf.cursor;
+                shelf.cursor += width;
+                self.glyphs += 1;
+                return Some((index, x));
+            }
+        }
+
+        let y = self.shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+        if y + height > self.height || width > self.width {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            y,
+            height,
+            cursor: width,
+            free: Vec::new(),
+        });
+        self.glyphs += 1;
+        Some((self.shelves.len() - 1, 0))
+    }
+
+    fn free(&mut self, slot: Slot) {
+        self.glyphs -= 1;
+        if self.glyphs == 0 {
+            // An empty page is repacked from scratch.
+            self.shelves.clear();
+            return;
+        }
+
+        // Neighbouring free spans merge, and a span reaching the cursor gives it back.
+        let shelf = &mut self.shelves[slot.shelf];
+        let (mut x, mut width) = (slot.x, slot.width);
+        shelf.free.retain(|&(free_x, free_width)| {
+            if free_x + free_width == x {
+                x = free_x;
+                width += free_width;
+                false
+            } else if x + width == free_x {
+                width += free_width;
+                false
+            } else {
+                true
+            }
+        });
+        if x + width == shelf.cursor {
+            shelf.cursor = x;
+        } else {
+            shelf.free.push((x, width));
+        }
+
+        // Empty shelves at the bottom give their height back for any glyph size.
+        while self.shelves.last().is_some_and(|shelf| shelf.cursor == 0) {
+            self.shelves.pop();
+        }
+    }
+}
+
+/// Cache of rasterized glyphs with a byte budget and least-recently-used eviction.
+///
+/// The budget bounds the memory of the atlas pages, not just the glyphs packed in them.
+pub(crate) struct GlyphAtlas {
+    context: ScaleContext,
+    antialiasing: TextAntialiasing,
+    entries: HashMap<CacheKey, Entry>,
+    /// Glyphs by the tick they were last used, oldest first.
+    recency: BTreeMap<u64, CacheKey>,
+    pages: Vec<Page>,
+    clock: u64,
+    /// Bytes of glyph bitmaps in the pages.
+    glyph_bytes: usize,
+    budget: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl GlyphAtlas {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            context: ScaleContext::new(),
+            antialiasing: TextAntialiasing::default(),
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            pages: Vec::new(),
+            clock: 0,
+            glyph_bytes: 0,
+            budget,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Returns the glyph, rasterizing and caching it on first use.
+    pub fn get(
+        &mut self,
+        cache_key: CacheKey,
+        font_system: &mut FontSystem,
+    ) -> Option<Glyph<'_>> {
+        self.clock += 1;
+
+        if let Some(entry) = self.entries.get_mut(&cache_key) {
+            self.recency.remove(&entry.last_used);
+            self.recency.insert(self.clock, cache_key);
+            entry.last_used = self.clock;
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            self.insert(cache_key, font_system)?;
+        }
+
+        let entry = &self.entries[&cache_key];
+        let slot = entry.slot?;
+        let page = &self.pages[slot.page];
+        Some(Glyph {
+            kind: entry.kind,
+            placement: entry.placement,
+            page,
+            x: slot.x,
+            y: page.shelves[slot.shelf].y,
+        })
+    }
+
+    fn insert(&mut self, cache_key: CacheKey, font_system: &mut FontSystem) -> Option<()> {
+        let mut image = rasterize(&mut self.context, font_system, cache_key, self.antialiasing)?;
+        let kind = match image.content {
+            Content::Mask => Kind::Mask,
+            Content::Color => Kind::Color,
+            Content::SubpixelMask => Kind::Subpixel,
+        };
+        // Bitmap strikes (e.g. PNG emoji) decode to straight alpha, while COLR layers are
+        // already composited premultiplied.
+        if kind == Kind::Color && matches!(image.source, Source::ColorBitmap(_)) {
+            premultiply(&mut image.data);
+        }
+        let (width, height) = (image.placement.width, image.placement.height);
+        let bpp = kind.bytes_per_pixel();
+        let size = width as usize * height as usize * bpp;
+
+        let slot = if size == 0 || image.data.len() < size {
+            None
+        } else {
+            let slot = self.allocate(bpp, width, height);
+            let page = &mut self.pages[slot.page];
+            let y = page.shelves[slot.shelf].y;
+            let row = width as usize * bpp;
+            for (i, source) in image.data[..size].chunks_exact(row).enumerate() {
+                let start = ((y as usize + i) * page.width as usize + slot.x as usize) * bpp;
+                page.data[start..start + row].copy_from_slice(source);
+            }
+            self.glyph_bytes += size;
+            Some(slot)
+        };
+
+        self.recency.insert(self.clock, cache_key);
+        self.entries.insert(
+            cache_key,
+            Entry {
+                kind,
+                placement: image.placement,
+                slot,
+                last_used: self.clock,
+            },
+        );
+        Some(())
+    }
+
+    /// Finds room for a glyph, evicting least recently used glyphs rather than adding
+    /// a page past the budget.
+    fn allocate(&mut self, bytes_per_pixel: usize, width: u32, height: u32) -> Slot {
+        loop {
+            for (index, page) in self.pages.iter_mut().enumerate() {
+                if page.bytes_per_pixel == bytes_per_pixel {
+                    if let Some((shelf, x)) = page.allocate(width, height) {
+                        return Slot { page: index, shelf, x, width };
+                    }
+                }
+            }
+
+            // Glyphs larger than a page get a page of their own.
+            let page_bytes = PAGE_SIZE.max(width) as usize * PAGE_SIZE.max(height) as usize * bytes_per_pixel;
+            let fits = |atlas: &Self| atlas.page_bytes() + page_bytes <= atlas.budget;
+            if fits(self) || !self.evict_until(fits, bytes_per_pixel) {
+                break;
+            }
+        }
+
+        let mut page = Page::new(bytes_per_pixel, PAGE_SIZE.max(width), PAGE_SIZE.max(height));
+        let (shelf, x) = page.allocate(width, height).expect("a new page fits the glyph");
+        self.pages.push(page);
+        Slot {
+            page: self.pages.len() - 1,
+            shelf,
+            x,
+            width,
+        }
+    }
+
+    /// Evicts least recently used glyphs until `done` holds or a glyph with
+    /// `bytes_per_pixel` frees room that may fit a new one. Returns whether anything
+    /// was evicted.
+    fn evict_until(&mut self, done: impl Fn(&Self) -> bool, bytes_per_pixel: usize) -> bool {
+        let mut evicted = false;
+        while !done(self) {
+            let Some((_, key)) = self.recency.pop_first() else { break };
+            let freed = self.remove(&key);
+            self.evictions += 1;
+            evicted = true;
+            if freed == Some(bytes_per_pixel) {
+                break;
+            }
+        }
+        evicted
+    }
+
+    /// Removes a glyph, dropping its page once empty. Returns the bytes per pixel of
+    /// the page it was freed from.
+    fn remove(&mut self, key: &CacheKey) -> Option<usize> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        let slot = entry.slot?;
+        self.glyph_bytes -= slot.width as usize * entry.placement.height as usize * entry.kind.bytes_per_pixel();
+        let page = &mut self.pages[slot.page];
+        page.free(slot);
+        let bytes_per_pixel = page.bytes_per_pixel;
+
+        if page.glyphs == 0 {
+            self.pages.remove(slot.page);
+            for entry in self.entries.values_mut() {
+                if let Some(other) = entry.slot.as_mut().filter(|other| other.page > slot.page) {
+                    other.page -= 1;
+                }
+            }
+        }
+        Some(bytes_per_pixel)
+    }
+
+    fn page_bytes(&self) -> usize {
+        self.pages.iter().map(Page::bytes).sum()
+    }
+
+    /// Sets how new glyphs are anti-aliased, dropping every glyph rasterized differently.
+    pub fn set_antialiasing(&mut self, antialiasing: TextAntialiasing) {
+        if antialiasing != self.antialiasing {
+            self.antialiasing = antialiasing;
+            self.entries.clear();
+            self.recency.clear();
+            self.pages.clear();
+            self.glyph_bytes = 0;
+        }
+    }
+
+    pub fn antialiasing(&self) -> TextAntialiasing {
+        self.antialiasing
+    }
+
+    /// Sets the memory budget, evicting glyphs until their pages fit in it.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        while self.page_bytes() > self.budget {
+            let Some((_, key)) = self.recency.pop_first() else { break };
+            self.remove(&key);
+            self.evictions += 1;
+        }
+    }
+
+    pub fn stats(&self) -> GlyphCacheStats {
+        GlyphCacheStats {
+            glyphs: self.entries.len(),
+            bytes: self.page_bytes(),
+            glyph_bytes: self.glyph_bytes,
+            budget: self.budget,
+            pages: self.pages.len(),
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+        }
+    }
+}
+
+/// Rasterizes a glyph like cosmic-text's `SwashCache`, but in the requested format.
+fn rasterize(
+    context: &mut ScaleContext,
+    font_system: &mut FontSystem,
+    cache_key: CacheKey,
+    antialiasing: TextAntialiasing,
+) -> Option<Image> {
+    let font = font_system.get_font(cache_key.font_id, cache_key.font_weight)?;
+    let swash_font = font.as_swash();
+    let weight = swash::Tag::from_be_bytes(*b"wght");
+
+    let mut builder = context
+        .builder(swash_font)
+        .size(f32::from_bits(cache_key.font_size_bits))
+        .hint(!cache_key.flags.contains(CacheKeyFlags::DISABLE_HINTING));
+    if let Some(variation) = swash_font.variations().find_by_tag(weight) {
+        builder = builder.variations(std::iter::once(swash::Setting {
+            tag: weight,
+            value: f32::from(cache_key.font_weight.0).clamp(variation.min_value(), variation.max_value()),
+        }));
+    }
+    let mut scaler = builder.build();
+
+    let offset = if cache_key.flags.contains(CacheKeyFlags::PIXEL_FONT) {
+        Vector::new(cache_key.x_bin.as_float().round() + 1.0, cache_key.y_bin.as_float().round())
+    } else {
+        Vector::new(cache_key.x_bin.as_float(), cache_key.y_bin.as_float())
+    };
+    let format = match antialiasing {
+        TextAntialiasing::Grayscale => Format::Alpha,
+        TextAntialiasing::Subpixel => Format::Subpixel,
+    };
+
+    Render::new(&[
+        Source::ColorOutline(0),
+        Source::ColorBitmap(StrikeWith::BestFit),
+        Source::Outline,
+    ])
+    .format(format)
+    .offset(offset)
+    .transform(
+        cache_key
+            .flags
+            .contains(CacheKeyFlags::FAKE_ITALIC)
+            .then(|| Transform::skew(Angle::from_degrees(14.0), Angle::from_degrees(0.0))),
+    )
+    .render(&mut scaler, cache_key.glyph_id)
+}
+
+/// Premultiplies straight-alpha RGBA8 pixels in place.
+fn premultiply(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_text::{Attrs, Buffer, Metrics, Shaping};
+
+    use super::*;
+    use crate::core::background::Color;
+    use crate::core::text::Text;
+    use crate::layout::{Rectangle, Size};
+    use crate::render::{bundled_font_system, Renderer};
+    use crate::View;
+
+    /// Shapes `content` with the bundled font and returns the keys of its glyphs.
+    fn glyphs(font_system: &mut FontSystem, content: &str, size: f32) -> Vec<CacheKey> {
+        let mut buffer = Buffer::new(font_system, Metrics::new(size, size));
+        buffer.set_text(font_system, content, &Attrs::new(), Shaping::Advanced, None);
+        buffer.shape_until_scroll(font_system, false);
+        buffer
+            .layout_runs()
+            .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.physical((0.0, 0.0), 1.0).cache_key))
+            .collect()
+    }
+
+    #[test]
+    fn test_masks_are_shared_across_colors() {
+        use crate::core::renderer::Renderer as _;
+        use crate::core::text::Renderer as _;
+
+        let mut renderer = Renderer::with_bundled_fonts();
+        let mut buffer = vec![0u32; 200 * 40];
+        let mut draw = |renderer: &mut Renderer, color: Color| {
+            renderer.reset(Rectangle::new(0.0, 0.0, 200.0, 40.0));
+            renderer.fill_text(Text {
+                size: 20.0,
+                color,
+                ..Text::new("abcdef", Rectangle::new(0.0, 0.0, 200.0, 40.0))
+            });
+            renderer.present(&mut buffer, 200, 40, 0x00FF_FFFF);
+        };
+
+        draw(&mut renderer, Color::new(1.0, 0.0, 0.0, 1.0));
+        let first = renderer.glyph_cache_stats();
+        assert_eq!((first.glyphs, first.misses, first.hits), (6, 6, 0));
+        assert_eq!(first.pages, 1);
+        assert!(0 < first.glyph_bytes && first.glyph_bytes < first.bytes);
+
+        draw(&mut renderer, Color::new(0.0, 0.0, 1.0, 1.0));
+        let second = renderer.glyph_cache_stats();
+        assert_eq!((second.glyphs, second.misses, second.hits), (6, 6, 6), "a new color reuses the masks");
+    }
+
+    #[test]
+    fn test_budget_bounds_pages_and_evicts_least_recently_used() {
+        let mut font_system = bundled_font_system().write().unwrap();
+        let page = (PAGE_SIZE * PAGE_SIZE) as usize;
+        let mut atlas = GlyphAtlas::new(2 * page);
+
+        // Large glyphs fill more than two pages
+        let keys = glyphs(&mut font_system, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz", 160.0);
+        for key in &keys {
+            atlas.get(*key, &mut font_system);
+            atlas.get(keys[0], &mut font_system);
+        }
+        let stats = atlas.stats();
+        assert!(stats.evictions > 0);
+        assert!(stats.pages <= 2 && stats.bytes <= 2 * page, "{stats:?}");
+
+        // The glyph used throughout survives
+        let misses = stats.misses;
+        atlas.get(keys[0], &mut font_system);
+        assert_eq!(atlas.stats().misses, misses);
+
+        // A smaller budget drops whole pages
+        atlas.set_budget(page);
+        let stats = atlas.stats();
+        assert!(stats.pages <= 1 && stats.bytes <= page, "{stats:?}");
+        atlas.set_budget(0);
+        assert_eq!((atlas.stats().glyphs, atlas.stats().pages, atlas.stats().glyph_bytes), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_freed_spans_merge_and_return_to_the_shelf() {
+        let mut page = Page::new(1, 64, 64);
+        let slots: Vec<Slot> = (0..4)
+            .map(|_| {
+                let (shelf, x) = page.allocate(10, 8).unwrap();
+                Slot { page: 0, shelf, x, width: 10 }
+            })
+            .collect();
+        assert_eq!(page.shelves[0].cursor, 40);
+
+        page.free(slots[1]);
+        page.free(slots[0]);
+        assert_eq!(page.shelves[0].free, vec![(0, 20)]);
+        page.free(slots[3]);
+        assert_eq!(page.shelves[0].cursor, 30);
+
+        // The merged span holds a glyph wider than either freed one
+        assert_eq!(page.allocate(20, 8), Some((0, 0)));
+        assert!(page.shelves[0].free.is_empty());
+    }
+
+    /// Renders "Hillo" in black on gray and counts the pixels that are not gray.
+    fn colored_pixels(antialiasing: TextAntialiasing) -> usize {
+        let mut renderer = Renderer::with_bundled_fonts();
+        renderer.set_text_antialiasing(antialiasing);
+        let image = renderer.render_to_image(&View::text("Hillo"), Size::new(80.0, 30.0), 1.0);
+        image.pixels().chunks_exact(4).filter(|p| p[0] != p[1] || p[1] != p[2]).count()
+    }
+
+    #[test]
+    fn test_grayscale_text_on_gray_stays_gray() {
+        assert_eq!(colored_pixels(TextAntialiasing::Grayscale), 0);
+    }
+
+    #[test]
+    fn test_subpixel_text_has_per_channel_coverage_on_stem_edges() {
+        assert!(colored_pixels(TextAntialiasing::Subpixel) > 20);
+    }
+}
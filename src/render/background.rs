@@ -0,0 +1,317 @@
+//! Build tiny-skia paints for [`Background`] fills.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use tiny_skia::{GradientStop, Paint, Pixmap, PremultipliedColorU8, Shader, SpreadMode};
+
+use crate::core::background::{Background, Color};
+use crate::core::gradient::{AngularGradient, Gradient};
+use crate::core::point::Point;
+use crate::core::transformation::Transformation;
+use crate::layout::Rectangle;
+use crate::render::quad::to_color;
+
+/// Angular gradients rasterized up front, reused while they are drawn at the same size.
+#[derive(Default)]
+pub(crate) struct GradientCache {
+    entries: HashMap<u64, Rasterized>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Usage of the cache of rasterized angular gradients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GradientCacheStats {
+    /// Number of rasterized gradients kept.
+    pub entries: usize,
+    /// Draws served from the cache.
+    pub hits: u64,
+    /// Draws that had to rasterize the gradient.
+    pub misses: u64,
+}
+
+struct Rasterized {
+    angular: AngularGradient,
+    size: (u32, u32, u32),
+    pixmap: Option<(Pixmap, tiny_skia::Transform)>,
+    used: bool,
+}
+
+impl GradientCache {
+    /// Returns the pixmap of `angular` filling `bounds` at the scale of `transformation`,
+    /// rasterizing it on a miss.
+    fn angular(
+        &mut self,
+        angular: &AngularGradient,
+        bounds: Rectangle,
+        transformation: Transformation,
+    ) -> Option<&(Pixmap, tiny_skia::Transform)> {
+        let scale = transformation.scale_factor().max(f32::EPSILON);
+        let size = (bounds.width.to_bits(), bounds.height.to_bits(), scale.to_bits());
+
+        let mut hasher = DefaultHasher::new();
+        size.hash(&mut hasher);
+        let point = |point: Point| (point.x.to_bits(), point.y.to_bits());
+        (point(angular.center), angular.start_angle.to_bits(), angular.end_angle.to_bits()).hash(&mut hasher);
+        for stop in angular.gradient.stops.iter() {
+            let color = stop.color;
+            (stop.offset.to_bits(), [color.r, color.g, color.b, color.a].map(f32::to_bits)).hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+
+        let rasterize = || Rasterized {
+            angular: angular.clone(),
+            size,
+            pixmap: angular_pixmap(angular, Rectangle::new(0.0, 0.0, bounds.width, bounds.height), scale),
+            used: false,
+        };
+        let entry = match self.entries.entry(hash) {
+            // A hash collision replaces the other gradient
+            Entry::Occupied(entry) if entry.get().angular == *angular && entry.get().size == size => {
+                self.hits += 1;
+                entry.into_mut()
+            }
+            Entry::Occupied(mut entry) => {
+                self.misses += 1;
+                entry.insert(rasterize());
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => {
+                self.misses += 1;
+                entry.insert(rasterize())
+            }
+        };
+        entry.used = true;
+        entry.pixmap.as_ref()
+    }
+
+    pub fn stats(&self) -> GradientCacheStats {
+        GradientCacheStats {
+            entries: self.entries.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+
+    /// Evicts gradients not drawn since the last trim.
+    pub fn trim(&mut self) {
+        self.entries.retain(|_, entry| std::mem::take(&mut entry.used));
+    }
+}
+
+/// Calls `f` with a paint filling `bounds` (in local coordinates) with `background`.
+///
+/// Gradients are positioned relative to `bounds`. The transformation is only used
+/// to pick the resolution of gradients that have to be rasterized up front, which
+/// are kept in `gradients`.
+pub(crate) fn with_paint<R>(
+    background: &Background,
+    bounds: Rectangle,
+    transformation: Transformation,
+    gradients: &mut GradientCache,
+    f: impl FnOnce(&Paint) -> R,
+) -> R {
+    let mut paint = Paint::default();
+
+    match background {
+        Background::Color(color) => {
+            paint.set_color(to_color(*color));
+            f(&paint)
+        }
+        Background::LinearGradient(linear) => {
+            let start = unit_to_local(bounds, linear.start.x, linear.start.y);
+            let end = unit_to_local(bounds, linear.end.x, linear.end.y);
+            paint.shader = tiny_skia::LinearGradient::new(
+                start,
+                end,
+                stops(&linear.gradient, |offset| offset),
+                SpreadMode::Pad,
+                tiny_skia::Transform::identity(),
+            )
+            .unwrap_or_else(|| solid(&linear.gradient));
+            f(&paint)
+        }
+        Background::RadialGradient(radial) => {
+            let center = unit_to_local(bounds, radial.center.x, radial.center.y);
+            let (start_radius, end_radius) = (radial.start_radius.max(0.0), radial.end_radius);
+
+            // tiny-skia radial gradients always start at the center, so stops are
+            // remapped from [start_radius, end_radius] to [0, end_radius].
+            paint.shader = if end_radius > start_radius {
+                tiny_skia::RadialGradient::new(
+                    center,
+                    center,
+                    end_radius,
+                    stops(&radial.gradient, |offset| {
+                        (start_radius + offset * (end_radius - start_radius)) / end_radius
+                    }),
+                    SpreadMode::Pad,
+                    tiny_skia::Transform::identity(),
+                )
+                .unwrap_or_else(|| solid(&radial.gradient))
+            } else {
+                solid(&radial.gradient)
+            };
+            f(&paint)
+        }
+        Background::AngularGradient(angular) => {
+            let Some((pixmap, transform)) = gradients.angular(angular, bounds, transformation) else {
+                paint.shader = solid(&angular.gradient);
+                return f(&paint);
+            };
+            // The pixmap is rasterized at the origin, so it is moved to the bounds
+            paint.shader = tiny_skia::Pattern::new(
+                pixmap.as_ref(),
+                SpreadMode::Pad,
+                tiny_skia::FilterQuality::Bilinear,
+                1.0,
+                transform.post_translate(bounds.x, bounds.y),
+            );
+            f(&paint)
+        }
+    }
+}
+
+fn unit_to_local(bounds: Rectangle, x: f32, y: f32) -> tiny_skia::Point {
+    tiny_skia::Point::from_xy(bounds.x + x * bounds.width, bounds.y + y * bounds.height)
+}
+
+fn stops(gradient: &Gradient, remap: impl Fn(f32) -> f32) -> Vec<GradientStop> {
+    gradient
+        .stops
+        .iter()
+        .map(|stop| GradientStop::new(remap(stop.offset), to_color(stop.color)))
+        .collect()
+}
+
+/// Fallback for degenerate gradients: the color of the last stop.
+fn solid(gradient: &Gradient) -> Shader<'static> {
+    let color = gradient.stops.last().map(|stop| stop.color).unwrap_or(Color::TRANSPARENT);
+    Shader::SolidColor(to_color(color))
+}
+
+/// tiny-skia has no sweep gradient, so angular gradients are rasterized into a
+/// pixmap at device resolution and used as a pattern.
+fn angular_pixmap(angular: &AngularGradient, bounds: Rectangle, scale: f32) -> Option<(Pixmap, tiny_skia::Transform)> {
+    let width = (bounds.width * scale).ceil() as u32;
+    let height = (bounds.height * scale).ceil() as u32;
+    let mut pixmap = Pixmap::new(width, height)?;
+
+    let span = angular.end_angle - angular.start_angle;
+    if span <= 0.0 {
+        return None;
+    }
+    let center_x = angular.center.x * bounds.width;
+    let center_y = angular.center.y * bounds.height;
+
+    for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = ((i as u32 % width) as f32 + 0.5) / scale - center_x;
+        let y = ((i as u32 / width) as f32 + 0.5) / scale - center_y;
+
+        // y grows downwards, so atan2 increases clockwise
+        let angle = (y.atan2(x) - angular.start_angle).rem_euclid(std::f32::consts::TAU);
+        let offset = if angle <= span {
+            angle / span
+        } else if angle - span < std::f32::consts::TAU - angle {
+            1.0
+        } else {
+            0.0
+        };
+
+        let color = to_color(angular.gradient.sample(offset)).premultiply().to_color_u8();
+        *pixel = PremultipliedColorU8::from_rgba(color.red(), color.green(), color.blue(), color.alpha())
+            .unwrap_or(PremultipliedColorU8::TRANSPARENT);
+    }
+
+    let transform = tiny_skia::Transform::from_row(1.0 / scale, 0.0, 0.0, 1.0 / scale, bounds.x, bounds.y);
+    Some((pixmap, transform))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gradient::{LinearGradient, RadialGradient};
+    use crate::core::renderer::{Quad, Renderer as _};
+    use crate::render::{GradientCacheStats, Renderer};
+
+    const BLACK_TO_WHITE: [Color; 2] = [Color::BLACK, Color::WHITE];
+
+    /// Presents a frame of 20x20 pixels with a quad for each of `quads`, and returns
+    /// the blue channel of every pixel.
+    fn present(renderer: &mut Renderer, quads: &[(Rectangle, Background)]) -> Vec<u32> {
+        renderer.reset(Rectangle::new(0.0, 0.0, 20.0, 20.0));
+        for (bounds, background) in quads {
+            renderer.fill_quad(Quad::new(*bounds), background.clone());
+        }
+        let mut buffer = vec![0u32; 20 * 20];
+        renderer.present(&mut buffer, 20, 20, 0);
+        buffer.iter().map(|pixel| pixel & 0xFF).collect()
+    }
+
+    /// Fills the whole frame with `background` and returns the blue channel at (`x`, `y`).
+    fn luma(background: impl Into<Background>) -> impl Fn(usize, usize) -> u32 {
+        let luma = present(&mut Renderer::new(), &[(Rectangle::new(0.0, 0.0, 20.0, 20.0), background.into())]);
+        move |x, y| luma[y * 20 + x]
+    }
+
+    fn angular() -> AngularGradient {
+        AngularGradient::full(BLACK_TO_WHITE.to_vec(), Point::new(0.5, 0.5))
+    }
+
+    #[test]
+    fn test_linear_gradient_runs_along_its_axis() {
+        let luma = luma(LinearGradient::vertical(BLACK_TO_WHITE.to_vec()));
+        assert!(luma(10, 0) < 0x10);
+        assert!(luma(10, 19) > 0xF0);
+        assert!(luma(10, 5) < luma(10, 14));
+    }
+
+    #[test]
+    fn test_radial_gradient_is_padded_past_its_end_radius() {
+        let luma = luma(RadialGradient::new(BLACK_TO_WHITE.to_vec(), Point::new(0.5, 0.5), 0.0, 10.0));
+        assert!(luma(10, 10) < 0x20);
+        assert!(luma(0, 0) > 0xF0);
+    }
+
+    #[test]
+    fn test_angular_gradient_starts_at_the_trailing_edge_and_sweeps_clockwise() {
+        let luma = luma(angular());
+        assert!(luma(19, 11) < 0x20);
+        assert!(luma(10, 19) < luma(0, 10));
+        assert!(luma(0, 10) < luma(10, 0));
+    }
+
+    #[test]
+    fn test_angular_gradient_is_rasterized_at_the_origin_and_moved_to_its_quad() {
+        let mut renderer = Renderer::new();
+        let luma = present(&mut renderer, &[(Rectangle::new(10.0, 10.0, 10.0, 10.0), angular().into())]);
+        let luma = |x: usize, y: usize| luma[y * 20 + x];
+        assert!(luma(19, 15) < 0x20);
+        assert!(luma(15, 19) < luma(10, 15));
+        assert!(luma(10, 15) < luma(15, 10));
+        assert_eq!(renderer.gradient_cache_stats(), GradientCacheStats { entries: 1, hits: 0, misses: 1 });
+    }
+
+    #[test]
+    fn test_angular_gradient_is_reused_wherever_it_is_drawn_at_the_same_size() {
+        let mut renderer = Renderer::new();
+        let quads = [
+            (Rectangle::new(0.0, 0.0, 10.0, 10.0), angular().into()),
+            (Rectangle::new(10.0, 10.0, 10.0, 10.0), angular().into()),
+        ];
+        let luma = present(&mut renderer, &quads);
+        assert_eq!(renderer.gradient_cache_stats(), GradientCacheStats { entries: 1, hits: 1, misses: 1 });
+        assert_eq!(luma[5 * 20 + 9], luma[15 * 20 + 19]);
+    }
+
+    #[test]
+    fn test_angular_gradient_is_evicted_by_a_frame_without_it() {
+        let mut renderer = Renderer::new();
+        present(&mut renderer, &[(Rectangle::new(0.0, 0.0, 10.0, 10.0), angular().into())]);
+        present(&mut renderer, &[(Rectangle::new(0.0, 0.0, 10.0, 10.0), Color::WHITE.into())]);
+        assert_eq!(renderer.gradient_cache_stats().entries, 0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Premultiplied-alpha compositing on RGBA8 pixels.
+//!
+//! The canvas stores premultiplied RGBA, the same layout tiny-skia uses, so glyph
+//! blits and tiny-skia fills compose with identical source-over semantics.
+
+use std::sync::OnceLock;
+
+/// A premultiplied RGBA color with channels in `0.0..=255.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Premultiplied(pub [f32; 4]);
+
+impl Premultiplied {
+    /// Premultiplies straight-alpha 8-bit channels, scaled by `opacity` (0.0..=1.0).
+    pub(crate) fn from_straight(rgba: [u8; 4], opacity: f32) -> Self {
+        let alpha = rgba[3] as f32 / 255.0 * opacity.clamp(0.0, 1.0);
+        Self([
+            rgba[0] as f32 * alpha,
+            rgba[1] as f32 * alpha,
+            rgba[2] as f32 * alpha,
+            alpha * 255.0,
+        ])
+    }
+
+    /// Scales already premultiplied 8-bit channels by `opacity` (0.0..=1.0).
+    pub(crate) fn from_premultiplied(rgba: [u8; 4], opacity: f32) -> Self {
+        let opacity = opacity.clamp(0.0, 1.0);
+        Self(rgba.map(|channel| channel as f32 * opacity))
+    }
+
+    /// Returns whether the color leaves the destination untouched.
+    pub(crate) fn is_transparent(&self) -> bool {
+        self.0[3] <= 0.0
+    }
+}
+
+/// Composites `src` over the premultiplied RGBA pixel `dst` (Porter-Duff source-over).
+pub(crate) fn source_over(dst: &mut [u8], src: Premultiplied) {
+    let inv_alpha = 1.0 - src.0[3] / 255.0;
+    for (dst, src) in dst.iter_mut().zip(src.0) {
+        *dst = (src + *dst as f32 * inv_alpha).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Blends an opaque text `color` over the premultiplied pixel `dst` with one coverage
+/// value (0.0..=1.0) per red, green and blue channel.
+///
+/// Over opaque pixels the blend happens in linear light, so anti-aliased edges keep
+/// the weight of the glyph instead of looking too thin on light backgrounds and too
+/// bold on dark ones. Over translucent pixels (e.g. inside an opacity group) there is
+/// no final background to blend with yet, so the average coverage is composited with
+/// plain source-over.
+pub(crate) fn blend_text(dst: &mut [u8], color: [u8; 3], coverage: [f32; 3]) {
+    if coverage.iter().all(|coverage| *coverage <= 0.0) {
+        return;
+    }
+
+    if dst[3] < 255 {
+        let alpha = (coverage[0] + coverage[1] + coverage[2]) / 3.0 * 255.0;
+        source_over(dst, Premultiplied::from_straight([color[0], color[1], color[2], alpha.round() as u8], 1.0));
+        return;
+    }
+
+    for i in 0..3 {
+        let coverage = coverage[i].clamp(0.0, 1.0);
+        let linear = to_linear(color[i]) * coverage + to_linear(dst[i]) * (1.0 - coverage);
+        dst[i] = to_srgb(linear);
+    }
+}
+
+/// Decodes an sRGB channel to linear light in `0.0..=1.0`.
+fn to_linear(channel: u8) -> f32 {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|i| {
+            let c = i as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    })[channel as usize]
+}
+
+/// Encodes linear light in `0.0..=1.0` as an sRGB channel.
+fn to_srgb(linear: f32) -> u8 {
+    const STEPS: usize = 4096;
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+
+    let table = TABLE.get_or_init(|| {
+        (0..=STEPS)
+            .map(|i| {
+                let l = i as f32 / STEPS as f32;
+                let c = if l <= 0.003_130_8 {
+                    l * 12.92
+                } else {
+                    1.055 * l.powf(1.0 / 2.4) - 0.055
+                };
+                (c * 255.0).round().clamp(0.0, 255.0) as u8
+            })
+            .collect()
+    });
+    table[(linear.clamp(0.0, 1.0) * STEPS as f32).round() as usize]
+}
+
+/// Converts a premultiplied RGBA pixel to straight alpha.
+pub(crate) fn demultiply(pixel: [u8; 4]) -> [u8; 4] {
+    let [r, g, b, a] = pixel;
+    match a {
+        0 => [0, 0, 0, 0],
+        255 => pixel,
+        _ => {
+            let scale = 255.0 / a as f32;
+            [
+                (r as f32 * scale).round().min(255.0) as u8,
+                (g as f32 * scale).round().min(255.0) as u8,
+                (b as f32 * scale).round().min(255.0) as u8,
+                a,
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::background::Color;
+    use crate::core::renderer::{Quad, Renderer as _};
+    use crate::layout::Rectangle;
+    use crate::render::Renderer;
+
+    /// Presents half-transparent red over white and returns a pixel of it.
+    fn half_red_over_white() -> u32 {
+        let mut renderer = Renderer::new();
+        renderer.reset(Rectangle::new(0.0, 0.0, 4.0, 4.0));
+        renderer.fill_quad(Quad::new(Rectangle::new(0.0, 0.0, 4.0, 4.0)), Color::new(1.0, 0.0, 0.0, 0.5));
+
+        let mut buffer = vec![0u32; 16];
+        renderer.present(&mut buffer, 4, 4, 0x00FF_FFFF);
+        buffer[5]
+    }
+
+    #[test]
+    fn test_translucent_fill_blends_with_destination() {
+        let pixel = half_red_over_white();
+        let [r, g, b] = [(pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF];
+        assert_eq!(r, 0xFF);
+        assert!((126..=129).contains(&g) && g == b, "{pixel:06x}");
+    }
+
+    #[test]
+    fn test_no_alpha_byte_leaks_into_the_surface() {
+        assert_eq!(half_red_over_white() >> 24, 0);
+    }
+
+    #[test]
+    fn test_opacity_group_composites_children_once() {
+        let mut renderer = Renderer::new();
+        renderer.reset(Rectangle::new(0.0, 0.0, 6.0, 2.0));
+        renderer.with_opacity(0.5, |renderer| {
+            renderer.fill_quad(Quad::new(Rectangle::new(0.0, 0.0, 4.0, 2.0)), Color::BLACK);
+            renderer.fill_quad(Quad::new(Rectangle::new(2.0, 0.0, 4.0, 2.0)), Color::BLACK);
+        });
+
+        let mut buffer = vec![0u32; 12];
+        renderer.present(&mut buffer, 6, 2, 0x00FF_FFFF);
+        assert_eq!(buffer[1], buffer[3], "overlap does not darken inside the group");
+        assert_eq!(buffer[3], buffer[5]);
+        assert!((0x7E..=0x81).contains(&(buffer[3] & 0xFF)), "{:06x}", buffer[3]);
+    }
+
+    #[test]
+    fn test_text_coverage_blends_in_linear_light() {
+        // Half coverage of black over white is half the light, not half the sRGB value.
+        let mut pixel = [255, 255, 255, 255];
+        super::blend_text(&mut pixel, [0, 0, 0], [0.5; 3]);
+        assert!((186..=190).contains(&pixel[0]), "{pixel:?}");
+    }
+}
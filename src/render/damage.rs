@@ -0,0 +1,336 @@
+//! Damage tracking: find the regions that changed between two display lists.
+
+use crate::layout::Rectangle;
+use crate::render::display_list::{Item, Primitive};
+use crate::render::path;
+
+/// Extra pixels around every primitive, covering anti-aliased edges.
+const MARGIN: f32 = 2.0;
+
+/// Rectangles closer than this (in pixels) are merged into one repaint region.
+const MERGE_DISTANCE: f32 = 16.0;
+
+/// Returns the regions (in target pixels) that differ between `previous` and `current`.
+///
+/// Items the two lists start or end with alike are skipped, so inserting or removing
+/// an item only damages that item. The items in between are compared in drawing
+/// order, and any change to an item damages both its old and its new bounds.
+pub(crate) fn diff(previous: &[Item], current: &[Item]) -> Vec<Rectangle> {
+    let prefix = previous.iter().zip(current).take_while(|(old, new)| old == new).count();
+    let (previous, current) = (&previous[prefix..], &current[prefix..]);
+    let suffix = previous.iter().rev().zip(current.iter().rev()).take_while(|(old, new)| old == new).count();
+    let (previous, current) = (&previous[..previous.len() - suffix], &current[..current.len() - suffix]);
+
+    let mut damage = Vec::new();
+    for i in 0..previous.len().max(current.len()) {
+        match (previous.get(i), current.get(i)) {
+            (Some(old), Some(new)) if old == new => {}
+            (old, new) => damage.extend(old.and_then(bounds).into_iter().chain(new.and_then(bounds))),
+        }
+    }
+
+    damage
+}
+
+/// Returns the area touched by an item in target pixels, or `None` if it is fully clipped.
+pub(crate) fn bounds(item: &Item) -> Option<Rectangle> {
+    let local = match &item.primitive {
+        Primitive::Quad { quad, .. } => {
+            let shadow = quad.shadow;
+            let mut bounds = quad.bounds;
+            if shadow.color.a > 0.0 {
+                // The blur is Gaussian with sigma = blur / 2, visible up to 3 sigma.
+                let shadow_bounds = Rectangle::new(
+                    bounds.x + shadow.offset.x,
+                    bounds.y + shadow.offset.y,
+                    bounds.width,
+                    bounds.height,
+                )
+                .expand(shadow.blur * 1.5);
+                bounds = bounds.union(&shadow_bounds);
+            }
+            bounds
+        }
+        // Glyphs reach past the bounds: synthesized italics lean by a quarter of their
+        // ascent, side bearings can be negative and tall glyphs overflow tight lines.
+        // The last line drawn only has to start inside the bounds, so it can end up to
+        // a line below them.
+        Primitive::Text(text) => {
+            let line_height = text.typography.line_height.resolve(text.size);
+            let bounds = text.bounds;
+            Rectangle::new(bounds.x, bounds.y, bounds.width, bounds.height + line_height).expand(text.size / 2.0)
+        }
+        Primitive::Image { bounds, .. } => *bounds,
+        Primitive::Fill { path, .. } => path::bounds(path)?,
+        // Miter joins can reach further than half the width from the outline.
+        Primitive::Stroke { path, stroke } => path::bounds(path)?.expand(stroke.width * 2.0),
+        Primitive::Group { items, .. } => {
+            return items.iter().filter_map(bounds).reduce(|a, b| a.union(&b));
+        }
+    };
+
+    item.transformation
+        .transform_rectangle(local)
+        .expand(MARGIN)
+        .intersection(&item.clip)
+}
+
+/// Merges overlapping or nearby rectangles and snaps them to whole pixels inside `viewport`.
+pub(crate) fn group(mut damage: Vec<Rectangle>, viewport: Rectangle) -> Vec<Rectangle> {
+    let mut merged: Vec<Rectangle> = Vec::with_capacity(damage.len());
+
+    while let Some(mut rect) = damage.pop() {
+        // Growing a rectangle can make it reach earlier ones, so keep merging until stable.
+        while let Some(i) = merged
+            .iter()
+            .position(|other| rect.expand(MERGE_DISTANCE / 2.0).intersection(&other.expand(MERGE_DISTANCE / 2.0)).is_some())
+        {
+            rect = rect.union(&merged.swap_remove(i));
+        }
+        merged.push(rect);
+    }
+
+    merged
+        .into_iter()
+        .filter_map(|rect| snap(rect).intersection(&viewport))
+        .collect()
+}
+
+/// Rounds a rectangle outwards to whole pixels.
+fn snap(rect: Rectangle) -> Rectangle {
+    let x = rect.x.floor();
+    let y = rect.y.floor();
+    Rectangle::new(
+        x,
+        y,
+        (rect.x + rect.width).ceil() - x,
+        (rect.y + rect.height).ceil() - y,
+    )
+}
+
+/// Returns a copy of the items with every clip restricted to `region`.
+///
+/// Items outside the region are dropped.
+pub(crate) fn clip_items(items: &[Item], region: Rectangle) -> Vec<Item> {
+    items
+        .iter()
+        .filter_map(|item| {
+            bounds(item)?.intersection(&region)?;
+            let clip = item.clip.intersection(&region)?;
+            let primitive = match &item.primitive {
+                Primitive::Group { opacity, items } => Primitive::Group {
+                    opacity: *opacity,
+                    items: clip_items(items, region),
+                },
+                primitive => primitive.clone(),
+            };
+            Some(Item {
+                primitive,
+                clip,
+                clip_paths: item.clip_paths.clone(),
+                transformation: item.transformation,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::background::{Background, Color};
+    use crate::core::renderer::{Quad, Renderer as _};
+    use crate::core::text::{Renderer as _, Text};
+    use crate::layout::{layout, Limits};
+    use crate::render::display_list::DisplayList;
+    use crate::render::Renderer;
+    use crate::view::{Shape, Text as TextView, VStack};
+    use crate::View;
+
+    const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
+    const GREEN: Color = Color::new(0.0, 0.6, 0.0, 1.0);
+
+    /// Records a 10px quad at each of `tops`, one below the other.
+    fn column(tops: &[f32]) -> DisplayList {
+        let mut list = DisplayList::new(Rectangle::new(0.0, 0.0, 100.0, 400.0));
+        for top in tops {
+            list.push(Primitive::Quad {
+                quad: Quad { bounds: Rectangle::new(20.0, *top, 10.0, 10.0), ..Quad::default() },
+                background: Background::Color(Color::BLACK),
+            });
+        }
+        list
+    }
+
+    #[test]
+    fn test_changing_an_item_damages_its_old_and_new_bounds() {
+        let damage = diff(column(&[0.0, 20.0, 40.0]).items(), column(&[0.0, 25.0, 40.0]).items());
+        assert_eq!(damage, vec![Rectangle::new(18.0, 18.0, 14.0, 14.0), Rectangle::new(18.0, 23.0, 14.0, 14.0)]);
+    }
+
+    #[test]
+    fn test_inserting_or_removing_an_item_damages_only_that_item() {
+        let tops: Vec<f32> = (0..20).map(|i| i as f32 * 20.0).collect();
+        let mut inserted = tops.clone();
+        inserted.insert(1, 10.0);
+        let (before, after) = (column(&tops), column(&inserted));
+
+        let expected = vec![Rectangle::new(18.0, 8.0, 14.0, 14.0)];
+        assert_eq!(diff(before.items(), after.items()), expected);
+        assert_eq!(diff(after.items(), before.items()), expected);
+    }
+
+    #[test]
+    fn test_text_damage_covers_a_last_line_overflowing_its_bounds() {
+        // The second line starts inside the 20px tall bounds and is drawn below them.
+        let text = |color: Color| Text { color, ..Text::new("one\ntwo", Rectangle::new(10.0, 10.0, 80.0, 20.0)) };
+        let present = |renderer: &mut Renderer, color: Color, buffer: &mut [u32]| {
+            renderer.reset(Rectangle::new(0.0, 0.0, 200.0, 200.0));
+            renderer.fill_text(text(color));
+            renderer.present(buffer, 200, 200, 0x00FF_FFFF);
+        };
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+
+        let mut renderer = Renderer::with_bundled_fonts();
+        let mut buffer = vec![0u32; 200 * 200];
+        present(&mut renderer, Color::BLACK, &mut buffer);
+        assert!(buffer[30 * 200..].iter().any(|pixel| *pixel != 0x00FF_FFFF), "the second line is drawn");
+        present(&mut renderer, red, &mut buffer);
+        assert!(renderer.damage()[0].area() < 200.0 * 200.0 / 2.0, "{:?}", renderer.damage());
+
+        let mut expected = vec![0u32; 200 * 200];
+        present(&mut Renderer::with_bundled_fonts(), red, &mut expected);
+        assert!(buffer == expected, "glyphs of the old color are left below the bounds");
+    }
+
+    /// A line of text, a circle of `color` and a blue bar, spaced out in 100x100 pixels.
+    fn circle(color: Color) -> View {
+        View::from(
+            VStack::new(vec![
+                View::from(TextView::new("Damage")),
+                View::from(Shape::circle().fill(color).frame(20.0, 20.0)),
+                View::from(Shape::rectangle().fill(Color::new(0.0, 0.0, 1.0, 1.0)).frame(60.0, 10.0)),
+            ])
+            .spacing(20.0)
+            .padding(4.0),
+        )
+    }
+
+    fn draw(renderer: &mut Renderer, view: &View, size: u32, buffer: &mut [u32]) {
+        let node = layout(view, Limits::loose(size as f32, size as f32), &*renderer);
+        renderer.draw(view, &node, buffer, size, size, 0x00FF_FFFF, 0.0, 0.0);
+    }
+
+    fn renderer() -> Renderer {
+        let mut renderer = Renderer::new();
+        renderer.load_default_font().unwrap();
+        renderer
+    }
+
+    /// Draws `before` and then `after` in `size` x `size` pixels. Returns the damage of
+    /// the second frame, its pixels, and the pixels of `after` painted in full.
+    fn redraw(before: &View, after: &View, size: u32) -> (Vec<Rectangle>, Vec<u32>, Vec<u32>) {
+        let mut renderer = renderer();
+        let mut buffer = vec![0u32; (size * size) as usize];
+        draw(&mut renderer, before, size, &mut buffer);
+        draw(&mut renderer, after, size, &mut buffer);
+
+        let mut expected = vec![0u32; (size * size) as usize];
+        draw(&mut self::renderer(), after, size, &mut expected);
+        (renderer.damage().to_vec(), buffer, expected)
+    }
+
+    /// Masked anti-aliasing may round edge pixels differently by one step.
+    fn close(buffer: &[u32], expected: &[u32]) -> bool {
+        let close = |a: u32, b: u32| (0..3).all(|i| ((a >> (i * 8)) as u8).abs_diff((b >> (i * 8)) as u8) <= 1);
+        buffer.iter().zip(expected).all(|(a, b)| close(*a, *b))
+    }
+
+    #[test]
+    fn test_first_frame_repaints_everything() {
+        let mut renderer = renderer();
+        draw(&mut renderer, &circle(RED), 100, &mut vec![0u32; 100 * 100]);
+        assert_eq!(renderer.damage(), &[Rectangle::new(0.0, 0.0, 100.0, 100.0)]);
+    }
+
+    #[test]
+    fn test_identical_frame_repaints_nothing() {
+        let (damage, _, _) = redraw(&circle(RED), &circle(RED), 100);
+        assert!(damage.is_empty(), "{damage:?}");
+    }
+
+    #[test]
+    fn test_changed_shape_repaints_only_its_area() {
+        let (damage, _, _) = redraw(&circle(RED), &circle(GREEN), 100);
+        assert_eq!(damage.len(), 1);
+        assert!(damage[0].area() < 40.0 * 40.0, "{damage:?}");
+    }
+
+    #[test]
+    fn test_partial_repaint_matches_full_repaint() {
+        let (_, buffer, expected) = redraw(&circle(RED), &circle(GREEN), 100);
+        assert!(close(&buffer, &expected));
+    }
+
+    #[test]
+    fn test_partial_repaint_covers_italics_leaning_past_their_bounds() {
+        let italic = |color: Color| View::from(VStack::new(vec![TextView::new("ff").size(40.0).italic().color(color)]).padding(4.0));
+        let (damage, buffer, expected) = redraw(&italic(RED), &italic(GREEN), 200);
+        assert!(damage[0].area() < 200.0 * 200.0 / 2.0, "{damage:?}");
+        assert!(close(&buffer, &expected));
+    }
+
+    /// Records a red 4x4 quad at (`x`, 0) in 40x40 pixels.
+    fn quad_at(renderer: &mut Renderer, x: f32) {
+        renderer.reset(Rectangle::new(0.0, 0.0, 40.0, 40.0));
+        renderer.fill_quad(Quad::new(Rectangle::new(x, 0.0, 4.0, 4.0)), RED);
+    }
+
+    /// Presents the quad at 0, then at 20, and returns the renderer.
+    fn moved_quad(buffer: &mut [u32]) -> Renderer {
+        let mut renderer = Renderer::new();
+        quad_at(&mut renderer, 0.0);
+        renderer.present(buffer, 40, 40, 0x00FF_FFFF);
+        quad_at(&mut renderer, 20.0);
+        renderer
+    }
+
+    #[test]
+    fn test_buffer_holding_the_last_frame_only_gets_the_damage() {
+        let mut buffer = vec![0u32; 40 * 40];
+        let mut renderer = moved_quad(&mut buffer);
+        buffer[39 * 40 + 39] = 0x123456;
+        renderer.hint_buffer_age(1);
+        renderer.present(&mut buffer, 40, 40, 0x00FF_FFFF);
+        assert_eq!((buffer[0], buffer[20]), (0x00FF_FFFF, 0x00FF_0000));
+        assert_eq!(buffer[39 * 40 + 39], 0x123456, "undamaged pixels are left alone");
+    }
+
+    #[test]
+    fn test_older_buffer_also_gets_what_changed_in_between() {
+        let mut buffer = vec![0u32; 40 * 40];
+        let mut renderer = moved_quad(&mut buffer);
+        renderer.present(&mut buffer, 40, 40, 0x00FF_FFFF);
+
+        // Two frames ago, this buffer showed the quad at 0
+        let mut older = vec![0x123456u32; 40 * 40];
+        older[0] = 0x00FF_0000;
+        quad_at(&mut renderer, 30.0);
+        renderer.hint_buffer_age(2);
+        renderer.present(&mut older, 40, 40, 0x00FF_FFFF);
+        assert_eq!((older[0], older[20], older[30]), (0x00FF_FFFF, 0x00FF_FFFF, 0x00FF_0000));
+        assert_eq!(older[39 * 40 + 39], 0x123456);
+    }
+
+    #[test]
+    fn test_unknown_or_too_old_buffers_are_written_whole() {
+        let mut buffer = vec![0u32; 40 * 40];
+        let mut renderer = moved_quad(&mut buffer);
+        for age in [0, 9] {
+            let mut unknown = vec![0x123456u32; 40 * 40];
+            quad_at(&mut renderer, 10.0 + age as f32);
+            renderer.hint_buffer_age(age);
+            renderer.present(&mut unknown, 40, 40, 0x00FF_FFFF);
+            assert_eq!(unknown[39 * 40 + 39], 0x00FF_FFFF, "age {age}");
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Recorded drawing commands, rasterized by the [`Renderer`](crate::render::Renderer).
+
+use crate::core::background::Background;
+use crate::core::image::{Handle, Interpolation};
+use crate::core::path::{Path, Stroke};
+use crate::core::renderer::Quad;
+use crate::core::text::Text;
+use crate::core::transformation::Transformation;
+use crate::layout::Rectangle;
+
+/// A drawable primitive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    /// A quad filled with a background.
+    Quad {
+        quad: Quad,
+        background: Background,
+    },
+    /// A paragraph of text.
+    Text(Text),
+    /// A raster image stretched to `bounds`.
+    Image {
+        handle: Handle,
+        interpolation: Interpolation,
+        bounds: Rectangle,
+    },
+    /// The inside of a path filled with a background.
+    Fill {
+        path: Path,
+        background: Background,
+    },
+    /// The outline of a path.
+    Stroke {
+        path: Path,
+        stroke: Stroke,
+    },
+    /// Items rendered offscreen, then composited with the given opacity.
+    Group {
+        opacity: f32,
+        items: Vec<Item>,
+    },
+}
+
+/// A [`Primitive`] together with the state it was recorded in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub primitive: Primitive,
+    /// Clip rectangle in target coordinates.
+    pub clip: Rectangle,
+    /// Paths the item is clipped to as well, innermost last. The clip rectangle
+    /// contains their bounds.
+    pub clip_paths: Vec<ClipPath>,
+    /// Transformation from logical to target coordinates.
+    pub transformation: Transformation,
+}
+
+/// A path that clips the items recorded inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipPath {
+    pub path: Path,
+    /// Transformation from the coordinates of the path to target coordinates.
+    pub transformation: Transformation,
+}
+
+/// A clip pushed with [`DisplayList::push_layer`] or [`DisplayList::push_clip_path`].
+#[derive(Debug, Clone)]
+struct Layer {
+    clip: Rectangle,
+    path: Option<ClipPath>,
+}
+
+/// An ordered list of primitives, recorded through the `core::Renderer` API.
+///
+/// Layers and transformations are resolved at record time, so every [`Item`]
+/// carries its own clip rectangle and transformation.
+#[derive(Debug, Clone)]
+pub struct DisplayList {
+    items: Vec<Item>,
+    bounds: Rectangle,
+    layers: Vec<Layer>,
+    transformations: Vec<Transformation>,
+    groups: Vec<(f32, Vec<Item>)>,
+}
+
+impl DisplayList {
+    /// Creates an empty display list covering `bounds`.
+    pub fn new(bounds: Rectangle) -> Self {
+        Self {
+            items: Vec::new(),
+            bounds,
+            layers: Vec::new(),
+            transformations: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Clears all recorded items and state, covering `bounds` from now on.
+    pub fn reset(&mut self, bounds: Rectangle) {
+        self.items.clear();
+        self.layers.clear();
+        self.transformations.clear();
+        self.groups.clear();
+        self.bounds = bounds;
+    }
+
+    /// Returns the bounds of the display list.
+    pub fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    /// Returns the recorded items in drawing order.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Returns whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the current clip rectangle in target coordinates.
+    pub fn clip(&self) -> Rectangle {
+        self.layers.last().map_or(self.bounds, |layer| layer.clip)
+    }
+
+    /// Returns the clip paths in effect, outermost first.
+    pub fn clip_paths(&self) -> Vec<ClipPath> {
+        self.layers.iter().filter_map(|layer| layer.path.clone()).collect()
+    }
+
+    /// Returns the current transformation.
+    pub fn transformation(&self) -> Transformation {
+        self.transformations.last().copied().unwrap_or_default()
+    }
+
+    /// Pushes a layer clipping to `bounds` (in the current coordinate space).
+    pub fn push_layer(&mut self, bounds: Rectangle) {
+        // A rotated rectangle is no longer axis-aligned, so it clips as a path.
+        if !self.transformation().is_axis_aligned() {
+            self.push_clip_path(Path::rectangle(bounds));
+            return;
+        }
+        let clip = self.restrict(bounds);
+        self.layers.push(Layer { clip, path: None });
+    }
+
+    /// Pushes a layer clipping to the inside of `path` (in the current coordinate space).
+    pub fn push_clip_path(&mut self, path: Path) {
+        let bounds = super::path::bounds(&path).unwrap_or(Rectangle::new(0.0, 0.0, 0.0, 0.0));
+        let clip = self.restrict(bounds);
+        let transformation = self.transformation();
+        self.layers.push(Layer {
+            clip,
+            path: Some(ClipPath { path, transformation }),
+        });
+    }
+
+    /// Intersects the current clip with `bounds` in the current coordinate space.
+    fn restrict(&self, bounds: Rectangle) -> Rectangle {
+        let bounds = self.transformation().transform_rectangle(bounds);
+        self.clip()
+            .intersection(&bounds)
+            .unwrap_or(Rectangle::new(bounds.x, bounds.y, 0.0, 0.0))
+    }
+
+    /// Pops the last layer or clip path.
+    pub fn pop_layer(&mut self) {
+        self.layers.pop();
+    }
+
+    /// Pushes a transformation, applied before the current one.
+    pub fn push_transformation(&mut self, transformation: Transformation) {
+        let current = self.transformation();
+        self.transformations.push(transformation.then(current));
+    }
+
+    /// Pops the last transformation.
+    pub fn pop_transformation(&mut self) {
+        self.transformations.pop();
+    }
+
+    /// Starts collecting items into a group composited with `opacity`.
+    pub fn push_group(&mut self, opacity: f32) {
+        self.groups.push((opacity, Vec::new()));
+    }
+
+    /// Ends the last group and records it as a single [`Primitive::Group`].
+    pub fn pop_group(&mut self) {
+        if let Some((opacity, items)) = self.groups.pop() {
+            self.push(Primitive::Group { opacity, items });
+        }
+    }
+
+    /// Records a primitive with the current clip and transformation.
+    pub fn push(&mut self, primitive: Primitive) {
+        let item = Item {
+            primitive,
+            clip: self.clip(),
+            clip_paths: self.clip_paths(),
+            transformation: self.transformation(),
+        };
+        match self.groups.last_mut() {
+            Some((_, items)) => items.push(item),
+            None => self.items.push(item),
+        }
+    }
+}
+
+impl Default for DisplayList {
+    fn default() -> Self {
+        Self::new(Rectangle::new(0.0, 0.0, f32::MAX, f32::MAX))
+    }
+}
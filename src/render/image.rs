@@ -0,0 +1,293 @@
+//! Decode PNG and JPEG images into premultiplied pixmaps.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tiny_skia::{IntSize, Pixmap};
+
+use crate::core::image::{Data, Handle};
+use crate::layout::Size;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Default memory budget for decoded images: 64 MiB.
+pub const DEFAULT_IMAGE_CACHE_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Decoded images keyed by [`Handle::id`], evicted least recently used past a byte
+/// budget and dropped once a frame goes by without them.
+pub(crate) struct ImageCache {
+    entries: HashMap<u64, Entry>,
+    /// Ids by the tick they were last used, oldest first.
+    recency: BTreeMap<u64, u64>,
+    clock: u64,
+    bytes: usize,
+    budget: usize,
+}
+
+struct Entry {
+    handle: Handle,
+    /// When the file was modified, to reload it when it changes.
+    modified: Option<SystemTime>,
+    /// `None` if the image could not be decoded; retried once the file changes or
+    /// the image has gone unused for a frame.
+    pixmap: Option<Arc<Pixmap>>,
+    last_used: u64,
+    used: bool,
+}
+
+impl Entry {
+    fn bytes(&self) -> usize {
+        self.pixmap.as_ref().map_or(0, |pixmap| pixmap.data().len())
+    }
+}
+
+impl ImageCache {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            bytes: 0,
+            budget,
+        }
+    }
+
+    /// Returns the decoded pixels of the image, decoding it on a miss.
+    pub fn load(&mut self, handle: &Handle) -> Option<Arc<Pixmap>> {
+        let modified = match handle.data() {
+            Data::Path(path) => std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok(),
+            Data::Bytes(_) => None,
+        };
+        let id = handle.id();
+        let fresh = self.entries.get(&id).is_some_and(|entry| entry.handle == *handle && entry.modified == modified);
+        if !fresh {
+            self.remove(id);
+            let pixmap = decode_data(handle.data()).ok().map(Arc::new);
+            let entry = Entry { handle: handle.clone(), modified, pixmap, last_used: 0, used: false };
+            self.bytes += entry.bytes();
+            self.entries.insert(id, entry);
+        }
+
+        self.clock += 1;
+        let entry = self.entries.get_mut(&id)?;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.clock, id);
+        entry.last_used = self.clock;
+        entry.used = true;
+        let pixmap = entry.pixmap.clone();
+
+        self.evict(Some(id));
+        pixmap
+    }
+
+    /// Evicts least recently used images other than `keep` until the cache is within budget.
+    fn evict(&mut self, keep: Option<u64>) {
+        while self.bytes > self.budget {
+            let Some((_, id)) = self.recency.first_key_value() else { break };
+            if Some(*id) == keep {
+                break;
+            }
+            self.remove(*id);
+        }
+    }
+
+    fn remove(&mut self, id: u64) {
+        if let Some(entry) = self.entries.remove(&id) {
+            self.recency.remove(&entry.last_used);
+            self.bytes -= entry.bytes();
+        }
+    }
+
+    /// Evicts images neither measured nor drawn since the last trim.
+    pub fn trim(&mut self) {
+        let unused: Vec<u64> = self.entries.iter().filter(|(_, entry)| !entry.used).map(|(id, _)| *id).collect();
+        for id in unused {
+            self.remove(id);
+        }
+        for entry in self.entries.values_mut() {
+            entry.used = false;
+        }
+    }
+
+    /// Sets the memory budget, evicting images if the cache is over it.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict(None);
+    }
+
+    /// Returns the bytes of decoded pixels held.
+    #[cfg(test)]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+/// Decodes the image to find its size in pixels, or `None` if it cannot be decoded.
+///
+/// Nothing is cached; the [`Renderer`](crate::render::Renderer) keeps decoded images.
+pub fn image_size(handle: &Handle) -> Option<Size> {
+    decode_data(handle.data()).ok().map(|pixmap| Size::new(pixmap.width() as f32, pixmap.height() as f32))
+}
+
+fn decode_data(data: &Data) -> Result<Pixmap, Error> {
+    match data {
+        Data::Path(path) => decode(&std::fs::read(path)?),
+        Data::Bytes(bytes) => decode(bytes),
+    }
+}
+
+/// Decodes a PNG or JPEG image, detected from its signature.
+pub fn decode(bytes: &[u8]) -> Result<Pixmap, Error> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Ok(Pixmap::decode_png(bytes)?)
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        decode_jpeg(bytes)
+    } else {
+        Err("unsupported image format (expected PNG or JPEG)".into())
+    }
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<Pixmap, Error> {
+    use zune_jpeg::zune_core::colorspace::ColorSpace;
+    use zune_jpeg::zune_core::options::DecoderOptions;
+
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+    let mut decoder = zune_jpeg::JpegDecoder::new_with_options(bytes, options);
+    let pixels = decoder.decode().map_err(|error| format!("{error:?}"))?;
+    let info = decoder.info().ok_or("missing JPEG header")?;
+
+    // JPEG has no transparency, so the RGBA output is already premultiplied.
+    let size = IntSize::from_wh(info.width as u32, info.height as u32).ok_or("empty JPEG image")?;
+    Pixmap::from_vec(pixels, size).ok_or_else(|| "unexpected JPEG buffer size".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::image::Interpolation;
+    use crate::layout::{layout, Limits};
+    use crate::render::Renderer;
+    use crate::view::{Image, View};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        Pixmap::new(width, height).unwrap().encode_png().unwrap()
+    }
+
+    #[test]
+    fn test_handles_are_identified_by_allocation() {
+        let bytes: Arc<[u8]> = png(2, 2).into();
+        let (a, b) = (Handle::from_bytes(bytes.clone()), Handle::from_bytes(bytes.clone()));
+        assert_eq!(a.id(), b.id());
+        assert_eq!(a, b);
+
+        // Equal contents elsewhere are equal, but cached separately
+        let copy = Handle::from_bytes(bytes.to_vec());
+        assert_ne!(a.id(), copy.id());
+        assert_eq!(a, copy);
+        assert_ne!(a, Handle::from_bytes(png(3, 2)));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_past_budget() {
+        let (small, large) = (Handle::from_bytes(png(2, 2)), Handle::from_bytes(png(4, 4)));
+        let mut cache = ImageCache::new(80);
+        assert!(cache.load(&small).is_some());
+        assert!(cache.load(&large).is_some());
+        assert_eq!(cache.bytes(), 80);
+
+        // Loading the small image again makes the large one the oldest
+        let other = Handle::from_bytes(png(1, 2));
+        cache.load(&small);
+        cache.load(&other);
+        assert_eq!(cache.bytes(), 16 + 8);
+
+        // A frame without an image drops it
+        cache.trim();
+        cache.load(&other);
+        cache.trim();
+        assert_eq!(cache.bytes(), 8);
+    }
+
+    #[test]
+    fn test_cache_retries_missing_and_reloads_changed_files() {
+        let path = std::env::temp_dir().join(format!("shellui-image-{}.png", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let handle = Handle::from_path(&path);
+        let mut cache = ImageCache::new(DEFAULT_IMAGE_CACHE_BUDGET);
+        assert!(cache.load(&handle).is_none());
+
+        std::fs::write(&path, png(2, 2)).unwrap();
+        assert_eq!(cache.load(&handle).map(|pixmap| pixmap.width()), Some(2));
+
+        std::fs::write(&path, png(3, 2)).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(10);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert_eq!(cache.load(&handle).map(|pixmap| pixmap.width()), Some(3));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// A 4x2 image: the left half red, the right half blue.
+    fn red_and_blue_png() -> Vec<u8> {
+        let mut pixmap = Pixmap::new(4, 2).unwrap();
+        for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+            let [r, b] = if i % 4 < 2 { [255, 0] } else { [0, 255] };
+            *pixel = tiny_skia::PremultipliedColorU8::from_rgba(r, 0, b, 255).unwrap();
+        }
+        pixmap.encode_png().unwrap()
+    }
+
+    /// Lays out the image in a 40x40 space.
+    fn laid_out(image: Image) -> Size {
+        layout(&View::from(image), Limits::loose(40.0, 40.0), &Renderer::new()).size()
+    }
+
+    #[test]
+    fn test_image_takes_its_pixel_size() {
+        assert_eq!(laid_out(Image::from_bytes(red_and_blue_png())), Size::new(4.0, 2.0));
+    }
+
+    #[test]
+    fn test_resizable_image_stretches_to_the_proposed_size() {
+        assert_eq!(laid_out(Image::from_bytes(red_and_blue_png()).resizable()), Size::new(40.0, 40.0));
+    }
+
+    #[test]
+    fn test_fitted_image_keeps_its_aspect_ratio_inside_the_proposed_size() {
+        let fit = Image::from_bytes(red_and_blue_png()).resizable().scaled_to_fit();
+        assert_eq!(laid_out(fit), Size::new(40.0, 20.0));
+    }
+
+    #[test]
+    fn test_filled_image_takes_the_proposed_size() {
+        let fill = Image::from_bytes(red_and_blue_png()).resizable().scaled_to_fill();
+        assert_eq!(laid_out(fill), Size::new(40.0, 40.0));
+    }
+
+    #[test]
+    fn test_unsupported_formats_fail_to_decode() {
+        assert!(decode(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn test_filled_image_is_clipped_to_its_frame() {
+        let view = View::from(
+            Image::from_bytes(red_and_blue_png())
+                .resizable()
+                .scaled_to_fill()
+                .interpolation(Interpolation::None)
+                .frame(8.0, 8.0),
+        );
+        let mut renderer = Renderer::new();
+        let node = layout(&view, Limits::loose(16.0, 8.0), &renderer);
+
+        let mut buffer = vec![0u32; 16 * 8];
+        renderer.draw(&view, &node, &mut buffer, 16, 8, 0x00FF_FFFF, 0.0, 0.0);
+
+        // Filling 8x8 with a 2:1 image scales it to 16x8, centered: the frame shows
+        // the right half of the red area and the left half of the blue area.
+        assert_eq!(buffer[4 * 16 + 1], 0xFF0000);
+        assert_eq!(buffer[4 * 16 + 6], 0x0000FF);
+        assert_eq!(buffer[4 * 16 + 10], 0x00FF_FFFF, "overflow is clipped");
+    }
+}
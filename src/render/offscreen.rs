@@ -0,0 +1,133 @@
+//! Render views without a window, into owned RGBA images.
+
+use std::path::Path;
+
+use crate::core::renderer::Renderer as _;
+use crate::layout::{layout, Limits, Size};
+use crate::core::background::Color;
+use crate::render::{PixelBuffer, PixelFormat, Renderer, DEFAULT_BACKGROUND};
+use crate::view::View;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// An owned image with straight-alpha RGBA8 pixels, row-major.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Creates an image from RGBA8 pixels. Returns `None` if the length does not match the size.
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+        (pixels.len() == width as usize * height as usize * 4).then_some(Self { width, height, pixels })
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the RGBA8 pixels, row-major.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the RGBA8 pixel at (`x`, `y`), or `None` if out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        Some([self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]])
+    }
+
+    /// Encodes the image as PNG.
+    pub fn encode_png(&self) -> Result<Vec<u8>, Error> {
+        let size = tiny_skia::IntSize::from_wh(self.width, self.height).ok_or("cannot encode an empty image")?;
+        // tiny-skia stores premultiplied pixels and demultiplies them when encoding.
+        let pixels = self
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| {
+                let color = tiny_skia::ColorU8::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]).premultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        let pixmap = tiny_skia::Pixmap::from_vec(pixels, size).ok_or("invalid image size")?;
+        Ok(pixmap.encode_png()?)
+    }
+
+    /// Writes the image to a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.encode_png()?)?;
+        Ok(())
+    }
+}
+
+/// Lays out and renders `view` into an image, without opening a window.
+///
+/// `size` is in logical pixels; the image is `size * scale` physical pixels. The view
+/// is placed at the top-left corner over the default window background, and text uses
+/// the bundled default font.
+pub fn render_to_image(view: &View, size: Size, scale: f32) -> RgbaImage {
+    let mut renderer = Renderer::new();
+    let _ = renderer.load_default_font();
+    renderer.render_to_image(view, size, scale)
+}
+
+impl Renderer {
+    /// Lays out and renders `view` into an image with this renderer's fonts.
+    ///
+    /// See [`render_to_image`](fn@render_to_image).
+    pub fn render_to_image(&mut self, view: &View, size: Size, scale: f32) -> RgbaImage {
+        let scale = if scale.is_finite() && scale > 0.0 { scale } else { 1.0 };
+        let width = (size.width * scale).round().max(0.0) as u32;
+        let height = (size.height * scale).round().max(0.0) as u32;
+        self.hint(scale);
+
+        let node = layout(view, Limits::loose(size.width, size.height), self);
+        let mut pixels = vec![0u8; width as usize * height as usize * PixelFormat::BYTES_PER_PIXEL];
+        let mut target = PixelBuffer::packed(&mut pixels, width, height, PixelFormat::Rgba8)
+            .expect("a packed buffer has the right size");
+        self.draw_to(view, &node, &mut target, Color::from_u32(DEFAULT_BACKGROUND), 0.0, 0.0);
+
+        RgbaImage { width, height, pixels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::decode_image;
+    use crate::view::Shape;
+
+    /// A red 10x5 rectangle in a 20x10 image, rendered at twice the size.
+    fn red_rectangle() -> RgbaImage {
+        let view = View::from(Shape::rectangle().fill(Color::new(1.0, 0.0, 0.0, 1.0)).frame(10.0, 5.0));
+        render_to_image(&view, Size::new(20.0, 10.0), 2.0)
+    }
+
+    #[test]
+    fn test_image_is_rendered_at_the_scale_factor() {
+        let image = red_rectangle();
+        assert_eq!((image.width(), image.height()), (40, 20));
+        assert_eq!(image.pixel(19, 9), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(20, 9), Some([0xEE, 0xEE, 0xEE, 255]), "the rest is the default background");
+        assert_eq!(image.pixel(40, 0), None);
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let image = red_rectangle();
+        let decoded = decode_image(&image.encode_png().unwrap()).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (40, 20));
+        assert_eq!(decoded.data(), image.pixels());
+    }
+}
@@ -0,0 +1,283 @@
+//! Rasterize vector paths with tiny-skia.
+
+use tiny_skia::{FillRule, Mask, Paint, PathBuilder, PathSegment, Pixmap, StrokeDash};
+
+use crate::core::background::Background;
+use crate::core::path::{Command, Path, Stroke};
+use crate::core::point::Point;
+use crate::core::transformation::Transformation;
+use crate::layout::Rectangle;
+use crate::render::background::{self, GradientCache};
+use crate::render::quad::{to_color, to_transform};
+
+/// Fills the inside of `path` with `background`.
+pub(crate) fn fill_path(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    background: &Background,
+    transformation: Transformation,
+    clip: Option<&Mask>,
+    gradients: &mut GradientCache,
+) {
+    let Some(path) = build(path) else { return };
+    let bounds = path.bounds();
+    let bounds = Rectangle::new(bounds.x(), bounds.y(), bounds.width(), bounds.height());
+
+    background::with_paint(background, bounds, transformation, gradients, |paint| {
+        pixmap.fill_path(&path, paint, FillRule::Winding, to_transform(transformation), clip);
+    });
+}
+
+/// Draws the outline of `path`.
+pub(crate) fn stroke_path(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    stroke: &Stroke,
+    transformation: Transformation,
+    clip: Option<&Mask>,
+) {
+    if stroke.width <= 0.0 || stroke.color.a <= 0.0 {
+        return;
+    }
+    let Some(path) = build(path) else { return };
+
+    let mut paint = Paint::default();
+    paint.set_color(to_color(stroke.color));
+
+    let skia_stroke = tiny_skia::Stroke {
+        width: stroke.width,
+        dash: dash(&stroke.dash),
+        ..Default::default()
+    };
+    pixmap.stroke_path(&path, &paint, &skia_stroke, to_transform(transformation), clip);
+}
+
+/// Builds a tiny-skia dash pattern. Odd-length patterns repeat, as in SVG.
+fn dash(pattern: &[f32]) -> Option<StrokeDash> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut intervals = pattern.to_vec();
+    if intervals.len() % 2 == 1 {
+        intervals.extend_from_slice(pattern);
+    }
+    StrokeDash::new(intervals, 0.0)
+}
+
+/// Returns the bounding box of the path in its own coordinates.
+pub(crate) fn bounds(path: &Path) -> Option<Rectangle> {
+    let bounds = build(path)?.bounds();
+    Some(Rectangle::new(bounds.x(), bounds.y(), bounds.width(), bounds.height()))
+}
+
+/// Converts a [`Path`] into a tiny-skia path, flattening arcs into cubic curves.
+pub(crate) fn build(path: &Path) -> Option<tiny_skia::Path> {
+    let mut builder = PathBuilder::new();
+    let mut has_current = false;
+
+    for command in path.commands() {
+        match *command {
+            Command::MoveTo(point) => {
+                builder.move_to(point.x, point.y);
+                has_current = true;
+            }
+            Command::LineTo(point) => {
+                line_to(&mut builder, &mut has_current, point.x, point.y);
+            }
+            Command::QuadTo { control, to } => {
+                if !has_current {
+                    builder.move_to(control.x, control.y);
+                    has_current = true;
+                }
+                builder.quad_to(control.x, control.y, to.x, to.y);
+            }
+            Command::CubicTo { control_a, control_b, to } => {
+                if !has_current {
+                    builder.move_to(control_a.x, control_a.y);
+                    has_current = true;
+                }
+                builder.cubic_to(control_a.x, control_a.y, control_b.x, control_b.y, to.x, to.y);
+            }
+            Command::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let (start_x, start_y) = (
+                    center.x + radius * start_angle.cos(),
+                    center.y + radius * start_angle.sin(),
+                );
+                line_to(&mut builder, &mut has_current, start_x, start_y);
+                push_arc(&mut builder, center.x, center.y, radius, start_angle, end_angle);
+            }
+            Command::Close => {
+                if has_current {
+                    builder.close();
+                }
+                has_current = false;
+            }
+        }
+    }
+
+    builder.finish()
+}
+
+/// Adds a line to the point, or starts a subpath there if there is no current point.
+fn line_to(builder: &mut PathBuilder, has_current: &mut bool, x: f32, y: f32) {
+    if *has_current {
+        builder.line_to(x, y);
+    } else {
+        builder.move_to(x, y);
+        *has_current = true;
+    }
+}
+
+/// Approximates the arc with one cubic curve per quarter turn (or less).
+fn push_arc(builder: &mut PathBuilder, cx: f32, cy: f32, radius: f32, start: f32, end: f32) {
+    let sweep = end - start;
+    if sweep == 0.0 || radius <= 0.0 {
+        return;
+    }
+    let segments = (sweep.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+
+    let mut angle = start;
+    for _ in 0..segments {
+        let next = angle + step;
+        let (sin_a, cos_a) = angle.sin_cos();
+        let (sin_b, cos_b) = next.sin_cos();
+        builder.cubic_to(
+            cx + radius * cos_a - k * sin_a,
+            cy + radius * sin_a + k * cos_a,
+            cx + radius * cos_b + k * sin_b,
+            cy + radius * sin_b - k * cos_b,
+            cx + radius * cos_b,
+            cy + radius * sin_b,
+        );
+        angle = next;
+    }
+}
+
+/// Returns whether `point` is inside `path` as it is filled and clipped to: with the
+/// nonzero winding rule, every subpath closed, and curves flattened into lines.
+pub(crate) fn contains(path: &Path, point: Point) -> bool {
+    /// Lines per curve; plenty for hit testing at the size of a view.
+    const STEPS: usize = 16;
+
+    let Some(path) = build(path) else {
+        return false;
+    };
+    let mut winding = 0;
+    let mut edge = |from: tiny_skia::Point, to: tiny_skia::Point| {
+        let side = (to.x - from.x) * (point.y - from.y) - (point.x - from.x) * (to.y - from.y);
+        if from.y <= point.y && to.y > point.y && side > 0.0 {
+            winding += 1;
+        } else if to.y <= point.y && from.y > point.y && side < 0.0 {
+            winding -= 1;
+        }
+    };
+
+    let (mut start, mut current) = (tiny_skia::Point::zero(), tiny_skia::Point::zero());
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(to) => {
+                edge(current, start);
+                (start, current) = (to, to);
+            }
+            PathSegment::LineTo(to) => {
+                edge(current, to);
+                current = to;
+            }
+            PathSegment::QuadTo(control, to) => {
+                let from = current;
+                for step in 1..=STEPS {
+                    let t = step as f32 / STEPS as f32;
+                    let u = 1.0 - t;
+                    let next = tiny_skia::Point::from_xy(
+                        u * u * from.x + 2.0 * u * t * control.x + t * t * to.x,
+                        u * u * from.y + 2.0 * u * t * control.y + t * t * to.y,
+                    );
+                    edge(current, next);
+                    current = next;
+                }
+            }
+            PathSegment::CubicTo(control_a, control_b, to) => {
+                let from = current;
+                for step in 1..=STEPS {
+                    let t = step as f32 / STEPS as f32;
+                    let u = 1.0 - t;
+                    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                    let next = tiny_skia::Point::from_xy(
+                        a * from.x + b * control_a.x + c * control_b.x + d * to.x,
+                        a * from.y + b * control_a.y + c * control_b.y + d * to.y,
+                    );
+                    edge(current, next);
+                    current = next;
+                }
+            }
+            PathSegment::Close => {
+                edge(current, start);
+                current = start;
+            }
+        }
+    }
+    edge(current, start);
+    winding != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::background::Color;
+    use crate::core::path::Path;
+    use crate::core::point::Point;
+    use crate::layout::{layout, Limits};
+    use crate::render::Renderer;
+    use crate::view::{Shape, VStack};
+    use crate::View;
+
+    /// Draws `shapes` stacked on white in 20x20 pixels.
+    fn draw(shapes: Vec<Shape>, padding: f32) -> Vec<u32> {
+        let view = View::from(VStack::new(shapes.into_iter().map(View::from).collect::<Vec<_>>()).padding(padding));
+        let mut renderer = Renderer::new();
+        let node = layout(&view, Limits::loose(20.0, 20.0), &renderer);
+        let mut buffer = vec![0u32; 20 * 20];
+        renderer.draw(&view, &node, &mut buffer, 20, 20, 0x00FF_FFFF, 0.0, 0.0);
+        buffer
+    }
+
+    #[test]
+    fn test_circle_is_centered_in_its_frame() {
+        // The frame is 20x10, so the radius is 5.
+        let buffer = draw(vec![Shape::circle().fill(Color::new(1.0, 0.0, 0.0, 1.0)).frame(20.0, 10.0)], 0.0);
+        assert_eq!(buffer[5 * 20 + 10], 0xFF0000);
+        assert_eq!(buffer[5 * 20 + 2], 0x00FF_FFFF);
+    }
+
+    #[test]
+    fn test_custom_path_is_placed_at_the_corner_of_its_frame() {
+        let triangle = Path::new()
+            .move_to(Point::new(0.0, 0.0))
+            .line_to(Point::new(10.0, 0.0))
+            .line_to(Point::new(0.0, 10.0))
+            .close();
+        let buffer = draw(vec![Shape::path(triangle).fill(Color::new(0.0, 1.0, 0.0, 1.0)).frame(20.0, 10.0)], 0.0);
+        assert_eq!(buffer[20 + 2], 0x00FF00);
+        assert_eq!(buffer[8 * 20 + 8], 0x00FF_FFFF);
+    }
+
+    #[test]
+    fn test_stroke_is_dashed() {
+        let buffer = draw(vec![Shape::rectangle().stroke(Color::new(0.0, 0.0, 1.0, 1.0), 2.0, &[4.0, 4.0]).frame(12.0, 12.0)], 4.0);
+        // The top edge runs from x = 4 with a 4px dash, then a 4px gap.
+        assert_eq!(buffer[4 * 20 + 5], 0x0000FF);
+        assert_eq!(buffer[4 * 20 + 9], 0x00FF_FFFF);
+    }
+
+    #[test]
+    fn test_stroked_shapes_are_not_filled() {
+        let buffer = draw(vec![Shape::rectangle().stroke(Color::new(0.0, 0.0, 1.0, 1.0), 2.0, &[]).frame(12.0, 12.0)], 4.0);
+        assert_eq!(buffer[10 * 20 + 10], 0x00FF_FFFF);
+    }
+}
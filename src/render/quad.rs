@@ -0,0 +1,360 @@
+//! Rasterize quads (rounded rectangles with borders and shadows) with tiny-skia.
+
+use tiny_skia::{FillRule, Mask, Paint, Path, PathBuilder, Pixmap, PixmapPaint};
+
+use crate::core::background::{Background, Color};
+use crate::core::renderer::Quad;
+use crate::core::transformation::Transformation;
+use crate::layout::Rectangle;
+use crate::render::display_list::ClipPath;
+use crate::render::background::{self, GradientCache};
+use crate::render::path;
+
+/// Control point distance for approximating a quarter circle with a cubic Bézier.
+const KAPPA: f32 = 0.552_284_8;
+
+/// Draws a [`Quad`] (shadow, background and inner border) onto the pixmap.
+pub(crate) fn fill_quad(
+    pixmap: &mut Pixmap,
+    quad: &Quad,
+    background: &Background,
+    transformation: Transformation,
+    clip: Option<&Mask>,
+    gradients: &mut GradientCache,
+) {
+    let mut bounds = quad.bounds;
+    if quad.snap {
+        bounds = snap(bounds);
+    }
+
+    let transform = to_transform(transformation);
+    let radius = quad.border.radius.min(bounds.width / 2.0).min(bounds.height / 2.0).max(0.0);
+
+    let shadow = quad.shadow;
+    if shadow.color.a > 0.0 && (shadow.blur > 0.0 || shadow.offset.x != 0.0 || shadow.offset.y != 0.0) {
+        let shadow_bounds = Rectangle::new(
+            bounds.x + shadow.offset.x,
+            bounds.y + shadow.offset.y,
+            bounds.width,
+            bounds.height,
+        );
+        if let Some(path) = rounded_rectangle(shadow_bounds, radius) {
+            draw_shadow(pixmap, &path, shadow.color, shadow.blur, transformation, clip);
+        }
+    }
+
+    let Some(outer) = rounded_rectangle(bounds, radius) else { return };
+
+    background::with_paint(background, bounds, transformation, gradients, |paint| {
+        pixmap.fill_path(&outer, paint, FillRule::Winding, transform, clip);
+    });
+
+    let border = quad.border;
+    if border.width > 0.0 && border.color.a > 0.0 {
+        let width = border.width.min(bounds.width / 2.0).min(bounds.height / 2.0);
+        let inner_bounds = Rectangle::new(
+            bounds.x + width,
+            bounds.y + width,
+            bounds.width - width * 2.0,
+            bounds.height - width * 2.0,
+        );
+
+        // The border is the area between the outer and the inner outline.
+        let mut builder = PathBuilder::new();
+        push_rounded_rectangle(&mut builder, bounds, radius);
+        if inner_bounds.width > 0.0 && inner_bounds.height > 0.0 {
+            push_rounded_rectangle(&mut builder, inner_bounds, (radius - width).max(0.0));
+        }
+        let Some(path) = builder.finish() else { return };
+
+        let mut paint = Paint::default();
+        paint.set_color(to_color(border.color));
+        pixmap.fill_path(&path, &paint, FillRule::EvenOdd, transform, clip);
+    }
+}
+
+/// Builds the outline of a rectangle with rounded corners.
+pub(crate) fn rounded_rectangle(bounds: Rectangle, radius: f32) -> Option<Path> {
+    if bounds.width <= 0.0 || bounds.height <= 0.0 {
+        return None;
+    }
+    let mut builder = PathBuilder::new();
+    push_rounded_rectangle(&mut builder, bounds, radius);
+    builder.finish()
+}
+
+fn push_rounded_rectangle(builder: &mut PathBuilder, bounds: Rectangle, radius: f32) {
+    let Rectangle { x, y, width, height } = bounds;
+    let r = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+
+    if r == 0.0 {
+        if let Some(rect) = tiny_skia::Rect::from_xywh(x, y, width, height) {
+            builder.push_rect(rect);
+        }
+        return;
+    }
+
+    let k = r * KAPPA;
+    let (right, bottom) = (x + width, y + height);
+
+    builder.move_to(x + r, y);
+    builder.line_to(right - r, y);
+    builder.cubic_to(right - r + k, y, right, y + r - k, right, y + r);
+    builder.line_to(right, bottom - r);
+    builder.cubic_to(right, bottom - r + k, right - r + k, bottom, right - r, bottom);
+    builder.line_to(x + r, bottom);
+    builder.cubic_to(x + r - k, bottom, x, bottom - r + k, x, bottom - r);
+    builder.line_to(x, y + r);
+    builder.cubic_to(x, y + r - k, x + r - k, y, x + r, y);
+    builder.close();
+}
+
+/// Draws a Gaussian-blurred copy of `path` filled with `color`.
+///
+/// `blur` follows the CSS convention: it is twice the standard deviation.
+fn draw_shadow(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    color: Color,
+    blur: f32,
+    transformation: Transformation,
+    clip: Option<&Mask>,
+) {
+    let Some(path) = path.clone().transform(to_transform(transformation)) else { return };
+
+    // Blur happens in device space, so the spread scales with the transformation.
+    let sigma = blur * transformation.scale_factor() / 2.0;
+    let margin = (sigma * 3.0).ceil();
+    let bounds = path.bounds();
+
+    let left = (bounds.left() - margin).floor().max(-margin - 1.0);
+    let top = (bounds.top() - margin).floor().max(-margin - 1.0);
+    let right = (bounds.right() + margin).ceil().min(pixmap.width() as f32 + margin + 1.0);
+    let bottom = (bounds.bottom() + margin).ceil().min(pixmap.height() as f32 + margin + 1.0);
+    if right <= left || bottom <= top {
+        return;
+    }
+
+    let Some(mut layer) = Pixmap::new((right - left) as u32, (bottom - top) as u32) else { return };
+
+    let mut paint = Paint::default();
+    paint.set_color(to_color(color));
+    layer.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        tiny_skia::Transform::from_translate(-left, -top),
+        None,
+    );
+
+    if sigma > 0.0 {
+        gaussian_blur(&mut layer, sigma);
+    }
+
+    pixmap.draw_pixmap(
+        left as i32,
+        top as i32,
+        layer.as_ref(),
+        &PixmapPaint::default(),
+        tiny_skia::Transform::identity(),
+        clip,
+    );
+}
+
+/// Approximates a Gaussian blur with three successive box blurs.
+///
+/// Blurring the premultiplied channels keeps edges free of color fringes.
+pub(crate) fn gaussian_blur(pixmap: &mut Pixmap, sigma: f32) {
+    let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+    let data = pixmap.data_mut();
+    let mut scratch = vec![0u8; data.len()];
+
+    for radius in box_radii(sigma) {
+        if radius == 0 {
+            continue;
+        }
+        box_blur_horizontal(data, &mut scratch, width, height, radius);
+        box_blur_vertical(&scratch, data, width, height, radius);
+    }
+}
+
+/// Box radii whose successive application approximates a Gaussian with `sigma`.
+fn box_radii(sigma: f32) -> [usize; 3] {
+    const PASSES: f32 = 3.0;
+
+    let ideal = (12.0 * sigma * sigma / PASSES + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let upper = lower + 2;
+
+    let lower_f = lower as f32;
+    let m = ((12.0 * sigma * sigma - PASSES * lower_f * lower_f - 4.0 * PASSES * lower_f - 3.0 * PASSES)
+        / (-4.0 * lower_f - 4.0))
+        .round() as i32;
+
+    let mut radii = [0usize; 3];
+    for (i, radius) in radii.iter_mut().enumerate() {
+        let size = if (i as i32) < m { lower } else { upper };
+        *radius = (size.max(1) as usize - 1) / 2;
+    }
+    radii
+}
+
+fn box_blur_horizontal(src: &[u8], dst: &mut [u8], width: usize, height: usize, radius: usize) {
+    let window = (radius * 2 + 1) as u32;
+    for y in 0..height {
+        let row = y * width * 4;
+        for channel in 0..4 {
+            let sample = |x: isize| -> u32 {
+                if x < 0 || x >= width as isize {
+                    0
+                } else {
+                    src[row + x as usize * 4 + channel] as u32
+                }
+            };
+
+            let mut sum: u32 = (-(radius as isize)..=radius as isize).map(sample).sum();
+            for x in 0..width {
+                dst[row + x * 4 + channel] = ((sum + window / 2) / window) as u8;
+                sum += sample(x as isize + radius as isize + 1);
+                sum -= sample(x as isize - radius as isize);
+            }
+        }
+    }
+}
+
+fn box_blur_vertical(src: &[u8], dst: &mut [u8], width: usize, height: usize, radius: usize) {
+    let window = (radius * 2 + 1) as u32;
+    for x in 0..width {
+        for channel in 0..4 {
+            let sample = |y: isize| -> u32 {
+                if y < 0 || y >= height as isize {
+                    0
+                } else {
+                    src[(y as usize * width + x) * 4 + channel] as u32
+                }
+            };
+
+            let mut sum: u32 = (-(radius as isize)..=radius as isize).map(sample).sum();
+            for y in 0..height {
+                dst[(y * width + x) * 4 + channel] = ((sum + window / 2) / window) as u8;
+                sum += sample(y as isize + radius as isize + 1);
+                sum -= sample(y as isize - radius as isize);
+            }
+        }
+    }
+}
+
+/// Builds a clip mask for the pixmap, or `None` if `clip` covers all of it and there
+/// are no clip paths.
+pub(crate) fn clip_mask(pixmap: &Pixmap, clip: Rectangle, paths: &[ClipPath]) -> Option<Mask> {
+    let full = Rectangle::new(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32);
+    let covers_all =
+        clip.x <= 0.0 && clip.y <= 0.0 && clip.x + clip.width >= full.width && clip.y + clip.height >= full.height;
+    if covers_all && paths.is_empty() {
+        return None;
+    }
+
+    let mut mask = Mask::new(pixmap.width(), pixmap.height())?;
+    if let Some(clip) = clip.intersection(&full) {
+        if let Some(rect) = tiny_skia::Rect::from_xywh(clip.x, clip.y, clip.width, clip.height) {
+            mask.fill_path(
+                &PathBuilder::from_rect(rect),
+                FillRule::Winding,
+                false,
+                tiny_skia::Transform::identity(),
+            );
+        }
+    }
+    for clip_path in paths {
+        match path::build(&clip_path.path) {
+            Some(outline) => {
+                mask.intersect_path(&outline, FillRule::Winding, true, to_transform(clip_path.transformation));
+            }
+            // An empty path clips everything away.
+            None => mask.data_mut().fill(0),
+        }
+    }
+    Some(mask)
+}
+
+fn snap(bounds: Rectangle) -> Rectangle {
+    let x = bounds.x.round();
+    let y = bounds.y.round();
+    Rectangle::new(
+        x,
+        y,
+        (bounds.x + bounds.width).round() - x,
+        (bounds.y + bounds.height).round() - y,
+    )
+}
+
+/// Converts a [`Color`] into a tiny-skia color, clamping each channel.
+pub(crate) fn to_color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(
+        color.r.clamp(0.0, 1.0),
+        color.g.clamp(0.0, 1.0),
+        color.b.clamp(0.0, 1.0),
+        color.a.clamp(0.0, 1.0),
+    )
+    .unwrap_or(tiny_skia::Color::TRANSPARENT)
+}
+
+/// Converts a [`Transformation`] into a tiny-skia transform.
+pub(crate) fn to_transform(transformation: Transformation) -> tiny_skia::Transform {
+    let Transformation { a, b, c, d, e, f } = transformation;
+    tiny_skia::Transform::from_row(a, b, c, d, e, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::border::Border;
+    use crate::core::renderer::Renderer as _;
+    use crate::core::shadow::Shadow;
+    use crate::core::vector::Vector;
+    use crate::render::Renderer;
+
+    /// Draws a red 20x20 quad at (10, 10) with a blue border, rounded corners and a
+    /// shadow on white, and returns the pixel at (`x`, `y`).
+    fn styled_quad_pixel(x: usize, y: usize) -> u32 {
+        let mut renderer = Renderer::new();
+        renderer.reset(Rectangle::new(0.0, 0.0, 40.0, 40.0));
+        renderer.fill_quad(
+            Quad {
+                bounds: Rectangle::new(10.0, 10.0, 20.0, 20.0),
+                border: Border::new(Color::new(0.0, 0.0, 1.0, 1.0), 2.0, 8.0),
+                shadow: Shadow::new(Color::BLACK, Vector::new(0.0, 4.0), 6.0),
+                snap: false,
+            },
+            Color::new(1.0, 0.0, 0.0, 1.0),
+        );
+
+        let mut buffer = vec![0u32; 40 * 40];
+        renderer.present(&mut buffer, 40, 40, 0x00FF_FFFF);
+        buffer[y * 40 + x]
+    }
+
+    #[test]
+    fn test_background_fills_the_center() {
+        assert_eq!(styled_quad_pixel(20, 20), 0xFF0000);
+    }
+
+    #[test]
+    fn test_border_is_drawn_inside_the_bounds() {
+        assert_eq!(styled_quad_pixel(20, 10), 0x0000FF);
+    }
+
+    #[test]
+    fn test_rounded_corner_does_not_cover_the_corner_of_the_bounds() {
+        assert!((styled_quad_pixel(10, 10) >> 16) & 0xFF > 0x80);
+    }
+
+    #[test]
+    fn test_shadow_is_blurred_below_the_quad() {
+        let below = styled_quad_pixel(20, 33);
+        assert!(below != 0x00FF_FFFF && below != 0, "{below:06x}");
+    }
+}
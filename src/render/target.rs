@@ -0,0 +1,162 @@
+//! Byte buffers the renderer can present into, in several pixel formats.
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Layout of the four bytes of a pixel in a [`PixelBuffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Red, green, blue, alpha; straight alpha. The usual format for image files and
+    /// `Rgba8Unorm` textures.
+    Rgba8,
+    /// Blue, green, red, alpha; straight alpha. Common for video frames and
+    /// `Bgra8Unorm` textures.
+    Bgra8,
+    /// Alpha, red, green, blue; color channels premultiplied by alpha.
+    PremultipliedArgb8,
+}
+
+impl PixelFormat {
+    /// Bytes per pixel; every format uses four.
+    pub const BYTES_PER_PIXEL: usize = 4;
+
+    /// Converts a premultiplied RGBA pixel of the canvas to this format.
+    fn encode(self, pixel: [u8; 4]) -> [u8; 4] {
+        match self {
+            PixelFormat::Rgba8 => super::composite::demultiply(pixel),
+            PixelFormat::Bgra8 => {
+                let [r, g, b, a] = super::composite::demultiply(pixel);
+                [b, g, r, a]
+            }
+            PixelFormat::PremultipliedArgb8 => {
+                let [r, g, b, a] = pixel;
+                [a, r, g, b]
+            }
+        }
+    }
+}
+
+/// A mutable byte buffer of `height` rows, `stride` bytes apart, to render into.
+///
+/// Rows may be padded: only the first `width * 4` bytes of each row are written.
+#[derive(Debug)]
+pub struct PixelBuffer<'a> {
+    data: &'a mut [u8],
+    width: u32,
+    height: u32,
+    stride: usize,
+    format: PixelFormat,
+}
+
+impl<'a> PixelBuffer<'a> {
+    /// Wraps `data` as a `width` x `height` image with rows `stride` bytes apart.
+    ///
+    /// Fails if the stride is shorter than a row or `data` is too small.
+    pub fn new(data: &'a mut [u8], width: u32, height: u32, stride: usize, format: PixelFormat) -> Result<Self, Error> {
+        let row = width as usize * PixelFormat::BYTES_PER_PIXEL;
+        if stride < row {
+            return Err(format!("stride {stride} is shorter than a row of {row} bytes").into());
+        }
+        let needed = if height == 0 { 0 } else { stride * (height as usize - 1) + row };
+        if data.len() < needed {
+            return Err(format!("buffer has {} bytes but {width}x{height} with stride {stride} needs {needed}", data.len()).into());
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+            stride,
+            format,
+        })
+    }
+
+    /// Wraps tightly packed rows (`stride == width * 4`).
+    pub fn packed(data: &'a mut [u8], width: u32, height: u32, format: PixelFormat) -> Result<Self, Error> {
+        Self::new(data, width, height, width as usize * PixelFormat::BYTES_PER_PIXEL, format)
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the distance between the starts of two rows, in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Copies the premultiplied RGBA canvas, which has the same size, into the buffer.
+    pub(crate) fn write(&mut self, canvas: &tiny_skia::Pixmap) {
+        let row = self.width as usize * PixelFormat::BYTES_PER_PIXEL;
+        if row == 0 {
+            return;
+        }
+        for (source, target) in canvas.data().chunks_exact(row).zip(self.data.chunks_mut(self.stride)) {
+            for (pixel, out) in source.chunks_exact(4).zip(target[..row].chunks_exact_mut(4)) {
+                out.copy_from_slice(&self.format.encode([pixel[0], pixel[1], pixel[2], pixel[3]]));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::background::Color;
+    use crate::core::renderer::{Quad, Renderer as _};
+    use crate::layout::Rectangle;
+    use crate::render::Renderer;
+
+    /// Presents half-transparent orange over the left half of 4x2 pixels, cleared to
+    /// transparent, into rows padded to 20 bytes with 7s.
+    fn present(format: PixelFormat) -> Vec<u8> {
+        let mut renderer = Renderer::new();
+        renderer.reset(Rectangle::new(0.0, 0.0, 4.0, 2.0));
+        renderer.fill_quad(Quad::new(Rectangle::new(0.0, 0.0, 2.0, 2.0)), Color::new(1.0, 0.5, 0.0, 0.5));
+        let mut data = vec![7u8; 20 * 2];
+        let mut target = PixelBuffer::new(&mut data, 4, 2, 20, format).unwrap();
+        renderer.present_to(&mut target, Color::TRANSPARENT);
+        data
+    }
+
+    #[test]
+    fn test_rgba_has_straight_alpha() {
+        assert_eq!(present(PixelFormat::Rgba8)[20..24], [255, 128, 0, 128]);
+    }
+
+    #[test]
+    fn test_bgra_swaps_red_and_blue() {
+        assert_eq!(present(PixelFormat::Bgra8)[..4], [0, 128, 255, 128]);
+    }
+
+    #[test]
+    fn test_premultiplied_argb_puts_alpha_first() {
+        assert_eq!(present(PixelFormat::PremultipliedArgb8)[..4], [128, 128, 64, 0]);
+    }
+
+    #[test]
+    fn test_transparent_background_clears_to_zero() {
+        assert_eq!(present(PixelFormat::Rgba8)[20 + 12..20 + 16], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_row_padding_is_untouched() {
+        assert_eq!(present(PixelFormat::Rgba8)[16..20], [7; 4]);
+    }
+
+    #[test]
+    fn test_buffers_too_short_for_their_size_are_rejected() {
+        let mut short = [0u8; 30];
+        assert!(PixelBuffer::new(&mut short, 4, 2, 16, PixelFormat::Rgba8).is_err());
+        assert!(PixelBuffer::new(&mut short, 4, 1, 12, PixelFormat::Rgba8).is_err());
+    }
+}
@@ -0,0 +1,718 @@
+//! Cache of shaped text, shared between measuring and drawing.
+//!
+//! Shaping is the expensive part of text rendering. Buffers are shaped once in
+//! logical pixels and reused by layout, drawing (at any scale factor) and later
+//! frames. Entries that were not used since the previous [`trim`](TextCache::trim)
+//! are evicted.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use std::ops::Range;
+
+use cosmic_text::{fontdb, Align, Attrs, Buffer, CacheKeyFlags, FeatureTag, FontFeatures, FontSystem, Metrics, Shaping, Style};
+
+use crate::core::font::{self, Family, Font};
+use crate::core::text::{Alignment, LineHeight, Span, Text, Truncation, Typography};
+use crate::layout::{LayoutDirection, Size};
+
+/// Usage of the shaped text cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextCacheStats {
+    /// Number of shaped paragraphs kept.
+    pub entries: usize,
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups that had to shape the text.
+    pub misses: u64,
+}
+
+/// What a shaped paragraph depends on.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Key<'a> {
+    pub content: &'a str,
+    /// Styled runs of `content`.
+    pub spans: &'a [Span],
+    pub font: &'a Font,
+    pub size: f32,
+    /// Wrap width in logical pixels, or `None` to lay out on as few lines as possible.
+    pub width: Option<f32>,
+    pub line_limit: Option<usize>,
+    pub truncation: Truncation,
+    pub alignment: Alignment,
+    /// The side leading and trailing alignment refer to.
+    pub direction: LayoutDirection,
+    pub typography: &'a Typography,
+}
+
+impl Key<'_> {
+    fn hash(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.content.hash(&mut hasher);
+        self.font.hash(&mut hasher);
+        // Only what changes shaping; colors and decorations are looked up when drawing
+        for span in self.spans {
+            span.range.hash(&mut hasher);
+            span.attributes.weight.hash(&mut hasher);
+            span.attributes.italic.hash(&mut hasher);
+            span.attributes.size.map(f32::to_bits).hash(&mut hasher);
+        }
+        self.size.to_bits().hash(&mut hasher);
+        self.width.map(f32::to_bits).hash(&mut hasher);
+        self.line_limit.hash(&mut hasher);
+        self.truncation.hash(&mut hasher);
+        self.alignment.hash(&mut hasher);
+        self.direction.hash(&mut hasher);
+        match self.typography.line_height {
+            LineHeight::Relative(factor) => (0u8, factor.to_bits()).hash(&mut hasher),
+            LineHeight::Absolute(height) => (1u8, height.to_bits()).hash(&mut hasher),
+        }
+        self.typography.tracking.to_bits().hash(&mut hasher);
+        self.typography.features.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A shaped paragraph.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub buffer: Buffer,
+    /// The size the laid out lines actually cover.
+    pub min_bounds: Size,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TextCache {
+    entries: HashMap<u64, Entry>,
+    /// The last wrap width each paragraph was laid out at, by the hash of its
+    /// unbounded key, with the hash of that entry.
+    wrapped: HashMap<u64, (u64, f32)>,
+    recently_used: HashSet<u64>,
+    hits: u64,
+    misses: u64,
+}
+
+impl TextCache {
+    /// Returns the shaped paragraph for `key`, shaping it on first use.
+    pub fn allocate(&mut self, font_system: &mut FontSystem, key: Key<'_>) -> &Entry {
+        let hash = key.hash();
+        self.recently_used.insert(hash);
+
+        if self.entries.contains_key(&hash) {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            let styled: Vec<_> = key.spans.iter().enumerate().map(|(index, span)| (index, span.range.clone())).collect();
+            let mut buffer = shape(font_system, &key, key.content, &styled);
+            if let Some(limit) = key.line_limit {
+                if buffer.layout_runs().count() > limit {
+                    buffer = truncate(font_system, &key, limit);
+                }
+            }
+
+            let (width, height) = buffer
+                .layout_runs()
+                .fold((0.0f32, 0.0f32), |(width, height), run| {
+                    (run.line_w.max(width), height + run.line_height)
+                });
+            self.entries.insert(
+                hash,
+                Entry {
+                    buffer,
+                    min_bounds: Size::new(width, height),
+                },
+            );
+        }
+
+        &self.entries[&hash]
+    }
+
+    /// Returns the paragraph laid out for drawing in `width`.
+    ///
+    /// A layout is reused when its lines already fit: the paragraph on as few lines as
+    /// possible, or wrapped at a larger width. Wrapping again would break the same
+    /// lines, and this is the common case of drawing text in the bounds it was
+    /// measured for. Only valid for left-aligned text.
+    pub fn allocate_for_width(&mut self, font_system: &mut FontSystem, key: Key<'_>, width: f32) -> &Entry {
+        let unbounded = Key { width: None, ..key };
+        let base = unbounded.hash();
+        let fits = |entry: &Entry| entry.min_bounds.width <= width + 0.5;
+
+        if self.entries.get(&base).is_some_and(fits) {
+            return self.allocate(font_system, unbounded);
+        }
+        if let Some(&(hash, wrap_width)) = self.wrapped.get(&base) {
+            if width <= wrap_width + 0.5 && self.entries.get(&hash).is_some_and(fits) {
+                self.hits += 1;
+                self.recently_used.insert(hash);
+                return &self.entries[&hash];
+            }
+        }
+
+        let wrapped = Key { width: Some(width), ..key };
+        self.wrapped.insert(base, (wrapped.hash(), width));
+        self.allocate(font_system, wrapped)
+    }
+
+    /// Returns the paragraph laid out for drawing `text` in its bounds.
+    pub fn allocate_for_text(&mut self, font_system: &mut FontSystem, text: &Text) -> &Entry {
+        let key = Key {
+            content: &text.content,
+            spans: &text.spans,
+            font: &text.font,
+            size: text.size,
+            width: None,
+            line_limit: text.line_limit,
+            truncation: text.truncation,
+            alignment: text.alignment,
+            direction: text.direction,
+            typography: &text.typography,
+        };
+        // Lines are aligned within the bounds, so aligned text is always laid out in them
+        if text.alignment == Alignment::Leading && text.direction == LayoutDirection::LeftToRight {
+            self.allocate_for_width(font_system, key, text.bounds.width)
+        } else {
+            self.allocate(font_system, Key { width: Some(text.bounds.width), ..key })
+        }
+    }
+
+    /// Evicts paragraphs not used since the last trim.
+    pub fn trim(&mut self) {
+        let recently_used = std::mem::take(&mut self.recently_used);
+        self.entries.retain(|hash, _| recently_used.contains(hash));
+        let entries = &self.entries;
+        self.wrapped.retain(|_, (hash, _)| entries.contains_key(hash));
+    }
+
+    pub fn stats(&self) -> TextCacheStats {
+        TextCacheStats {
+            entries: self.entries.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+/// Shapes `content`, whose `styled` ranges use the attributes of the span with the
+/// given index in `key.spans`.
+fn shape(font_system: &mut FontSystem, key: &Key<'_>, content: &str, styled: &[(usize, Range<usize>)]) -> Buffer {
+    let typography = key.typography;
+    let mut buffer = Buffer::new(font_system, Metrics::new(key.size, typography.line_height.resolve(key.size)));
+    buffer.set_size(font_system, key.width, None);
+    let mut default = attrs(font_system, key.font);
+    if !typography.features.is_empty() {
+        let mut features = FontFeatures::new();
+        for feature in &typography.features {
+            features.set(FeatureTag::new(&feature.tag), feature.value);
+        }
+        default = default.font_features(features);
+    }
+    let default = tracked(default, typography, key.size);
+    if styled.is_empty() {
+        buffer.set_text(font_system, content, &default, Shaping::Advanced, None);
+    } else {
+        let runs = runs(font_system, content, key.spans, styled, &default, typography);
+        buffer.set_rich_text(
+            font_system,
+            runs.iter().map(|(range, attrs)| (&content[range.clone()], attrs.clone())),
+            &default,
+            Shaping::Advanced,
+            None,
+        );
+    }
+
+    // Leading is the left side left to right, whatever the direction of each line
+    let align = match (key.alignment, key.direction) {
+        (Alignment::Leading, LayoutDirection::LeftToRight) | (Alignment::Trailing, LayoutDirection::RightToLeft) => Align::Left,
+        (Alignment::Leading, LayoutDirection::RightToLeft) | (Alignment::Trailing, LayoutDirection::LeftToRight) => Align::Right,
+        (Alignment::Center, _) => Align::Center,
+        (Alignment::Justified, _) => Align::Justified,
+    };
+    // Lines are laid out from the left unless they are right to left
+    if align != Align::Left || buffer.layout_runs().any(|run| run.rtl) {
+        for line in buffer.lines.iter_mut() {
+            line.set_align(Some(align));
+        }
+        buffer.shape_until_scroll(font_system, false);
+    }
+    buffer
+}
+
+/// Shapes the longest excerpt of the paragraph, cut as `key.truncation` says and
+/// marked with an ellipsis, that fits in `limit` lines.
+fn truncate(font_system: &mut FontSystem, key: &Key<'_>, limit: usize) -> Buffer {
+    let boundaries: Vec<usize> = key.content.char_indices().map(|(index, _)| index).collect();
+    let excerpt = |kept: usize| excerpt(key.content, key.spans, &boundaries, key.truncation, kept);
+
+    // Binary search the number of characters kept
+    let (mut fitting, mut too_many) = (0, boundaries.len());
+    while too_many - fitting > 1 {
+        let kept = (fitting + too_many) / 2;
+        let (content, styled) = excerpt(kept);
+        if shape(font_system, key, &content, &styled).layout_runs().count() <= limit {
+            fitting = kept;
+        } else {
+            too_many = kept;
+        }
+    }
+
+    let (content, styled) = excerpt(fitting);
+    shape(font_system, key, &content, &styled)
+}
+
+/// Keeps `kept` characters of `content` around an ellipsis and moves the styled
+/// ranges of `spans` along with them.
+fn excerpt(
+    content: &str,
+    spans: &[Span],
+    boundaries: &[usize],
+    truncation: Truncation,
+    kept: usize,
+) -> (String, Vec<(usize, Range<usize>)>) {
+    const ELLIPSIS: &str = "\u{2026}";
+
+    let (head, tail) = match truncation {
+        Truncation::Head => (0, kept),
+        Truncation::Middle => (kept.div_ceil(2), kept / 2),
+        Truncation::Tail => (kept, 0),
+    };
+    let at = |chars: usize| boundaries.get(chars).copied().unwrap_or(content.len());
+    // No whitespace next to the ellipsis
+    let prefix = 0..content[..at(head)].trim_end().len();
+    let suffix_start = at(boundaries.len() - tail);
+    let suffix = content.len() - content[suffix_start..].trim_start().len()..content.len();
+
+    let mut excerpt = String::with_capacity(prefix.len() + ELLIPSIS.len() + suffix.len());
+    excerpt.push_str(&content[prefix.clone()]);
+    excerpt.push_str(ELLIPSIS);
+    excerpt.push_str(&content[suffix.clone()]);
+
+    let shift = prefix.end + ELLIPSIS.len();
+    let mut styled = Vec::new();
+    for (index, span) in spans.iter().enumerate() {
+        let before = span.range.start.max(prefix.start)..span.range.end.min(prefix.end);
+        if before.start < before.end {
+            styled.push((index, before));
+        }
+    }
+    for (index, span) in spans.iter().enumerate() {
+        let after = span.range.start.max(suffix.start)..span.range.end.min(suffix.end);
+        if after.start < after.end {
+            styled.push((index, after.start - suffix.start + shift..after.end - suffix.start + shift));
+        }
+    }
+    (excerpt, styled)
+}
+
+/// Splits `content` into consecutive runs with their shaping attributes. Styled runs
+/// carry the index of their span plus one as metadata; unstyled gaps carry zero.
+fn runs<'a>(
+    font_system: &FontSystem,
+    content: &str,
+    spans: &[Span],
+    styled: &[(usize, Range<usize>)],
+    default: &Attrs<'a>,
+    typography: &Typography,
+) -> Vec<(Range<usize>, Attrs<'a>)> {
+    let mut runs = Vec::new();
+    let mut end = 0;
+    for (index, range) in styled {
+        let (index, span) = (*index, &spans[*index]);
+        // Clamp to the content and to char boundaries; overlapping spans are cut
+        let start = range.start.max(end).min(content.len());
+        let stop = range.end.min(content.len());
+        if !content.is_char_boundary(start) || !content.is_char_boundary(stop) || start >= stop {
+            continue;
+        }
+        if end < start {
+            runs.push((end..start, default.clone()));
+        }
+
+        let attributes = &span.attributes;
+        let mut attrs = default.clone().metadata(index + 1);
+        if let Some(weight) = attributes.weight {
+            attrs = attrs.weight(cosmic_text::Weight(weight.0));
+        }
+        if attributes.italic {
+            attrs = attrs.style(Style::Italic);
+        }
+        if let Some(size) = attributes.size {
+            attrs = tracked(attrs, typography, size).metrics(Metrics::new(size, typography.line_height.resolve(size)));
+        }
+        runs.push((start..stop, synthesize_italic(font_system, attrs)));
+        end = stop;
+    }
+    if end < content.len() {
+        runs.push((end..content.len(), default.clone()));
+    }
+    runs
+}
+
+/// Applies the tracking of `typography`, which cosmic-text measures in ems, to text
+/// of the given size.
+fn tracked<'a>(attrs: Attrs<'a>, typography: &Typography, size: f32) -> Attrs<'a> {
+    if typography.tracking == 0.0 || size <= 0.0 {
+        return attrs;
+    }
+    attrs.letter_spacing(typography.tracking / size)
+}
+
+/// Resolves a font descriptor to shaping attributes, picking the first available
+/// family of its fallback chain.
+pub(crate) fn attrs<'a>(font_system: &FontSystem, font: &'a Font) -> Attrs<'a> {
+    let family = font
+        .families
+        .iter()
+        .map(|family| match family {
+            Family::Name(name) => fontdb::Family::Name(name),
+            Family::Serif => fontdb::Family::Serif,
+            Family::SansSerif => fontdb::Family::SansSerif,
+            Family::Monospace => fontdb::Family::Monospace,
+            Family::Cursive => fontdb::Family::Cursive,
+            Family::Fantasy => fontdb::Family::Fantasy,
+        })
+        .find(|family| match family {
+            fontdb::Family::Name(name) => font_system.db().faces().any(|face| {
+                face.families.iter().any(|(family, _)| family.eq_ignore_ascii_case(name))
+            }),
+            _ => true,
+        })
+        .unwrap_or(fontdb::Family::SansSerif);
+
+    let attrs = Attrs::new()
+        .family(family)
+        .weight(cosmic_text::Weight(font.weight.0))
+        .style(match font.style {
+            font::Style::Normal => Style::Normal,
+            font::Style::Italic => Style::Italic,
+            font::Style::Oblique => Style::Oblique,
+        })
+        .stretch(match font.stretch {
+            font::Stretch::UltraCondensed => fontdb::Stretch::UltraCondensed,
+            font::Stretch::ExtraCondensed => fontdb::Stretch::ExtraCondensed,
+            font::Stretch::Condensed => fontdb::Stretch::Condensed,
+            font::Stretch::SemiCondensed => fontdb::Stretch::SemiCondensed,
+            font::Stretch::Normal => fontdb::Stretch::Normal,
+            font::Stretch::SemiExpanded => fontdb::Stretch::SemiExpanded,
+            font::Stretch::Expanded => fontdb::Stretch::Expanded,
+            font::Stretch::ExtraExpanded => fontdb::Stretch::ExtraExpanded,
+            font::Stretch::UltraExpanded => fontdb::Stretch::UltraExpanded,
+        });
+    synthesize_italic(font_system, attrs)
+}
+
+/// Slants the glyphs when italic is asked for but the matching face is upright.
+fn synthesize_italic<'a>(font_system: &FontSystem, attrs: Attrs<'a>) -> Attrs<'a> {
+    let flags = attrs.cache_key_flags - CacheKeyFlags::FAKE_ITALIC;
+    if attrs.style == Style::Normal {
+        return attrs.cache_key_flags(flags);
+    }
+    let query = fontdb::Query {
+        families: &[attrs.family],
+        weight: attrs.weight,
+        stretch: attrs.stretch,
+        style: attrs.style,
+    };
+    let upright = font_system
+        .db()
+        .query(&query)
+        .and_then(|id| font_system.db().face(id))
+        .is_some_and(|face| face.style == Style::Normal);
+    if upright {
+        attrs.cache_key_flags(flags | CacheKeyFlags::FAKE_ITALIC)
+    } else {
+        attrs.cache_key_flags(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::background::Color;
+    use crate::core::text::{AttributedString, Attributes, Feature};
+    use crate::layout::{layout, Limits, Rectangle};
+    use crate::render::{bundled_font_system, text_primitive, Renderer};
+    use crate::view::Text as TextView;
+    use crate::View;
+
+    fn frame(renderer: &mut Renderer, view: &View) {
+        let mut buffer = vec![0u32; 200 * 60];
+        let node = layout(view, Limits::loose(200.0, 60.0), renderer);
+        renderer.draw(view, &node, &mut buffer, 200, 60, 0x00FF_FFFF, 0.0, 0.0);
+    }
+
+    #[test]
+    fn test_drawing_reuses_what_layout_shaped() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        frame(&mut renderer, &View::vstack(vec![View::text("Hello"), View::text("World")]));
+        let stats = renderer.text_cache_stats();
+        assert_eq!((stats.entries, stats.misses), (2, 2));
+        assert!(stats.hits >= 2);
+    }
+
+    #[test]
+    fn test_later_frames_shape_nothing() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        let view = View::vstack(vec![View::text("Hello"), View::text("World")]);
+        frame(&mut renderer, &view);
+        let misses = renderer.text_cache_stats().misses;
+        frame(&mut renderer, &view);
+        assert_eq!(renderer.text_cache_stats().misses, misses);
+    }
+
+    #[test]
+    fn test_text_no_longer_shown_is_evicted_at_the_end_of_the_frame() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        frame(&mut renderer, &View::vstack(vec![View::text("Hello"), View::text("World")]));
+        let misses = renderer.text_cache_stats().misses;
+        frame(&mut renderer, &View::text("Hello"));
+        let stats = renderer.text_cache_stats();
+        assert_eq!((stats.entries, stats.misses), (1, misses));
+    }
+
+    const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
+
+    /// Draws "Status: FAILED now" with "FAILED" bold, red, underlined and on yellow, in
+    /// 300x40 pixels of white.
+    fn draw_status() -> Vec<u32> {
+        let rich = AttributedString::new()
+            .plain("Status: ")
+            .styled("FAILED", Attributes::new().bold().color(RED).underline().background(Color::new(1.0, 1.0, 0.0, 1.0)))
+            .plain(" now");
+        let view = View::from(TextView::rich(rich));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(300.0, 40.0), &renderer);
+        let mut buffer = vec![0u32; 300 * 40];
+        renderer.draw(&view, &node, &mut buffer, 300, 40, 0x00FF_FFFF, 0.0, 0.0);
+        buffer
+    }
+
+    /// Returns the first and last of the 300 columns with a pixel that matches.
+    fn columns(buffer: &[u32], matches: impl Fn(u32) -> bool) -> (usize, usize) {
+        let xs: Vec<usize> = (0..300).filter(|x| (0..40).any(|y| matches(buffer[y * 300 + x]))).collect();
+        (xs[0], xs[xs.len() - 1])
+    }
+
+    fn channels(pixel: u32) -> (u32, u32, u32) {
+        ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF)
+    }
+
+    fn is_red(pixel: u32) -> bool {
+        let (r, g, b) = channels(pixel);
+        r > 200 && g < 100 && b < 100
+    }
+
+    #[test]
+    fn test_sized_spans_grow_the_paragraph() {
+        let renderer = Renderer::with_bundled_fonts();
+        let plain = renderer.measure_text("Status: FAILED", 16.0);
+        let larger = renderer.measure_attributed_text(
+            &AttributedString::new().plain("Status: ").styled("FAILED", Attributes::new().size(32.0)),
+            16.0,
+        );
+        assert!(larger.height > plain.height && larger.width > plain.width, "{plain:?} vs {larger:?}");
+    }
+
+    #[test]
+    fn test_span_is_drawn_in_its_colors_between_the_plain_runs() {
+        let buffer = draw_status();
+        let (red_start, red_end) = columns(&buffer, is_red);
+        let (highlight_start, highlight_end) = columns(&buffer, |pixel| pixel == 0xFFFF00);
+        let (black_start, black_end) = columns(&buffer, |pixel| {
+            let (r, g, b) = channels(pixel);
+            r < 60 && g < 60 && b < 60
+        });
+        assert!(black_start < highlight_start && highlight_end < black_end, "plain runs surround the span");
+        assert!(highlight_start <= red_start && red_end <= highlight_end, "the span is red on yellow");
+    }
+
+    #[test]
+    fn test_span_is_underlined_below_the_baseline() {
+        let buffer = draw_status();
+        let (red_start, red_end) = columns(&buffer, is_red);
+        let underline = (0..40).find(|y| (red_start + 1..red_end).all(|x| buffer[y * 300 + x] == 0xFF0000));
+        assert!(underline.is_some_and(|y| y > 12), "{underline:?}");
+    }
+
+    #[test]
+    fn test_first_installed_family_of_a_fallback_chain_is_used() {
+        let renderer = Renderer::with_bundled_fonts();
+        let roboto = renderer.measure_text_with_font("Fallback", &Font::family("Roboto"), 20.0);
+        let chain = Font::family("No Such Family").fallback("Roboto");
+        assert_eq!(renderer.measure_text_with_font("Fallback", &chain, 20.0), roboto);
+    }
+
+    /// Draws `text` at 40px and returns the leftmost ink of its top and bottom rows.
+    fn stem(text: TextView) -> (usize, usize) {
+        let view = View::from(text.size(40.0));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(60.0, 60.0), &renderer);
+        let mut buffer = vec![0u32; 60 * 60];
+        renderer.draw(&view, &node, &mut buffer, 60, 60, 0x00FF_FFFF, 0.0, 0.0);
+        let rows: Vec<usize> = (0..60).filter(|y| buffer[y * 60..y * 60 + 60].iter().any(|pixel| *pixel & 0xFF < 128)).collect();
+        let leftmost = |y: usize| (0..60).find(|x| buffer[y * 60 + x] & 0xFF < 128).unwrap();
+        (leftmost(rows[0]), leftmost(rows[rows.len() - 1]))
+    }
+
+    #[test]
+    fn test_upright_text_has_straight_stems() {
+        let (top, bottom) = stem(TextView::new("l"));
+        assert_eq!(top, bottom);
+    }
+
+    #[test]
+    fn test_italic_is_slanted_when_the_font_has_no_italic_face() {
+        // Roboto is bundled without an italic face.
+        let (top, bottom) = stem(TextView::new("l").italic());
+        assert!(top > bottom + 3, "top {top}, bottom {bottom}");
+    }
+
+    const FOX: &str = "The quick brown fox jumps over the lazy dog";
+
+    /// Shapes [`FOX`] 120px wide and returns the text of each line.
+    fn fox_lines(line_limit: Option<usize>, truncation: Truncation) -> Vec<String> {
+        let (font, typography) = (Font::default(), Typography::default());
+        let key = Key {
+            content: FOX,
+            spans: &[],
+            font: &font,
+            size: 16.0,
+            width: Some(120.0),
+            line_limit,
+            truncation,
+            alignment: Alignment::Leading,
+            direction: LayoutDirection::LeftToRight,
+            typography: &typography,
+        };
+        let mut cache = TextCache::default();
+        let entry = cache.allocate(&mut bundled_font_system().write().unwrap(), key);
+        assert!(entry.min_bounds.width <= 120.0);
+        entry
+            .buffer
+            .layout_runs()
+            .map(|run| run.text[run.glyphs[0].start..run.glyphs[run.glyphs.len() - 1].end].trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_text_wraps_to_its_width() {
+        assert!(fox_lines(None, Truncation::Tail).len() > 2);
+    }
+
+    #[test]
+    fn test_tail_truncation_ends_the_last_line_with_an_ellipsis() {
+        let lines = fox_lines(Some(2), Truncation::Tail);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("The quick") && lines[1].ends_with('\u{2026}'), "{lines:?}");
+    }
+
+    #[test]
+    fn test_head_truncation_starts_with_an_ellipsis() {
+        let line = &fox_lines(Some(1), Truncation::Head)[0];
+        assert!(line.starts_with('\u{2026}') && line.ends_with("lazy dog"), "{line}");
+    }
+
+    #[test]
+    fn test_middle_truncation_keeps_both_ends() {
+        let line = &fox_lines(Some(1), Truncation::Middle)[0];
+        assert!(line.starts_with("The") && line.ends_with("dog") && line.contains('\u{2026}'), "{line}");
+    }
+
+    #[test]
+    fn test_centered_lines_share_a_center() {
+        let view = View::from(TextView::new("i\nwwwwww").size(20.0).multiline_text_alignment(Alignment::Center));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(200.0, 60.0), &renderer);
+        let mut buffer = vec![0u32; 200 * 60];
+        renderer.draw(&view, &node, &mut buffer, 200, 60, 0x00FF_FFFF, 0.0, 0.0);
+        let center = |rows: std::ops::Range<usize>| {
+            let ink: Vec<usize> = (0..200).filter(|x| rows.clone().any(|y| buffer[y * 200 + x] & 0xFF < 128)).collect();
+            (ink[0] + ink[ink.len() - 1]) / 2
+        };
+        let (top, bottom) = (center(0..24), center(24..48));
+        assert!(top.abs_diff(bottom) <= 2, "top line centered at {top}, bottom at {bottom}");
+    }
+
+    /// Shapes "fit" with `typography` and returns how many glyphs it takes.
+    fn fit_glyphs(typography: &Typography) -> usize {
+        let font = Font::default();
+        let key = Key {
+            content: "fit",
+            spans: &[],
+            font: &font,
+            size: 20.0,
+            width: None,
+            line_limit: None,
+            truncation: Truncation::Tail,
+            alignment: Alignment::Leading,
+            direction: LayoutDirection::LeftToRight,
+            typography,
+        };
+        let mut cache = TextCache::default();
+        let entry = cache.allocate(&mut bundled_font_system().write().unwrap(), key);
+        entry.buffer.layout_runs().map(|run| run.glyphs.len()).sum()
+    }
+
+    #[test]
+    fn test_standard_ligatures_can_be_disabled() {
+        let mut no_ligatures = Typography::default();
+        no_ligatures.set_feature(Feature::disable(&Feature::STANDARD_LIGATURES));
+        assert!(fit_glyphs(&Typography::default()) < fit_glyphs(&no_ligatures), "fi is a ligature by default");
+    }
+
+    #[test]
+    fn test_lines_are_drawn_at_the_line_height() {
+        let view = View::from(TextView::new("a\nb").size(20.0).line_height(LineHeight::Absolute(40.0)));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(40.0, 80.0), &renderer);
+        let mut buffer = vec![0u32; 40 * 80];
+        renderer.draw(&view, &node, &mut buffer, 40, 80, 0x00FF_FFFF, 0.0, 0.0);
+        // The second line starts below the first 40px.
+        let rows: Vec<usize> = (0..80).filter(|y| buffer[y * 40..y * 40 + 40].iter().any(|pixel| *pixel & 0xFF < 128)).collect();
+        assert!(rows.iter().any(|y| *y < 40) && rows.iter().any(|y| *y >= 40 + 10), "{rows:?}");
+    }
+
+    const HEBREW: &str = "\u{5E9}\u{5DC}\u{5D5}\u{5DD}";
+
+    /// Returns `content` as text 200px wide, laid out in `direction`.
+    fn directed(content: &str, direction: LayoutDirection) -> Text {
+        text_primitive(&TextView::new(content), Rectangle::new(0.0, 0.0, 200.0, 40.0), direction)
+    }
+
+    /// Returns where the caret goes before and after `range` of `text.content`.
+    fn carets(renderer: &Renderer, text: &Text, range: Range<usize>) -> (f32, f32) {
+        (renderer.caret_bounds(text, range.start).x, renderer.caret_bounds(text, range.end).x)
+    }
+
+    #[test]
+    fn test_text_starts_at_the_leading_edge_of_its_direction() {
+        let renderer = Renderer::with_bundled_fonts();
+        assert_eq!(renderer.caret_bounds(&directed("abc", LayoutDirection::LeftToRight), 0).x, 0.0);
+        let (start, end) = carets(&renderer, &directed("abc", LayoutDirection::RightToLeft), 0..3);
+        assert!(150.0 < start && start < end && end <= 200.5, "{start}..{end}");
+    }
+
+    #[test]
+    fn test_hebrew_reads_right_to_left_in_either_direction() {
+        let renderer = Renderer::with_bundled_fonts();
+        for (direction, edge) in [(LayoutDirection::LeftToRight, 0.0), (LayoutDirection::RightToLeft, 200.0)] {
+            let text = directed(HEBREW, direction);
+            let (start, end) = carets(&renderer, &text, 0..HEBREW.len());
+            assert!(end < start, "{direction:?}: {start}..{end}");
+            assert!((edge - start).abs().min((edge - end).abs()) < 0.5, "{direction:?}: {start}..{end}");
+            assert_eq!(renderer.hit_text(&text, start - 1.0, 5.0), 0, "{direction:?}");
+        }
+    }
+
+    #[test]
+    fn test_right_to_left_paragraph_starting_with_latin_reads_left_to_right() {
+        // The layout direction only aligns the line; the first strong character sets the order.
+        let renderer = Renderer::with_bundled_fonts();
+        let mixed = format!("abc {HEBREW}");
+        let text = directed(&mixed, LayoutDirection::RightToLeft);
+        let (start, end) = carets(&renderer, &text, 0..3);
+        assert!(start < end, "{start}..{end}");
+        let right = (0..=mixed.len()).filter(|offset| mixed.is_char_boundary(*offset)).map(|offset| renderer.caret_bounds(&text, offset).x);
+        assert!((right.fold(0.0, f32::max) - 200.0).abs() < 0.5);
+    }
+}
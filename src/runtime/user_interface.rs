@@ -22,6 +22,7 @@ pub struct UserInterface<'a, Message, Renderer> {
     bounds: Size,
 }
 
+#[allow(dead_code)] // Overlays are not laid out yet
 struct Overlay {
     layout: Node,
     interaction: Interaction,
@@ -0,0 +1,358 @@
+//! Golden-image snapshot testing.
+//!
+//! A snapshot renders a [`View`] headlessly with the bundled Roboto font only (no
+//! system fonts) and compares it against a stored PNG. Differences are measured per
+//! pixel in the YIQ color space, which weighs brightness changes more than hue changes,
+//! the way the eye does.
+//!
+//! Set the `SHELLUI_BLESS` environment variable to write the rendered images as the
+//! new goldens instead of comparing.
+
+use std::path::{Path, PathBuf};
+
+use crate::layout::Size;
+use crate::render::{decode_image, Renderer, RgbaImage};
+use crate::view::View;
+
+/// Environment variable that makes snapshots overwrite their goldens.
+pub const BLESS_ENV: &str = "SHELLUI_BLESS";
+
+/// Largest possible YIQ delta between two colors.
+const MAX_DELTA: f32 = 35215.0;
+
+/// Renders views and compares them against golden PNG files.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    size: Size,
+    scale: f32,
+    tolerance: f32,
+    max_differing_pixels: usize,
+    bless: bool,
+}
+
+/// Why a snapshot did not match its golden.
+#[derive(Debug)]
+pub enum Mismatch {
+    /// There is no golden image yet.
+    MissingGolden { golden: PathBuf },
+    /// The golden exists but could not be read or decoded.
+    InvalidGolden { golden: PathBuf, error: String },
+    /// Blessing was requested but the golden could not be written.
+    Bless { golden: PathBuf, error: String },
+    /// The rendered image has a different size than the golden.
+    Size {
+        golden: PathBuf,
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// Too many pixels differ by more than the tolerance.
+    Pixels {
+        golden: PathBuf,
+        differing: usize,
+        diff: PathBuf,
+    },
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::MissingGolden { golden } => write!(
+                f,
+                "golden image {} does not exist; run with {BLESS_ENV}=1 to create it",
+                golden.display()
+            ),
+            Mismatch::InvalidGolden { golden, error } => {
+                write!(f, "cannot read golden image {}: {error}", golden.display())
+            }
+            Mismatch::Bless { golden, error } => {
+                write!(f, "cannot write golden image {}: {error}", golden.display())
+            }
+            Mismatch::Size { golden, expected, actual } => write!(
+                f,
+                "snapshot is {}x{} but golden image {} is {}x{}",
+                actual.0,
+                actual.1,
+                golden.display(),
+                expected.0,
+                expected.1
+            ),
+            Mismatch::Pixels { golden, differing, diff } => write!(
+                f,
+                "{differing} pixels differ from golden image {} (diff written to {}); run with {BLESS_ENV}=1 to update it",
+                golden.display(),
+                diff.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+impl Snapshot {
+    /// A snapshot of `size` logical pixels at scale 1.0 with a small default tolerance.
+    ///
+    /// Blessing is enabled if the [`BLESS_ENV`] environment variable is set.
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            scale: 1.0,
+            tolerance: 0.1,
+            max_differing_pixels: 0,
+            bless: std::env::var_os(BLESS_ENV).is_some_and(|value| !value.is_empty() && value != "0"),
+        }
+    }
+
+    /// Render at the given scale factor.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set how much a pixel may differ before it counts, from 0.0 (exact) to 1.0 (anything).
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Allow up to `count` pixels to differ by more than the tolerance.
+    pub fn max_differing_pixels(mut self, count: usize) -> Self {
+        self.max_differing_pixels = count;
+        self
+    }
+
+    /// Overwrite the golden with the rendered image instead of comparing.
+    pub fn bless(mut self, bless: bool) -> Self {
+        self.bless = bless;
+        self
+    }
+
+    /// Renders `view` with the bundled font only.
+    pub fn render(&self, view: &View) -> RgbaImage {
+        Renderer::with_bundled_fonts().render_to_image(view, self.size, self.scale)
+    }
+
+    /// Renders `view` and compares it against the PNG at `golden`.
+    ///
+    /// On a pixel mismatch, the rendered image and a diff image are written next to
+    /// the golden as `<name>.actual.png` and `<name>.diff.png`.
+    pub fn compare(&self, view: &View, golden: impl AsRef<Path>) -> Result<(), Mismatch> {
+        let golden = golden.as_ref();
+        let actual = self.render(view);
+
+        if self.bless {
+            let result = golden
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(|error| error.to_string())
+                .and_then(|()| actual.save_png(golden).map_err(|error| error.to_string()));
+            return result.map_err(|error| Mismatch::Bless {
+                golden: golden.to_path_buf(),
+                error,
+            });
+        }
+
+        let bytes = match std::fs::read(golden) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Err(Mismatch::MissingGolden {
+                    golden: golden.to_path_buf(),
+                })
+            }
+            Err(error) => {
+                return Err(Mismatch::InvalidGolden {
+                    golden: golden.to_path_buf(),
+                    error: error.to_string(),
+                })
+            }
+        };
+        let expected = decode_image(&bytes).map_err(|error| Mismatch::InvalidGolden {
+            golden: golden.to_path_buf(),
+            error: error.to_string(),
+        })?;
+
+        if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+            return Err(Mismatch::Size {
+                golden: golden.to_path_buf(),
+                expected: (expected.width(), expected.height()),
+                actual: (actual.width(), actual.height()),
+            });
+        }
+
+        let threshold = MAX_DELTA * self.tolerance * self.tolerance;
+        let mut differing = 0;
+        let mut diff = Vec::with_capacity(actual.pixels().len());
+
+        for (expected, actual) in expected.pixels().iter().zip(actual.pixels().chunks_exact(4)) {
+            let expected = expected.demultiply();
+            let expected = [expected.red(), expected.green(), expected.blue(), expected.alpha()];
+            let actual = [actual[0], actual[1], actual[2], actual[3]];
+
+            if delta(expected, actual) > threshold {
+                differing += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Unchanged pixels are shown as faded grayscale for context.
+                let gray = (255.0 - (255.0 - luma(expected)) * 0.25) as u8;
+                diff.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+
+        if differing <= self.max_differing_pixels {
+            return Ok(());
+        }
+
+        let diff_path = sibling(golden, "diff");
+        if let Some(image) = RgbaImage::from_pixels(actual.width(), actual.height(), diff) {
+            let _ = image.save_png(&diff_path);
+        }
+        let _ = actual.save_png(sibling(golden, "actual"));
+
+        Err(Mismatch::Pixels {
+            golden: golden.to_path_buf(),
+            differing,
+            diff: diff_path,
+        })
+    }
+
+    /// Like [`compare`](Self::compare), but panics with a readable message on mismatch.
+    #[track_caller]
+    pub fn assert(&self, view: &View, golden: impl AsRef<Path>) {
+        if let Err(mismatch) = self.compare(view, golden) {
+            panic!("snapshot mismatch: {mismatch}");
+        }
+    }
+}
+
+/// Renders `view` at `size` and asserts it matches the golden PNG, with default settings.
+#[track_caller]
+pub fn assert_snapshot(view: &View, size: Size, golden: impl AsRef<Path>) {
+    Snapshot::new(size).assert(view, golden);
+}
+
+/// Returns `<golden stem>.<suffix>.png` next to the golden.
+fn sibling(golden: &Path, suffix: &str) -> PathBuf {
+    let stem = golden.file_stem().unwrap_or_default().to_string_lossy();
+    golden.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+/// Squared YIQ distance between two colors, blended over white.
+fn delta(a: [u8; 4], b: [u8; 4]) -> f32 {
+    let (ya, ia, qa) = yiq(a);
+    let (yb, ib, qb) = yiq(b);
+    let (y, i, q) = (ya - yb, ia - ib, qa - qb);
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn yiq(rgba: [u8; 4]) -> (f32, f32, f32) {
+    let alpha = rgba[3] as f32 / 255.0;
+    let [r, g, b] = [0, 1, 2].map(|i| 255.0 + (rgba[i] as f32 - 255.0) * alpha);
+    (
+        r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_2,
+        r * 0.595_977_99 - g * 0.274_176_1 - b * 0.321_801_9,
+        r * 0.211_470_19 - g * 0.522_617_2 + b * 0.311_147,
+    )
+}
+
+fn luma(rgba: [u8; 4]) -> f32 {
+    yiq(rgba).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gradient::LinearGradient;
+    use crate::core::{Color, Shadow, Vector};
+    use crate::view::{Button, HStack, Shape, Text as TextView, VStack};
+
+    fn square(color: Color) -> View {
+        View::from(Shape::rectangle().fill(color).frame(10.0, 10.0))
+    }
+
+    /// A snapshot that never blesses, and a golden path in a fresh directory of its own.
+    fn fixture(name: &str) -> (Snapshot, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("shellui-snapshot-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        (Snapshot::new(Size::new(20.0, 20.0)).bless(false), dir.join("shape.png"))
+    }
+
+    /// Blesses a red square as the golden.
+    fn red_golden(name: &str) -> (Snapshot, PathBuf) {
+        let (snapshot, golden) = fixture(name);
+        snapshot.clone().bless(true).compare(&square(Color::new(1.0, 0.0, 0.0, 1.0)), &golden).unwrap();
+        (snapshot, golden)
+    }
+
+    fn cleanup(golden: &Path) {
+        let _ = std::fs::remove_dir_all(golden.parent().unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_matches_golden() {
+        let view = View::from(
+            VStack::new(vec![
+                View::from(TextView::new("Golden").size(24.0).color(Color::new(0.2, 0.2, 0.8, 1.0))),
+                View::from(
+                    HStack::new(vec![
+                        View::from(Shape::circle().fill(Color::new(1.0, 0.3, 0.3, 1.0)).frame(20.0, 20.0)),
+                        View::from(Button::new("Button").shadow(Shadow::new(
+                            Color::new(0.0, 0.0, 0.0, 0.3),
+                            Vector::new(0.0, 2.0),
+                            4.0,
+                        ))),
+                    ])
+                    .spacing(8.0),
+                ),
+                View::from(
+                    Shape::capsule()
+                        .fill(LinearGradient::horizontal(vec![
+                            Color::new(0.2, 0.8, 0.4, 1.0),
+                            Color::new(0.2, 0.4, 0.9, 1.0),
+                        ]))
+                        .frame(120.0, 12.0),
+                ),
+            ])
+            .spacing(8.0)
+            .padding(10.0),
+        );
+        Snapshot::new(Size::new(160.0, 110.0))
+            .scale(2.0)
+            .assert(&view, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/basic_views.png"));
+    }
+
+    #[test]
+    fn test_missing_golden_is_reported() {
+        let (snapshot, golden) = fixture("missing");
+        let result = snapshot.compare(&square(Color::new(1.0, 0.0, 0.0, 1.0)), &golden);
+        assert!(matches!(result, Err(Mismatch::MissingGolden { .. })), "{result:?}");
+    }
+
+    #[test]
+    fn test_blessed_golden_matches_the_same_view() {
+        let (snapshot, golden) = red_golden("blessed");
+        snapshot.compare(&square(Color::new(1.0, 0.0, 0.0, 1.0)), &golden).unwrap();
+        cleanup(&golden);
+    }
+
+    #[test]
+    fn test_mismatch_writes_a_diff_and_the_actual_image() {
+        let (snapshot, golden) = red_golden("mismatch");
+        match snapshot.compare(&square(Color::new(0.0, 1.0, 0.0, 1.0)), &golden) {
+            Err(Mismatch::Pixels { differing, diff, .. }) => {
+                assert_eq!(differing, 100);
+                let diff = decode_image(&std::fs::read(diff).unwrap()).unwrap();
+                let pixel = diff.pixel(5, 5).unwrap().demultiply();
+                assert_eq!((pixel.red(), pixel.green()), (255, 0), "differing pixels are red");
+            }
+            other => panic!("expected a pixel mismatch, got {other:?}"),
+        }
+        assert!(sibling(&golden, "actual").exists());
+        cleanup(&golden);
+    }
+
+    #[test]
+    fn test_max_differing_pixels_tolerates_a_mismatch() {
+        let (snapshot, golden) = red_golden("tolerated");
+        snapshot.max_differing_pixels(100).compare(&square(Color::new(0.0, 1.0, 0.0, 1.0)), &golden).unwrap();
+        cleanup(&golden);
+    }
+}
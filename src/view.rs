@@ -1,6 +1,13 @@
-//! SwiftUI-like view types: Text, VStack, HStack.
+//! SwiftUI-like view types: Text, Image, Shape, Canvas, TextField, TextEditor, VStack,
+//! HStack.
 
-use crate::core::{Background, Color};
+use std::ops::Range;
+
+use crate::core::image::{Handle, Interpolation};
+use crate::core::font::{Font, Style, Weight};
+use crate::core::text::{Alignment as TextAlignment, AttributedString, Feature, LineHeight, Span, Truncation, Typography};
+use crate::core::{Background, Border, Color, Shadow};
+use crate::layout::{LayoutDirection, Rectangle, Size};
 
 /// Alignment along the cross axis for stacks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -38,19 +45,46 @@ impl Alignment {
 #[derive(Debug, Clone)]
 pub struct Text {
     pub(crate) string: String,
+    /// Styled runs of `string`.
+    pub(crate) spans: Vec<Span>,
+    pub(crate) font: Font,
     pub(crate) size: Option<f32>,
     pub(crate) color: Option<Color>,
+    pub(crate) line_limit: Option<usize>,
+    pub(crate) truncation: Truncation,
+    pub(crate) alignment: TextAlignment,
+    pub(crate) typography: Typography,
+    pub(crate) selectable: bool,
+    /// Selected byte range of `string`, set by the window before drawing.
+    pub(crate) selection: Option<Range<usize>>,
+    pub(crate) effects: Effects,
 }
 
 impl Text {
     pub fn new(s: impl Into<String>) -> Self {
         Self {
             string: s.into(),
+            spans: Vec::new(),
+            font: Font::default(),
             size: None,
             color: None,
+            line_limit: None,
+            truncation: Truncation::default(),
+            alignment: TextAlignment::default(),
+            typography: Typography::default(),
+            selectable: false,
+            selection: None,
+            effects: Effects::default(),
         }
     }
 
+    /// A paragraph whose runs may differ in weight, style, size, color and decoration.
+    /// `size` and `color` apply to the runs that don't set their own.
+    pub fn rich(text: impl Into<AttributedString>) -> Self {
+        let (string, spans) = text.into().into_parts();
+        Self { spans, ..Self::new(string) }
+    }
+
     /// Set the font size in pixels. If not set, uses the default size.
     pub fn size(mut self, size: f32) -> Self {
         self.size = Some(size);
@@ -62,6 +96,203 @@ impl Text {
         self.color = Some(color.into());
         self
     }
+
+    /// Set the font family, weight, style and stretch. If not set, uses regular sans-serif.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Set the font weight.
+    pub fn font_weight(mut self, weight: Weight) -> Self {
+        self.font.weight = weight;
+        self
+    }
+
+    /// Use the bold weight of the font.
+    pub fn bold(self) -> Self {
+        self.font_weight(Weight::BOLD)
+    }
+
+    /// Use the italic style of the font, slanted synthetically if it has none.
+    pub fn italic(mut self) -> Self {
+        self.font.style = Style::Italic;
+        self
+    }
+
+    /// Show at most `lines` lines, truncating the rest. Text wraps to the width its
+    /// parent proposes either way.
+    pub fn line_limit(mut self, lines: usize) -> Self {
+        self.line_limit = Some(lines.max(1));
+        self
+    }
+
+    /// Set where text beyond the line limit is cut. Defaults to the tail.
+    pub fn truncation_mode(mut self, truncation: Truncation) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    /// Set how the lines of wrapped or multi-line text are aligned to each other.
+    pub fn multiline_text_alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Set the distance between baselines. Defaults to 1.2 times the font size.
+    pub fn line_height(mut self, line_height: LineHeight) -> Self {
+        self.typography.line_height = line_height;
+        self
+    }
+
+    /// Add `tracking` logical pixels after every character; negative values tighten.
+    pub fn tracking(mut self, tracking: f32) -> Self {
+        self.typography.tracking = tracking;
+        self
+    }
+
+    /// Turn the font's pair kerning on or off. It is on by default.
+    pub fn kerning(self, enabled: bool) -> Self {
+        self.font_feature(Feature::new(&Feature::KERNING, enabled as u32))
+    }
+
+    /// Turn standard and contextual ligatures on or off. They are on by default.
+    pub fn ligatures(self, enabled: bool) -> Self {
+        self.font_feature(Feature::new(&Feature::STANDARD_LIGATURES, enabled as u32))
+            .font_feature(Feature::new(&Feature::CONTEXTUAL_LIGATURES, enabled as u32))
+    }
+
+    /// Use digits of equal width, so numbers that change don't shift the text around.
+    pub fn monospaced_digit(self) -> Self {
+        self.font_feature(Feature::enable(&Feature::TABULAR_NUMBERS))
+    }
+
+    /// Set an OpenType feature, e.g. `Feature::enable(b"ss01")` for a stylistic set.
+    pub fn font_feature(mut self, feature: Feature) -> Self {
+        self.typography.set_feature(feature);
+        self
+    }
+
+    /// Let the user select the text by dragging or double-clicking a word, and copy
+    /// the selection with Ctrl+C (Cmd+C on macOS).
+    pub fn text_selection(mut self, enabled: bool) -> Self {
+        self.selectable = enabled;
+        self
+    }
+}
+
+/// How a resizable image fills its frame when the aspect ratios differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentMode {
+    /// Scale to fit inside the frame, keeping the aspect ratio.
+    Fit,
+    /// Scale to cover the frame, keeping the aspect ratio. Overflow is clipped.
+    Fill,
+}
+
+/// A view displaying a PNG or JPEG image.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub(crate) handle: Handle,
+    pub(crate) resizable: bool,
+    pub(crate) aspect_ratio: Option<f32>,
+    pub(crate) content_mode: Option<ContentMode>,
+    pub(crate) interpolation: Interpolation,
+    pub(crate) frame: Option<(f32, f32)>,
+    pub(crate) effects: Effects,
+}
+
+impl Image {
+    /// Create an image from a file path or an existing [`Handle`].
+    pub fn new(handle: impl Into<Handle>) -> Self {
+        Self {
+            handle: handle.into(),
+            resizable: false,
+            aspect_ratio: None,
+            content_mode: None,
+            interpolation: Interpolation::default(),
+            frame: None,
+            effects: Effects::default(),
+        }
+    }
+
+    /// Create an image from encoded PNG or JPEG bytes.
+    ///
+    /// Pass clones of one `Arc` each frame; see [`Handle::from_bytes`].
+    pub fn from_bytes(bytes: impl Into<std::sync::Arc<[u8]>>) -> Self {
+        Self::new(Handle::from_bytes(bytes))
+    }
+
+    /// Let the image scale to the size proposed by its parent instead of its pixel size.
+    pub fn resizable(mut self) -> Self {
+        self.resizable = true;
+        self
+    }
+
+    /// Keep the given aspect ratio (width / height) when resized. `None` uses the image's own ratio.
+    pub fn aspect_ratio(mut self, ratio: Option<f32>, content_mode: ContentMode) -> Self {
+        self.aspect_ratio = ratio;
+        self.content_mode = Some(content_mode);
+        self
+    }
+
+    /// Scale to fit the proposed size, keeping the image's aspect ratio.
+    pub fn scaled_to_fit(self) -> Self {
+        self.aspect_ratio(None, ContentMode::Fit)
+    }
+
+    /// Scale to fill the proposed size, keeping the image's aspect ratio.
+    pub fn scaled_to_fill(self) -> Self {
+        self.aspect_ratio(None, ContentMode::Fill)
+    }
+
+    /// Set the sampling quality used when the image is scaled.
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Give the image a fixed frame size in logical pixels.
+    pub fn frame(mut self, width: f32, height: f32) -> Self {
+        self.frame = Some((width, height));
+        self
+    }
+
+    /// Returns where the image pixels go inside `frame`, given the image's pixel size.
+    pub(crate) fn content_bounds(&self, frame: Rectangle, intrinsic: Size) -> Rectangle {
+        let centered = |width: f32, height: f32| {
+            Rectangle::new(
+                frame.x + (frame.width - width) / 2.0,
+                frame.y + (frame.height - height) / 2.0,
+                width,
+                height,
+            )
+        };
+
+        if !self.resizable {
+            return centered(intrinsic.width, intrinsic.height);
+        }
+
+        let ratio = self.ratio(intrinsic);
+        match (self.content_mode, ratio) {
+            (Some(ContentMode::Fit), Some(ratio)) => {
+                let width = frame.width.min(frame.height * ratio);
+                centered(width, width / ratio)
+            }
+            (Some(ContentMode::Fill), Some(ratio)) => {
+                let width = frame.width.max(frame.height * ratio);
+                centered(width, width / ratio)
+            }
+            _ => frame,
+        }
+    }
+
+    /// The aspect ratio to keep, if any.
+    pub(crate) fn ratio(&self, intrinsic: Size) -> Option<f32> {
+        self.aspect_ratio
+            .or((intrinsic.height > 0.0).then(|| intrinsic.width / intrinsic.height))
+            .filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+    }
 }
 
 /// A clickable button with customizable styling.
@@ -73,6 +304,11 @@ pub struct Button {
     pub(crate) background: Option<Background>,
     pub(crate) text_color: Option<Color>,
     pub(crate) text_size: Option<f32>,
+    pub(crate) font: Font,
+    pub(crate) border: Border,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) opacity: f32,
+    pub(crate) effects: Effects,
 }
 
 impl Button {
@@ -84,6 +320,11 @@ impl Button {
             background: Some(Background::Color(Color::new(0.2, 0.5, 1.0, 1.0))), // Default blue
             text_color: Some(Color::new(1.0, 1.0, 1.0, 1.0)), // Default white text
             text_size: None,
+            font: Font::default(),
+            border: Border::new(Color::TRANSPARENT, 0.0, 6.0), // Default rounded corners
+            shadow: None,
+            opacity: 1.0,
+            effects: Effects::default(),
         }
     }
 
@@ -112,10 +353,53 @@ impl Button {
         self
     }
 
+    /// Set the font of the label.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Use the bold weight of the label's font.
+    pub fn bold(mut self) -> Self {
+        self.font.weight = Weight::BOLD;
+        self
+    }
+
+    /// Use the italic style of the label's font.
+    pub fn italic(mut self) -> Self {
+        self.font.style = Style::Italic;
+        self
+    }
+
     pub fn clear_background(mut self) -> Self {
         self.background = None;
         self
     }
+
+    /// Round the corners of the background with the given radius.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.border.radius = radius;
+        self
+    }
+
+    /// Draw a border of the given color and width inside the bounds.
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border.color = color;
+        self.border.width = width;
+        self
+    }
+
+    /// Draw a drop shadow behind the background.
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Set the opacity (0.0 to 1.0) of the view and all of its content, composited as one group.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
 }
 
 /// Vertical stack of views.
@@ -125,7 +409,13 @@ pub struct VStack {
     pub(crate) alignment: Alignment,
     pub(crate) justify: Justify,
     pub(crate) padding: f32,
+    pub(crate) layout_direction: Option<LayoutDirection>,
     pub(crate) background: Option<Background>,
+    pub(crate) border: Border,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) opacity: f32,
+    pub(crate) clip: Option<ShapeKind>,
+    pub(crate) effects: Effects,
     pub(crate) children: Vec<View>,
 }
 
@@ -140,7 +430,13 @@ impl VStack {
             alignment: Alignment::Default,
             justify: Justify::Start,
             padding: 0.0,
+            layout_direction: None,
             background: None,
+            border: Border::default(),
+            shadow: None,
+            opacity: 1.0,
+            clip: None,
+            effects: Effects::default(),
             children: children.into_iter().map(|c| c.into()).collect(),
         }
     }
@@ -185,6 +481,13 @@ impl VStack {
         self
     }
 
+    /// Lay out the stack and its descendants in `direction` instead of the direction
+    /// of its parent.
+    pub fn layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.layout_direction = Some(direction);
+        self
+    }
+
     pub fn padding(mut self, padding: f32) -> Self {
         self.padding = padding;
         self
@@ -194,6 +497,43 @@ impl VStack {
         self.background = Some(background.into());
         self
     }
+
+    /// Round the corners of the background with the given radius.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.border.radius = radius;
+        self
+    }
+
+    /// Draw a border of the given color and width inside the bounds.
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border.color = color;
+        self.border.width = width;
+        self
+    }
+
+    /// Draw a drop shadow behind the background.
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Set the opacity (0.0 to 1.0) of the view and all of its content, composited as one group.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Clip the background and children to the bounds of the stack.
+    pub fn clipped(self) -> Self {
+        self.clip_shape(ShapeKind::Rectangle)
+    }
+
+    /// Clip the background and children to a shape filling the bounds of the stack,
+    /// e.g. `ShapeKind::RoundedRectangle(12.0)`.
+    pub fn clip_shape(mut self, shape: ShapeKind) -> Self {
+        self.clip = Some(shape);
+        self
+    }
 }
 
 impl Default for VStack {
@@ -209,7 +549,13 @@ pub struct HStack {
     pub(crate) alignment: Alignment,
     pub(crate) justify: Justify,
     pub(crate) padding: f32,
+    pub(crate) layout_direction: Option<LayoutDirection>,
     pub(crate) background: Option<Background>,
+    pub(crate) border: Border,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) opacity: f32,
+    pub(crate) clip: Option<ShapeKind>,
+    pub(crate) effects: Effects,
     pub(crate) children: Vec<View>,
 }
 
@@ -224,7 +570,13 @@ impl HStack {
             alignment: Alignment::Default,
             justify: Justify::Start,
             padding: 0.0,
+            layout_direction: None,
             background: None,
+            border: Border::default(),
+            shadow: None,
+            opacity: 1.0,
+            clip: None,
+            effects: Effects::default(),
             children: children.into_iter().map(|c| c.into()).collect(),
         }
     }
@@ -269,6 +621,13 @@ impl HStack {
         self
     }
 
+    /// Lay out the stack and its descendants in `direction` instead of the direction
+    /// of its parent.
+    pub fn layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.layout_direction = Some(direction);
+        self
+    }
+
     pub fn padding(mut self, padding: f32) -> Self {
         self.padding = padding;
         self
@@ -278,6 +637,43 @@ impl HStack {
         self.background = Some(background.into());
         self
     }
+
+    /// Round the corners of the background with the given radius.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.border.radius = radius;
+        self
+    }
+
+    /// Draw a border of the given color and width inside the bounds.
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border.color = color;
+        self.border.width = width;
+        self
+    }
+
+    /// Draw a drop shadow behind the background.
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Set the opacity (0.0 to 1.0) of the view and all of its content, composited as one group.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Clip the background and children to the bounds of the stack.
+    pub fn clipped(self) -> Self {
+        self.clip_shape(ShapeKind::Rectangle)
+    }
+
+    /// Clip the background and children to a shape filling the bounds of the stack,
+    /// e.g. `ShapeKind::RoundedRectangle(12.0)`.
+    pub fn clip_shape(mut self, shape: ShapeKind) -> Self {
+        self.clip = Some(shape);
+        self
+    }
 }
 
 impl Default for HStack {
@@ -286,13 +682,18 @@ impl Default for HStack {
     }
 }
 
-/// A view in the tree: text, button, or a stack of child views.
+/// A view in the tree: text, image, shape, canvas, button, or a stack of child views.
 #[derive(Debug, Clone)]
 pub enum View {
     Text(Text),
+    Image(Image),
+    Shape(Shape),
+    Canvas(Canvas),
     Button(Button),
     VStack(VStack),
     HStack(HStack),
+    TextField(TextField),
+    TextEditor(TextEditor),
 }
 
 impl View {
@@ -300,10 +701,34 @@ impl View {
         Self::Text(Text::new(s))
     }
 
+    pub fn rich_text(text: impl Into<AttributedString>) -> Self {
+        Self::Text(Text::rich(text))
+    }
+
+    pub fn image(handle: impl Into<Handle>) -> Self {
+        Self::Image(Image::new(handle))
+    }
+
+    pub fn shape(kind: ShapeKind) -> Self {
+        Self::Shape(Shape::new(kind))
+    }
+
+    pub fn canvas(draw: impl Fn(&mut Frame<'_>) + 'static) -> Self {
+        Self::Canvas(Canvas::new(draw))
+    }
+
     pub fn button(label: impl Into<String>) -> Self {
         Self::Button(Button::new(label))
     }
 
+    pub fn text_field(text: &Binding<String>) -> Self {
+        Self::TextField(TextField::new(text))
+    }
+
+    pub fn text_editor(text: &Binding<String>) -> Self {
+        Self::TextEditor(TextEditor::new(text))
+    }
+
     pub fn vstack(children: Vec<View>) -> Self {
         Self::VStack(VStack::new(children))
     }
@@ -311,6 +736,40 @@ impl View {
     pub fn hstack(children: Vec<View>) -> Self {
         Self::HStack(HStack::new(children))
     }
+
+    /// The group opacity applied to this view and its content.
+    pub(crate) fn opacity(&self) -> f32 {
+        match self {
+            View::Text(_) | View::Image(_) | View::Shape(_) | View::Canvas(_) | View::TextField(_) | View::TextEditor(_) => 1.0,
+            View::Button(b) => b.opacity,
+            View::VStack(v) => v.opacity,
+            View::HStack(h) => h.opacity,
+        }
+    }
+
+    /// The rotation, scale and offset applied to this view when drawn.
+    pub(crate) fn effects(&self) -> &Effects {
+        match self {
+            View::Text(t) => &t.effects,
+            View::Image(i) => &i.effects,
+            View::Shape(s) => &s.effects,
+            View::Canvas(c) => &c.effects,
+            View::Button(b) => &b.effects,
+            View::VStack(v) => &v.effects,
+            View::HStack(h) => &h.effects,
+            View::TextField(f) => &f.effects,
+            View::TextEditor(e) => &e.effects,
+        }
+    }
+
+    /// The shape this view and its content are clipped to, if any.
+    pub(crate) fn clip_shape(&self) -> Option<&ShapeKind> {
+        match self {
+            View::VStack(v) => v.clip.as_ref(),
+            View::HStack(h) => h.clip.as_ref(),
+            _ => None,
+        }
+    }
 }
 
 impl From<Text> for View {
@@ -319,12 +778,42 @@ impl From<Text> for View {
     }
 }
 
+impl From<Image> for View {
+    fn from(i: Image) -> Self {
+        Self::Image(i)
+    }
+}
+
+impl From<Shape> for View {
+    fn from(s: Shape) -> Self {
+        Self::Shape(s)
+    }
+}
+
+impl From<Canvas> for View {
+    fn from(c: Canvas) -> Self {
+        Self::Canvas(c)
+    }
+}
+
 impl From<Button> for View {
     fn from(b: Button) -> Self {
         Self::Button(b)
     }
 }
 
+impl From<TextField> for View {
+    fn from(f: TextField) -> Self {
+        Self::TextField(f)
+    }
+}
+
+impl From<TextEditor> for View {
+    fn from(e: TextEditor) -> Self {
+        Self::TextEditor(e)
+    }
+}
+
 impl From<VStack> for View {
     fn from(v: VStack) -> Self {
         Self::VStack(v)
@@ -343,18 +832,55 @@ pub use crate::widget::{Text as TextWidget, VStack as VStackWidget, HStack as HS
 // Adapter module for View to Element conversion
 pub mod adapter;
 
+mod binding;
+mod canvas;
+mod effect;
+mod shape;
+mod text_editor;
+mod text_field;
+pub use binding::Binding;
+pub use canvas::{Canvas, Frame};
+pub use effect::UnitPoint;
+pub use effect::GeometryEffects;
+pub(crate) use effect::Effects;
+pub use shape::{Shape, ShapeKind};
+pub use text_editor::TextEditor;
+pub use text_field::TextField;
+pub(crate) use text_field::Editing;
+
+impl effect::sealed::HasEffects for View {
+    fn effects_mut(&mut self) -> &mut Effects {
+        match self {
+            View::Text(t) => &mut t.effects,
+            View::Image(i) => &mut i.effects,
+            View::Shape(s) => &mut s.effects,
+            View::Canvas(c) => &mut c.effects,
+            View::Button(b) => &mut b.effects,
+            View::VStack(v) => &mut v.effects,
+            View::HStack(h) => &mut h.effects,
+            View::TextField(f) => &mut f.effects,
+            View::TextEditor(e) => &mut e.effects,
+        }
+    }
+}
+
+macro_rules! impl_has_effects {
+    ($($view:ty),*) => {
+        $(impl effect::sealed::HasEffects for $view {
+            fn effects_mut(&mut self) -> &mut Effects {
+                &mut self.effects
+            }
+        })*
+    };
+}
+
+impl_has_effects!(Text, Image, Shape, Canvas, Button, VStack, HStack, TextField, TextEditor);
+
 /// Builds a vertical stack of views. Example: `vstack![Text::new("A"), Text::new("B")]`
 #[macro_export]
 macro_rules! vstack {
     ($($child:expr),+ $(,)?) => {
-        $crate::View::VStack($crate::VStack {
-            spacing: 0.0,
-            alignment: $crate::Alignment::Default,
-            justify: $crate::Justify::Start,
-            padding: 0.0,
-            background: None,
-            children: vec![$(($child).into()),+],
-        })
+        $crate::View::VStack($crate::VStack::new(vec![$($crate::View::from($child)),+]))
     };
 }
 
@@ -362,13 +888,45 @@ macro_rules! vstack {
 #[macro_export]
 macro_rules! hstack {
     ($($child:expr),+ $(,)?) => {
-        $crate::View::HStack($crate::HStack {
-            spacing: 0.0,
-            alignment: $crate::Alignment::Default,
-            justify: $crate::Justify::Start,
-            padding: 0.0,
-            background: None,
-            children: vec![$(($child).into()),+],
-        })
+        $crate::View::HStack($crate::HStack::new(vec![$($crate::View::from($child)),+]))
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{layout, Limits};
+    use crate::render::Renderer;
+
+    /// Draws two red 40px squares and a line of text in a stack 40x50 pixels large,
+    /// in a 40x100 buffer of white.
+    fn draw_overflowing(clip: impl Fn(VStack) -> VStack) -> Vec<u32> {
+        let square = || View::from(Shape::rectangle().fill(Color::new(1.0, 0.0, 0.0, 1.0)).frame(40.0, 40.0));
+        let view = View::from(clip(VStack::new(vec![square(), square(), View::text("Overflow")])));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(40.0, 50.0), &renderer);
+        let mut buffer = vec![0u32; 40 * 100];
+        renderer.draw(&view, &node, &mut buffer, 40, 100, 0x00FF_FFFF, 0.0, 0.0);
+        buffer
+    }
+
+    #[test]
+    fn test_children_overflow_stacks_without_a_clip() {
+        assert_eq!(draw_overflowing(|stack| stack)[70 * 40 + 20], 0xFF0000);
+    }
+
+    #[test]
+    fn test_clipped_stacks_cut_children_at_their_bounds() {
+        let buffer = draw_overflowing(VStack::clipped);
+        assert_eq!(buffer[45 * 40 + 20], 0xFF0000);
+        assert!(buffer[50 * 40..].iter().all(|pixel| *pixel == 0x00FF_FFFF));
+    }
+
+    #[test]
+    fn test_clip_shapes_cut_children_at_their_outline() {
+        let buffer = draw_overflowing(|stack| stack.clip_shape(ShapeKind::RoundedRectangle(16.0)));
+        assert_eq!(buffer[0], 0x00FF_FFFF, "corners are cut off");
+        assert_eq!(buffer[20 * 40 + 20], 0xFF0000);
+        assert!(buffer[50 * 40..].iter().all(|pixel| *pixel == 0x00FF_FFFF));
+    }
+}
@@ -0,0 +1,52 @@
+//! Bindings: state shared between the app and the views that edit it.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A value that views read when they are built and write back when the user edits
+/// them, e.g. the text of a [`TextField`](crate::view::TextField).
+///
+/// Clones share the value. Create the binding once, outside the closure that builds
+/// the view tree, and move a clone into it.
+#[derive(Default)]
+pub struct Binding<T>(Rc<RefCell<T>>);
+
+impl<T> Binding<T> {
+    pub fn new(value: T) -> Self {
+        Self(Rc::new(RefCell::new(value)))
+    }
+
+    /// Replaces the value.
+    pub fn set(&self, value: T) {
+        *self.0.borrow_mut() = value;
+    }
+
+    /// Calls `f` with a reference to the value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.0.borrow())
+    }
+
+    /// Changes the value in place.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.0.borrow_mut());
+    }
+}
+
+impl<T: Clone> Binding<T> {
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T {
+        self.0.borrow().clone()
+    }
+}
+
+impl<T> Clone for Binding<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Binding<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Binding").field(&self.0.borrow()).finish()
+    }
+}
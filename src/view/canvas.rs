@@ -0,0 +1,241 @@
+//! Canvas view: immediate-mode drawing with a closure.
+
+use std::rc::Rc;
+
+use crate::core::image::{Handle, Interpolation, Renderer as _};
+use crate::core::path::{Path, Renderer as _, Stroke};
+use crate::core::renderer::Renderer as _;
+use crate::core::text::{Renderer as _, Text};
+use crate::core::{Background, Color, Point, Transformation};
+use crate::layout::{LayoutDirection, Rectangle, Size};
+use crate::render::Renderer;
+use crate::view::Effects;
+
+/// A view that draws its content with a closure every frame.
+///
+/// The closure receives a [`Frame`] whose origin is the top-left corner of the
+/// canvas. Drawing is clipped to the canvas bounds.
+#[derive(Clone)]
+pub struct Canvas {
+    pub(crate) draw: Rc<dyn Fn(&mut Frame<'_>)>,
+    pub(crate) frame: Option<(f32, f32)>,
+    pub(crate) effects: Effects,
+}
+
+impl Canvas {
+    pub fn new(draw: impl Fn(&mut Frame<'_>) + 'static) -> Self {
+        Self {
+            draw: Rc::new(draw),
+            frame: None,
+            effects: Effects::default(),
+        }
+    }
+
+    /// Give the canvas a fixed frame size in logical pixels.
+    pub fn frame(mut self, width: f32, height: f32) -> Self {
+        self.frame = Some((width, height));
+        self
+    }
+}
+
+impl std::fmt::Debug for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Canvas").field("frame", &self.frame).finish_non_exhaustive()
+    }
+}
+
+/// The drawing context handed to a [`Canvas`] closure.
+pub struct Frame<'a> {
+    renderer: &'a mut Renderer,
+    size: Size,
+    direction: LayoutDirection,
+    clips: usize,
+    transformations: usize,
+}
+
+impl<'a> Frame<'a> {
+    pub(crate) fn new(renderer: &'a mut Renderer, size: Size, direction: LayoutDirection) -> Self {
+        Self {
+            renderer,
+            size,
+            direction,
+            clips: 0,
+            transformations: 0,
+        }
+    }
+
+    /// Returns the size of the canvas.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the layout direction of the canvas, which text is drawn in.
+    pub fn direction(&self) -> LayoutDirection {
+        self.direction
+    }
+
+    /// Returns the center of the canvas.
+    pub fn center(&self) -> Point {
+        Point::new(self.size.width / 2.0, self.size.height / 2.0)
+    }
+
+    /// Fills the inside of the path with a color or gradient.
+    pub fn fill(&mut self, path: &Path, background: impl Into<Background>) {
+        self.renderer.fill_path(path, background);
+    }
+
+    /// Fills a rectangle with a color or gradient.
+    pub fn fill_rectangle(&mut self, bounds: Rectangle, background: impl Into<Background>) {
+        self.fill(&Path::rectangle(bounds), background);
+    }
+
+    /// Draws the outline of the path.
+    pub fn stroke(&mut self, path: &Path, stroke: Stroke) {
+        self.renderer.stroke_path(path, stroke);
+    }
+
+    /// Draws a line of text with its leading top corner at `position`: the top-left
+    /// corner, or the top-right one when the canvas is laid out right to left.
+    pub fn fill_text(&mut self, content: impl Into<String>, position: Point, size: f32, color: Color) {
+        let height = (self.size.height - position.y).max(0.0);
+        let bounds = match self.direction {
+            LayoutDirection::LeftToRight => Rectangle::new(position.x, position.y, (self.size.width - position.x).max(0.0), height),
+            LayoutDirection::RightToLeft => Rectangle::new(0.0, position.y, position.x.max(0.0), height),
+        };
+        self.renderer.fill_text(Text {
+            size,
+            color,
+            direction: self.direction,
+            ..Text::new(content, bounds)
+        });
+    }
+
+    /// Draws an image stretched to `bounds`.
+    pub fn draw_image(&mut self, handle: impl Into<Handle>, bounds: Rectangle) {
+        self.renderer.draw_image(handle.into(), Interpolation::default(), bounds);
+    }
+
+    /// Returns the size of the image in pixels, or `None` if it cannot be decoded.
+    pub fn measure_image(&self, handle: &Handle) -> Option<Size> {
+        self.renderer.measure_image(handle)
+    }
+
+    /// Clips everything drawn until the matching [`pop_clip`](Self::pop_clip) to `bounds`.
+    pub fn push_clip(&mut self, bounds: Rectangle) {
+        self.renderer.start_layer(bounds);
+        self.clips += 1;
+    }
+
+    /// Removes the last clip.
+    pub fn pop_clip(&mut self) {
+        if self.clips > 0 {
+            self.renderer.end_layer();
+            self.clips -= 1;
+        }
+    }
+
+    /// Applies `transformation` to everything drawn until the matching
+    /// [`pop_transform`](Self::pop_transform).
+    pub fn push_transform(&mut self, transformation: Transformation) {
+        self.renderer.start_transformation(transformation);
+        self.transformations += 1;
+    }
+
+    /// Removes the last transformation.
+    pub fn pop_transform(&mut self) {
+        if self.transformations > 0 {
+            self.renderer.end_transformation();
+            self.transformations -= 1;
+        }
+    }
+
+    /// Draws with a clip applied to everything drawn in `f`.
+    pub fn with_clip(&mut self, bounds: Rectangle, f: impl FnOnce(&mut Self)) {
+        self.push_clip(bounds);
+        f(self);
+        self.pop_clip();
+    }
+
+    /// Draws with a transformation applied to everything drawn in `f`.
+    pub fn with_transform(&mut self, transformation: Transformation, f: impl FnOnce(&mut Self)) {
+        self.push_transform(transformation);
+        f(self);
+        self.pop_transform();
+    }
+}
+
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        // Unbalanced pushes must not leak into the views drawn after the canvas.
+        for _ in 0..self.transformations {
+            self.renderer.end_transformation();
+        }
+        for _ in 0..self.clips {
+            self.renderer.end_layer();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::layout::{layout, Limits};
+    use crate::view::{Shape, VStack};
+    use crate::View;
+
+    const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
+
+    /// Draws a 16x8 canvas running `draw` above a blue 16x4 rectangle, inset by 2px in
+    /// 20x20 pixels of white.
+    fn draw(draw: impl Fn(&mut Frame<'_>) + 'static) -> Vec<u32> {
+        let view = View::from(
+            VStack::new(vec![
+                View::from(Canvas::new(draw).frame(16.0, 8.0)),
+                View::from(Shape::rectangle().fill(Color::new(0.0, 0.0, 1.0, 1.0)).frame(16.0, 4.0)),
+            ])
+            .padding(2.0),
+        );
+        let mut renderer = Renderer::new();
+        let node = layout(&view, Limits::loose(20.0, 20.0), &renderer);
+        let mut buffer = vec![0u32; 20 * 20];
+        renderer.draw(&view, &node, &mut buffer, 20, 20, 0x00FF_FFFF, 0.0, 0.0);
+        buffer
+    }
+
+    #[test]
+    fn test_frame_has_the_size_of_the_canvas() {
+        let size = Rc::new(Cell::new(Size::ZERO));
+        let seen = size.clone();
+        draw(move |frame| seen.set(frame.size()));
+        assert_eq!(size.get(), Size::new(16.0, 8.0));
+    }
+
+    #[test]
+    fn test_drawing_is_in_local_coordinates() {
+        let buffer = draw(|frame| {
+            frame.fill_rectangle(Rectangle::new(0.0, 0.0, 4.0, 4.0), RED);
+            frame.with_transform(Transformation::translate(8.0, 0.0), |frame| {
+                frame.fill_rectangle(Rectangle::new(0.0, 0.0, 4.0, 4.0), RED);
+            });
+        });
+        assert_eq!(buffer[3 * 20 + 3], 0xFF0000);
+        assert_eq!(buffer[3 * 20 + 7], 0x00FF_FFFF);
+        assert_eq!(buffer[3 * 20 + 11], 0xFF0000);
+    }
+
+    #[test]
+    fn test_drawing_is_clipped_to_the_canvas() {
+        let buffer = draw(|frame| frame.fill_rectangle(Rectangle::new(-4.0, 6.0, 40.0, 40.0), RED));
+        assert_eq!(buffer[9 * 20 + 1], 0x00FF_FFFF);
+        assert_eq!(buffer[9 * 20 + 2], 0xFF0000);
+        assert_eq!(buffer[11 * 20 + 10], 0x0000FF);
+    }
+
+    #[test]
+    fn test_clips_left_pushed_do_not_leak_to_siblings() {
+        let buffer = draw(|frame| frame.push_clip(Rectangle::new(0.0, 0.0, 1.0, 1.0)));
+        assert_eq!(buffer[11 * 20 + 10], 0x0000FF);
+    }
+}
@@ -0,0 +1,142 @@
+//! Geometry effects: rotation, scale and offset applied to a view after layout.
+
+use crate::core::transformation::Transformation;
+use crate::layout::Rectangle;
+
+/// A point relative to the bounds of a view: (0, 0) is the top-left corner and
+/// (1, 1) the bottom-right corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl UnitPoint {
+    pub const TOP_LEADING: Self = Self::new(0.0, 0.0);
+    pub const TOP: Self = Self::new(0.5, 0.0);
+    pub const TOP_TRAILING: Self = Self::new(1.0, 0.0);
+    pub const LEADING: Self = Self::new(0.0, 0.5);
+    pub const CENTER: Self = Self::new(0.5, 0.5);
+    pub const TRAILING: Self = Self::new(1.0, 0.5);
+    pub const BOTTOM_LEADING: Self = Self::new(0.0, 1.0);
+    pub const BOTTOM: Self = Self::new(0.5, 1.0);
+    pub const BOTTOM_TRAILING: Self = Self::new(1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the absolute position of this point inside `bounds`.
+    fn resolve(self, bounds: Rectangle) -> (f32, f32) {
+        (bounds.x + bounds.width * self.x, bounds.y + bounds.height * self.y)
+    }
+}
+
+impl Default for UnitPoint {
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+/// Rotation, scale and offset of a view. They change where the view is drawn and
+/// where it reacts to clicks, but not its layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Effects {
+    /// Clockwise rotation around the center, in radians.
+    pub rotation: f32,
+    pub scale: (f32, f32),
+    pub scale_anchor: UnitPoint,
+    pub offset: (f32, f32),
+}
+
+impl Effects {
+    /// Returns whether the effects leave the view where it is.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the transformation for a view laid out at `bounds`: rotation around the
+    /// center first, then scale around the anchor, then offset.
+    pub fn transformation(&self, bounds: Rectangle) -> Transformation {
+        let (cx, cy) = UnitPoint::CENTER.resolve(bounds);
+        let (ax, ay) = self.scale_anchor.resolve(bounds);
+
+        Transformation::translate(-cx, -cy)
+            .then(Transformation::rotate(self.rotation))
+            .then(Transformation::translate(cx - ax, cy - ay))
+            .then(Transformation::scale(self.scale.0, self.scale.1))
+            .then(Transformation::translate(ax + self.offset.0, ay + self.offset.1))
+    }
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            scale: (1.0, 1.0),
+            scale_anchor: UnitPoint::CENTER,
+            offset: (0.0, 0.0),
+        }
+    }
+}
+
+/// Builders for the geometry effects, available on every view type and on [`View`](crate::View).
+pub trait GeometryEffects: sealed::HasEffects + Sized {
+    /// Rotate the view clockwise around its center by `angle` radians, without changing its layout.
+    fn rotation_effect(mut self, angle: f32) -> Self {
+        self.effects_mut().rotation = angle;
+        self
+    }
+
+    /// Scale the view around `anchor` without changing its layout.
+    fn scale_effect(mut self, scale: f32, anchor: UnitPoint) -> Self {
+        let effects = self.effects_mut();
+        effects.scale = (scale, scale);
+        effects.scale_anchor = anchor;
+        self
+    }
+
+    /// Move the view by (`x`, `y`) logical pixels without changing its layout.
+    fn offset(mut self, x: f32, y: f32) -> Self {
+        self.effects_mut().offset = (x, y);
+        self
+    }
+}
+
+impl<T: sealed::HasEffects> GeometryEffects for T {}
+
+pub(crate) mod sealed {
+    use super::Effects;
+
+    /// Gives [`GeometryEffects`](super::GeometryEffects) access to a view's effects
+    /// while keeping the trait closed to other crates.
+    pub trait HasEffects {
+        fn effects_mut(&mut self) -> &mut Effects;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{layout, Limits};
+    use crate::render::Renderer;
+    use crate::view::{Button, VStack};
+    use crate::View;
+
+    #[test]
+    fn test_rotated_button_is_drawn_turned_around_its_center() {
+        let button = Button::new("A wide label").rotation_effect(std::f32::consts::FRAC_PI_2);
+        let view = View::from(VStack::new(vec![View::from(button)]).padding(60.0));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(240.0, 240.0), &renderer);
+        let bounds = node.children[0].bounds;
+        let (w, h) = (bounds.width, bounds.height);
+        let (cx, cy) = (bounds.x + w / 2.0, bounds.y + h / 2.0);
+
+        let mut buffer = vec![0u32; 240 * 240];
+        renderer.draw(&view, &node, &mut buffer, 240, 240, 0x00FF_FFFF, 0.0, 0.0);
+        let pixel = |x: f32, y: f32| buffer[y as usize * 240 + x as usize];
+        assert_eq!(pixel(cx + h / 2.0 - 3.0, cy + w / 2.0 - 10.0), 0x3380FF, "the button covers a vertical strip");
+        assert_eq!(pixel(cx - w / 2.0 + 4.0, cy), 0x00FF_FFFF, "nothing is drawn where it was laid out");
+    }
+}
@@ -0,0 +1,135 @@
+//! Shape views: rectangles, circles, capsules, ellipses and custom paths.
+
+use crate::core::path::{Path, Stroke};
+use crate::core::{Background, Color, Point, Vector};
+use crate::layout::Rectangle;
+use crate::view::Effects;
+
+/// The outline drawn by a [`Shape`] inside its frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeKind {
+    Rectangle,
+    RoundedRectangle(f32),
+    /// The largest circle centered in the frame.
+    Circle,
+    /// A rectangle whose shorter sides are fully rounded.
+    Capsule,
+    Ellipse,
+    /// A custom path, in coordinates relative to the top-left corner of the frame.
+    Path(Path),
+}
+
+impl ShapeKind {
+    /// Returns the outline of the shape placed in `bounds`.
+    pub fn path(&self, bounds: Rectangle) -> Path {
+        match self {
+            ShapeKind::Rectangle => Path::rectangle(bounds),
+            ShapeKind::RoundedRectangle(radius) => Path::rounded_rectangle(bounds, *radius),
+            ShapeKind::Circle => Path::circle(
+                Point::new(bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0),
+                bounds.width.min(bounds.height) / 2.0,
+            ),
+            ShapeKind::Capsule => Path::rounded_rectangle(bounds, bounds.width.min(bounds.height) / 2.0),
+            ShapeKind::Ellipse => Path::ellipse(bounds),
+            ShapeKind::Path(path) => path.clone().translate(Vector::new(bounds.x, bounds.y)),
+        }
+    }
+}
+
+/// A view that fills its proposed frame with a shape.
+///
+/// Without `.fill(...)` or `.stroke(...)` the shape is filled with black.
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub(crate) kind: ShapeKind,
+    pub(crate) fill: Option<Background>,
+    pub(crate) stroke: Option<Stroke>,
+    pub(crate) frame: Option<(f32, f32)>,
+    pub(crate) effects: Effects,
+}
+
+impl Shape {
+    pub fn new(kind: ShapeKind) -> Self {
+        Self {
+            kind,
+            fill: None,
+            stroke: None,
+            frame: None,
+            effects: Effects::default(),
+        }
+    }
+
+    pub fn rectangle() -> Self {
+        Self::new(ShapeKind::Rectangle)
+    }
+
+    pub fn rounded_rectangle(radius: f32) -> Self {
+        Self::new(ShapeKind::RoundedRectangle(radius))
+    }
+
+    pub fn circle() -> Self {
+        Self::new(ShapeKind::Circle)
+    }
+
+    pub fn capsule() -> Self {
+        Self::new(ShapeKind::Capsule)
+    }
+
+    pub fn ellipse() -> Self {
+        Self::new(ShapeKind::Ellipse)
+    }
+
+    /// A custom shape. The path is relative to the top-left corner of the frame.
+    pub fn path(path: Path) -> Self {
+        Self::new(ShapeKind::Path(path))
+    }
+
+    /// Fill the inside of the shape with a color or gradient.
+    pub fn fill<B: Into<Background>>(mut self, background: B) -> Self {
+        self.fill = Some(background.into());
+        self
+    }
+
+    /// Draw the outline of the shape. `dash` alternates dash and gap lengths; pass `&[]` for a solid line.
+    pub fn stroke(mut self, color: Color, width: f32, dash: &[f32]) -> Self {
+        self.stroke = Some(Stroke::new(color, width).dash(dash));
+        self
+    }
+
+    /// Give the shape a fixed frame size in logical pixels.
+    pub fn frame(mut self, width: f32, height: f32) -> Self {
+        self.frame = Some((width, height));
+        self
+    }
+
+    /// The fill to use: the explicit one, or black if the shape is neither filled nor stroked.
+    pub(crate) fn effective_fill(&self) -> Option<Background> {
+        match (&self.fill, &self.stroke) {
+            (Some(fill), _) => Some(fill.clone()),
+            (None, None) => Some(Background::Color(Color::BLACK)),
+            (None, Some(_)) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{layout, Limits, Size};
+    use crate::render::Renderer;
+    use crate::View;
+
+    fn laid_out(shape: Shape) -> Size {
+        layout(&View::from(shape), Limits::loose(40.0, 20.0), &Renderer::new()).size()
+    }
+
+    #[test]
+    fn test_shapes_fill_the_proposed_size() {
+        assert_eq!(laid_out(Shape::capsule()), Size::new(40.0, 20.0));
+    }
+
+    #[test]
+    fn test_framed_shapes_take_their_frame() {
+        assert_eq!(laid_out(Shape::circle().frame(10.0, 4.0)), Size::new(10.0, 4.0));
+    }
+}
@@ -0,0 +1,113 @@
+//! TextEditor view: multi-line editable text bound to a string.
+
+use crate::core::font::Font;
+use crate::core::{Background, Border, Color};
+use crate::layout::Rectangle;
+use crate::view::{Binding, Editing, Effects};
+
+/// A scrollable, multi-line text input for longer text such as notes or
+/// configuration. Lines wrap at the width of the view; edits are written back to its
+/// [`Binding`] as the user types.
+///
+/// Besides the keys of a [`TextField`](crate::view::TextField), Return starts a new
+/// line, Up/Down and Page Up/Page Down move between lines, Ctrl+Home/End (Cmd + arrows
+/// on macOS) go to the start or end of the text, and Ctrl+Z/Ctrl+Shift+Z undo and
+/// redo. The mouse wheel scrolls.
+#[derive(Debug, Clone)]
+pub struct TextEditor {
+    pub(crate) text: Binding<String>,
+    pub(crate) font: Font,
+    pub(crate) size: Option<f32>,
+    pub(crate) color: Option<Color>,
+    pub(crate) padding: f32,
+    pub(crate) frame: Option<(f32, f32)>,
+    pub(crate) background: Option<Background>,
+    pub(crate) border: Border,
+    pub(crate) effects: Effects,
+    /// Cursor, selection and caret of the focused editor, set by the window before drawing.
+    pub(crate) editing: Option<Editing>,
+    /// How far the content is scrolled up, set by the window.
+    pub(crate) scroll: f32,
+}
+
+impl TextEditor {
+    pub fn new(text: &Binding<String>) -> Self {
+        Self {
+            text: text.clone(),
+            font: Font::default(),
+            size: None,
+            color: None,
+            padding: 6.0,
+            frame: None,
+            background: Some(Background::Color(Color::WHITE)),
+            border: Border::new(Color::new(0.75, 0.75, 0.75, 1.0), 1.0, 5.0),
+            effects: Effects::default(),
+            editing: None,
+            scroll: 0.0,
+        }
+    }
+
+    /// Set the font family, weight, style and stretch. If not set, uses regular sans-serif.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Set the font size in pixels. If not set, uses the default size.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the text color. If not set, uses black.
+    pub fn color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Set the space between the border and the text.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Give the editor a fixed frame size in logical pixels. If not set, it takes the
+    /// size its parent proposes.
+    pub fn frame(mut self, width: f32, height: f32) -> Self {
+        self.frame = Some((width, height));
+        self
+    }
+
+    pub fn background<B: Into<Background>>(mut self, background: B) -> Self {
+        self.background = Some(background.into());
+        self
+    }
+
+    pub fn clear_background(mut self) -> Self {
+        self.background = None;
+        self
+    }
+
+    /// Draw a border of the given color and width inside the bounds.
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border.color = color;
+        self.border.width = width;
+        self
+    }
+
+    /// Round the corners of the background with the given radius.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.border.radius = radius;
+        self
+    }
+
+    /// Returns the bounds of the text inside an editor laid out at `bounds`.
+    pub(crate) fn inner_bounds(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle::new(
+            bounds.x + self.padding,
+            bounds.y + self.padding,
+            (bounds.width - self.padding * 2.0).max(0.0),
+            (bounds.height - self.padding * 2.0).max(0.0),
+        )
+    }
+}
@@ -0,0 +1,267 @@
+//! TextField view: a single line of editable text bound to a string.
+
+use std::ops::Range;
+
+use crate::core::font::Font;
+use crate::core::text::{Attributes, Span};
+use crate::core::{Background, Border, Color};
+use crate::layout::Rectangle;
+use crate::view::{Binding, Effects};
+
+/// Shown in place of every character of a secure field.
+const BULLET: char = '\u{2022}';
+
+/// A single-line text input. Edits are written back to its [`Binding`] as the user
+/// types; clicking the field focuses it.
+///
+/// Keys move the cursor by character (arrows), word (Ctrl or Alt + arrows) and line
+/// (Home/End, or Cmd + arrows on macOS); Shift extends the selection. Ctrl+A, Ctrl+C,
+/// Ctrl+X and Ctrl+V (Cmd on macOS) select all, copy, cut and paste.
+#[derive(Clone)]
+pub struct TextField {
+    pub(crate) text: Binding<String>,
+    pub(crate) placeholder: String,
+    pub(crate) secure: bool,
+    pub(crate) on_submit: Option<fn(&str)>,
+    pub(crate) font: Font,
+    pub(crate) size: Option<f32>,
+    pub(crate) color: Option<Color>,
+    pub(crate) padding: f32,
+    pub(crate) width: Option<f32>,
+    pub(crate) background: Option<Background>,
+    pub(crate) border: Border,
+    pub(crate) effects: Effects,
+    /// Cursor, selection and caret of the focused field, set by the window before drawing.
+    pub(crate) editing: Option<Editing>,
+    /// How far the content is scrolled left to keep the cursor in view, set by the window.
+    pub(crate) scroll: f32,
+}
+
+impl std::fmt::Debug for TextField {
+    /// Formats the field without the value of a secure field, or the text being
+    /// composed into it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        struct Redacted;
+
+        impl std::fmt::Debug for Redacted {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("<redacted>")
+            }
+        }
+
+        let mut debug = f.debug_struct("TextField");
+        if self.secure {
+            debug.field("text", &Redacted);
+        } else {
+            debug.field("text", &self.text);
+        }
+        debug
+            .field("placeholder", &self.placeholder)
+            .field("secure", &self.secure)
+            .field("on_submit", &self.on_submit)
+            .field("font", &self.font)
+            .field("size", &self.size)
+            .field("color", &self.color)
+            .field("padding", &self.padding)
+            .field("width", &self.width)
+            .field("background", &self.background)
+            .field("border", &self.border)
+            .field("effects", &self.effects);
+        match &self.editing {
+            Some(editing @ Editing { preedit: Some(_), .. }) if self.secure => {
+                debug.field("editing", &Some(Editing { preedit: Some("<redacted>".into()), ..editing.clone() }))
+            }
+            editing => debug.field("editing", editing),
+        };
+        debug.field("scroll", &self.scroll).finish()
+    }
+}
+
+/// What the window knows about the field or editor being edited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Editing {
+    /// Byte offset of the cursor in the value.
+    pub cursor: usize,
+    /// Selected byte range of the value.
+    pub selection: Option<Range<usize>>,
+    /// Text being composed by an input method, shown at the cursor.
+    pub preedit: Option<String>,
+    pub caret_visible: bool,
+}
+
+impl TextField {
+    pub fn new(text: &Binding<String>) -> Self {
+        Self {
+            text: text.clone(),
+            placeholder: String::new(),
+            secure: false,
+            on_submit: None,
+            font: Font::default(),
+            size: None,
+            color: None,
+            padding: 6.0,
+            width: None,
+            background: Some(Background::Color(Color::WHITE)),
+            border: Border::new(Color::new(0.75, 0.75, 0.75, 1.0), 1.0, 5.0),
+            effects: Effects::default(),
+            editing: None,
+            scroll: 0.0,
+        }
+    }
+
+    /// Set the gray hint shown while the field is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Show a bullet for every character, for passwords. Secure text can't be copied.
+    pub fn secure(mut self, enabled: bool) -> Self {
+        self.secure = enabled;
+        self
+    }
+
+    /// Call `callback` with the text when the user presses Return.
+    pub fn on_submit(mut self, callback: fn(&str)) -> Self {
+        self.on_submit = Some(callback);
+        self
+    }
+
+    /// Set the font family, weight, style and stretch. If not set, uses regular sans-serif.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Set the font size in pixels. If not set, uses the default size.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the text color. If not set, uses black.
+    pub fn color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Set the space between the border and the text.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Give the field a fixed width. If not set, it takes the width its parent proposes.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn background<B: Into<Background>>(mut self, background: B) -> Self {
+        self.background = Some(background.into());
+        self
+    }
+
+    pub fn clear_background(mut self) -> Self {
+        self.background = None;
+        self
+    }
+
+    /// Draw a border of the given color and width inside the bounds.
+    pub fn border(mut self, color: Color, width: f32) -> Self {
+        self.border.color = color;
+        self.border.width = width;
+        self
+    }
+
+    /// Round the corners of the background with the given radius.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.border.radius = radius;
+        self
+    }
+
+    /// Returns the bounds of the text inside a field laid out at `bounds`.
+    pub(crate) fn inner_bounds(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle::new(
+            bounds.x + self.padding,
+            bounds.y + self.padding,
+            (bounds.width - self.padding * 2.0).max(0.0),
+            (bounds.height - self.padding * 2.0).max(0.0),
+        )
+    }
+
+    /// Returns what the field shows for `value`: a bullet per character if it is
+    /// secure, with the text being composed by an input method underlined at the cursor.
+    pub(crate) fn display(&self, value: &str) -> (String, Vec<Span>) {
+        let mut shown = if self.secure {
+            std::iter::repeat_n(BULLET, value.chars().count()).collect()
+        } else {
+            value.to_string()
+        };
+        let mut spans = Vec::new();
+        if let Some(Editing { cursor, preedit: Some(preedit), .. }) = &self.editing {
+            let at = self.display_offset(value, *cursor);
+            shown.insert_str(at, preedit);
+            spans.push(Span {
+                range: at..at + preedit.len(),
+                attributes: Attributes::new().underline(),
+            });
+        }
+        (shown, spans)
+    }
+
+    /// Maps a byte offset in `value` to the same position in what the field shows,
+    /// not counting text being composed.
+    pub(crate) fn display_offset(&self, value: &str, offset: usize) -> usize {
+        if self.secure {
+            value[..offset].chars().count() * BULLET.len_utf8()
+        } else {
+            offset
+        }
+    }
+
+    /// Maps a byte offset in what the field shows back to `value`.
+    pub(crate) fn value_offset(&self, value: &str, offset: usize) -> usize {
+        if !self.secure {
+            return offset.min(value.len());
+        }
+        let chars = offset / BULLET.len_utf8();
+        value.char_indices().nth(chars).map_or(value.len(), |(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::VStack;
+    use crate::View;
+
+    #[test]
+    fn test_debug_redacts_the_value_of_secure_fields() {
+        let password = Binding::new(String::from("hunter2"));
+        let mut secure = TextField::new(&password).secure(true);
+        secure.editing = Some(Editing { preedit: Some("x9".into()), ..Editing::default() });
+        let printed = format!("{:?}", View::from(VStack::new(vec![View::from(secure)])));
+        assert!(!printed.contains("hunter2") && !printed.contains("x9"), "{printed}");
+        assert!(printed.contains("<redacted>"));
+
+        let plain = format!("{:?}", TextField::new(&password));
+        assert!(plain.contains("hunter2"), "{plain}");
+    }
+
+    #[test]
+    fn test_field_takes_the_direction_of_its_stack() {
+        use crate::layout::{layout, LayoutDirection, Limits};
+        use crate::render::Renderer;
+        use crate::view::HStack;
+
+        let field = TextField::new(&Binding::new("abc".to_string())).width(200.0).clear_background();
+        let view = View::from(HStack::new(vec![View::from(field)]).layout_direction(LayoutDirection::RightToLeft));
+        let mut renderer = Renderer::with_bundled_fonts();
+        let node = layout(&view, Limits::loose(200.0, 100.0), &renderer);
+        let mut buffer = vec![0x00FF_FFFFu32; 200 * 100];
+        renderer.draw(&view, &node, &mut buffer, 200, 100, 0x00FF_FFFF, 0.0, 0.0);
+        let inked = |columns: std::ops::Range<usize>| (0..100).any(|y| columns.clone().any(|x| buffer[y * 200 + x] != 0x00FF_FFFF));
+        assert!(inked(150..200) && !inked(20..100), "the text sits at the right edge");
+    }
+}
@@ -5,6 +5,7 @@ use crate::core::layout::Layout;
 use crate::layout::{Limits, Node, Size, Rectangle};
 use crate::core::length::Length;
 use crate::core::mouse::Cursor;
+use crate::core::renderer::Quad;
 use crate::core::tree::Tree;
 use crate::core::widget::Widget;
 use crate::core::Color;
@@ -139,6 +140,10 @@ where
         cursor: Cursor,
         viewport: &Rectangle,
     ) {
+        if let Some(color) = self.background {
+            renderer.fill_quad(Quad::new(layout.bounds()), color);
+        }
+
         // Draw children
         for ((child, state), layout) in self
             .children
@@ -4,7 +4,7 @@ use crate::core::layout::Layout;
 use crate::layout::{Limits, Node};
 use crate::core::length::Length;
 use crate::core::mouse::Cursor;
-use crate::core::renderer::Renderer as RendererTrait;
+use crate::core::text::{self, Renderer as TextRenderer};
 use crate::core::tree::Tree;
 use crate::core::widget::Widget;
 use crate::layout::{Rectangle, Size, TextMeasurer};
@@ -34,7 +34,7 @@ impl Text {
 
 impl<Message, R> Widget<Message, R> for Text
 where
-    R: RendererTrait + TextMeasurer,
+    R: TextRenderer + TextMeasurer,
 {
     fn size(&self) -> Size<Length> {
         let font_size = self.size.unwrap_or(DEFAULT_FONT_SIZE);
@@ -62,9 +62,9 @@ where
         _cursor: Cursor,
         _viewport: &Rectangle,
     ) {
-        // Note: This requires the renderer to have access to the buffer
-        // For now, this is a placeholder - actual drawing happens at the window level
-        // The renderer needs to be extended to support drawing with Layout
-        let _ = (renderer, layout);
+        renderer.fill_text(text::Text {
+            size: self.size.unwrap_or(DEFAULT_FONT_SIZE),
+            ..text::Text::new(self.string.clone(), layout.bounds())
+        });
     }
 }
@@ -1,18 +1,29 @@
 //! Window and event loop: winit + softbuffer.
 
 use std::num::NonZeroU32;
+use std::ops::Range;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use winit::application::ApplicationHandler;
-use winit::event::{WindowEvent, MouseButton, ElementState};
-use winit::event_loop::ActiveEventLoop;
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, StartCause, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
+use winit::keyboard::{Key, ModifiersState};
 use winit::window::WindowAttributes;
 
 use crate::app::{ContentPosition, ContentSizing, Scene, WindowConfiguration};
-use crate::layout::{layout, Limits, Node, Rectangle};
-use crate::render::Renderer;
+use crate::clipboard::Clipboard;
+use crate::core::renderer::Renderer as _;
+use crate::core::Point;
+use crate::layout::{layout_with_direction, LayoutDirection, Limits, Node, Rectangle};
+use crate::render::{path_contains, text_primitive, Renderer, DEFAULT_BACKGROUND};
+use crate::view::ShapeKind;
 use crate::View;
 
+pub(crate) mod text_input;
+
+use text_input::{Command, TextInput};
+
 /// Wraps a winit Window in Rc so we can share it with softbuffer::Surface (which requires Clone).
 #[derive(Clone)]
 struct WindowRef(Rc<winit::window::Window>);
@@ -49,6 +60,9 @@ pub fn run(view_fn: impl Fn() -> View + 'static) {
         offset_x: 0.0,
         offset_y: 0.0,
         cursor_pos: (0.0, 0.0),
+        selection: Selection::default(),
+        text_input: TextInput::default(),
+        modifiers: ModifiersState::empty(),
     };
     let _ = event_loop.run_app(&mut app);
 }
@@ -59,6 +73,12 @@ pub fn run_scene(scene: Scene) {
         Scene::WindowGroup { content, config } => {
             let mut renderer = Renderer::new();
             let _ = renderer.load_default_font();
+            renderer.set_text_antialiasing(config.text_antialiasing);
+            for source in &config.fonts {
+                if let Err(error) = renderer.load_font_source(source) {
+                    log::warn!("could not register font: {error}");
+                }
+            }
 
             let event_loop = winit::event_loop::EventLoop::new().expect("event loop");
             let display_handle = event_loop.owned_display_handle();
@@ -74,6 +94,9 @@ pub fn run_scene(scene: Scene) {
                 offset_x: 0.0,
                 offset_y: 0.0,
                 cursor_pos: (0.0, 0.0),
+                selection: Selection::default(),
+                text_input: TextInput::default(),
+                modifiers: ModifiersState::empty(),
             };
             let _ = event_loop.run_app(&mut app);
         }
@@ -91,6 +114,10 @@ struct InternalApp<D, F> {
     offset_x: f32,
     offset_y: f32,
     cursor_pos: (f32, f32),
+    selection: Selection,
+    /// The text field or editor being edited, and how far each is scrolled.
+    text_input: TextInput,
+    modifiers: ModifiersState,
 }
 
 impl<D, F> ApplicationHandler for InternalApp<D, F>
@@ -145,10 +172,80 @@ where
             WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Pressed, .. } => {
                 // Handle mouse clicks
                 self.handle_click(self.cursor_pos.0, self.cursor_pos.1);
+                self.handle_pointer(|selection, text_input, view, node, renderer, (x, y), (offset_x, offset_y)| {
+                    let now = Instant::now();
+                    let focused = text_input.press(view, node, renderer, x, y, offset_x, offset_y, now);
+                    selection.press(view, node, renderer, x, y, offset_x, offset_y, now) | focused
+                });
+                // Input methods only compose while a field is being edited
+                if let Some(ref window) = self.window {
+                    window.0.set_ime_allowed(self.text_input.is_focused());
+                }
+            }
+            WindowEvent::MouseInput { button: MouseButton::Left, state: ElementState::Released, .. } => {
+                self.selection.release();
+                self.text_input.release();
             }
             WindowEvent::CursorMoved { position, .. } => {
-                // Store mouse position for click testing
-                self.cursor_pos = (position.x as f32, position.y as f32);
+                // Store mouse position for click testing, in logical pixels like the layout
+                let scale_factor = self.window.as_ref().map_or(1.0, |window| window.0.scale_factor());
+                let position = position.to_logical::<f32>(scale_factor);
+                self.cursor_pos = (position.x, position.y);
+                if self.selection.is_dragging() {
+                    self.handle_pointer(|selection, _, view, node, renderer, (x, y), (offset_x, offset_y)| {
+                        selection.drag(view, node, renderer, x, y, offset_x, offset_y)
+                    });
+                }
+                if self.text_input.is_dragging() {
+                    self.handle_pointer(|_, text_input, view, node, renderer, (x, y), (offset_x, offset_y)| {
+                        text_input.drag(view, node, renderer, x, y, offset_x, offset_y)
+                    });
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                // The text editor under the mouse scrolls
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(_, lines) => lines * SCROLL_LINE,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        let scale_factor = self.window.as_ref().map_or(1.0, |window| window.0.scale_factor());
+                        position.to_logical::<f32>(scale_factor).y
+                    }
+                };
+                self.handle_pointer(|_, text_input, view, node, renderer, (x, y), (offset_x, offset_y)| {
+                    text_input.scroll(view, node, renderer, x, y, offset_x, offset_y, delta)
+                });
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                // The focused field or editor takes the keyboard
+                if self.text_input.is_focused() {
+                    if let Some(command) = text_input::command(&event, self.modifiers) {
+                        self.edit(command);
+                    }
+                    return;
+                }
+                // Ctrl+C, or Cmd+C on macOS, copies the selected text
+                let shortcut = self.modifiers.control_key() || self.modifiers.super_key();
+                if shortcut && matches!(&event.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("c")) {
+                    let view = (self.content_fn)();
+                    self.selection.copy(&view, self.config.clipboard.as_ref());
+                }
+            }
+            WindowEvent::Ime(Ime::Preedit(text, _)) => {
+                self.text_input.set_preedit(Some(text));
+                self.request_redraw();
+            }
+            WindowEvent::Ime(Ime::Commit(text)) => {
+                self.edit(Command::Insert(text));
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                // winit resizes the window to keep its logical size; redraw at the new density
+                self.renderer.hint(scale_factor as f32);
+                if let Some(ref window) = self.window {
+                    window.0.request_redraw();
+                }
             }
             WindowEvent::Resized(size) => {
                 if let Some(ref window) = self.window {
@@ -168,6 +265,20 @@ where
             _ => {}
         }
     }
+
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        // Time to turn the caret on or off
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            self.request_redraw();
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        match self.text_input.next_blink(Instant::now()) {
+            Some(blink) => event_loop.set_control_flow(ControlFlow::WaitUntil(blink)),
+            None => event_loop.set_control_flow(ControlFlow::Wait),
+        }
+    }
 }
 
 impl<D, F> InternalApp<D, F>
@@ -187,35 +298,42 @@ where
             let _ = surface.resize(w, h);
         }
         let Ok(mut buffer) = surface.buffer_mut() else { return };
-        let pixels: &mut [u32] = &mut *buffer;
+        // A buffer that still shows a recent frame only needs the regions changed since
+        self.renderer.hint_buffer_age(buffer.age());
+        let pixels: &mut [u32] = &mut buffer;
+
+        // Layout happens in logical pixels; the renderer scales to the physical buffer
+        let scale_factor = window.0.scale_factor() as f32;
+        self.renderer.hint(scale_factor);
+        let (logical_width, logical_height) = (width as f32 / scale_factor, height as f32 / scale_factor);
         
         // Rebuild view tree on each draw for reactivity
-        let view = (self.content_fn)();
+        let mut view = (self.content_fn)();
         
-        // Determine content limits based on sizing mode
-        let (content_limits, offset_x, offset_y) = match self.config.content_sizing {
+        // Lay out once according to the sizing mode; the content size decides the offset
+        let (layout_root, offset_x, offset_y) = match self.config.content_sizing {
             ContentSizing::Auto => {
                 // Use loose limits and position based on content_position
-                let limits = Limits::loose(width as f32, height as f32);
-                let layout_root = layout(&view, limits, &self.renderer);
+                let limits = Limits::loose(logical_width, logical_height);
+                let layout_root = layout_with_direction(&view, limits, &self.renderer, self.config.layout_direction);
                 let (offset_x, offset_y) = InternalApp::<D, F>::calculate_content_offset(
                     &self.config,
                     layout_root.bounds.width,
                     layout_root.bounds.height,
-                    width as f32,
-                    height as f32
+                    logical_width,
+                    logical_height
                 );
-                (limits, offset_x, offset_y)
+                (layout_root, offset_x, offset_y)
             },
             ContentSizing::FillWindow => {
                 // Use exact window size limits
                 let limits = Limits {
-                    min_width: width as f32,
-                    min_height: height as f32,
-                    max_width: width as f32,
-                    max_height: height as f32,
+                    min_width: logical_width,
+                    min_height: logical_height,
+                    max_width: logical_width,
+                    max_height: logical_height,
                 };
-                (limits, 0.0, 0.0)
+                (layout_with_direction(&view, limits, &self.renderer, self.config.layout_direction), 0.0, 0.0)
             },
             ContentSizing::Fixed(w, h) => {
                 // Use fixed size limits and position based on content_position
@@ -225,48 +343,70 @@ where
                     max_width: w,
                     max_height: h,
                 };
-                let (offset_x, offset_y) = InternalApp::<D, F>::calculate_content_offset(&self.config, w, h, width as f32, height as f32);
-                (limits, offset_x, offset_y)
+                let (offset_x, offset_y) = InternalApp::<D, F>::calculate_content_offset(&self.config, w, h, logical_width, logical_height);
+                (layout_with_direction(&view, limits, &self.renderer, self.config.layout_direction), offset_x, offset_y)
             },
             ContentSizing::Minimum(min_w, min_h) => {
                 // Use minimum size but allow expansion
                 let limits = Limits {
                     min_width: min_w,
                     min_height: min_h,
-                    max_width: width as f32,
-                    max_height: height as f32,
+                    max_width: logical_width,
+                    max_height: logical_height,
                 };
-                let layout_root = layout(&view, limits, &self.renderer);
+                let layout_root = layout_with_direction(&view, limits, &self.renderer, self.config.layout_direction);
                 let (offset_x, offset_y) = InternalApp::<D, F>::calculate_content_offset(
                     &self.config,
                     layout_root.bounds.width,
                     layout_root.bounds.height,
-                    width as f32,
-                    height as f32
+                    logical_width,
+                    logical_height
                 );
-                (limits, offset_x, offset_y)
+                (layout_root, offset_x, offset_y)
             },
         };
         
-        let layout_root = layout(&view, content_limits, &self.renderer);
-        
         // Store layout and offset for click testing
         self.layout_root = Some(layout_root.clone());
         self.offset_x = offset_x;
         self.offset_y = offset_y;
+
+        // Selections and the edited field are drawn where the layout put them
+        self.selection.apply(&mut view);
+        self.text_input.apply(&mut view, &layout_root, &self.renderer, offset_x, offset_y, Instant::now());
+        if let Some(caret) = self.text_input.caret() {
+            let position = winit::dpi::LogicalPosition::new(caret.x, caret.y);
+            window.0.set_ime_cursor_area(position, winit::dpi::LogicalSize::new(caret.width, caret.height));
+        }
         
-        let background = 0x00_EE_EE_EEu32; // light gray
         self.renderer.draw(
             &view,
             &layout_root,
             pixels,
             width,
             height,
-            background,
+            DEFAULT_BACKGROUND,
             offset_x,
             offset_y,
         );
-        let _ = buffer.present();
+
+        // Only hand the changed regions to the compositor; an unchanged frame needs no present
+        let damage: Vec<softbuffer::Rect> = self
+            .renderer
+            .damage()
+            .iter()
+            .filter_map(|rect| {
+                Some(softbuffer::Rect {
+                    x: rect.x as u32,
+                    y: rect.y as u32,
+                    width: NonZeroU32::new(rect.width as u32)?,
+                    height: NonZeroU32::new(rect.height as u32)?,
+                })
+            })
+            .collect();
+        if !damage.is_empty() {
+            let _ = buffer.present_with_damage(&damage);
+        }
     }
 
     fn calculate_content_offset(
@@ -279,15 +419,56 @@ where
         let extra_width = window_width - content_width;
         let extra_height = window_height - content_height;
 
+        // Leading and trailing corners swap sides right to left
+        let (leading, trailing) = match config.layout_direction {
+            LayoutDirection::LeftToRight => (0.0, extra_width),
+            LayoutDirection::RightToLeft => (extra_width, 0.0),
+        };
         match config.content_position {
-            ContentPosition::Leading => (0.0, 0.0), // Top-left
+            ContentPosition::Leading => (leading, 0.0), // Top-left
             ContentPosition::Center => (extra_width / 2.0, extra_height / 2.0), // Center
-            ContentPosition::Trailing => (extra_width, extra_height), // Bottom-right
+            ContentPosition::Trailing => (trailing, extra_height), // Bottom-right
             ContentPosition::TopCenter => (extra_width / 2.0, 0.0), // Top-center
             ContentPosition::BottomCenter => (extra_width / 2.0, extra_height), // Bottom-center
         }
     }
 
+    /// Runs a mouse gesture on the selection and the text inputs against the current
+    /// view tree, and redraws if it changed either.
+    #[allow(clippy::type_complexity)]
+    fn handle_pointer(
+        &mut self,
+        gesture: impl FnOnce(&mut Selection, &mut TextInput, &View, &Node, &Renderer, (f32, f32), (f32, f32)) -> bool,
+    ) {
+        let Some(ref layout_root) = self.layout_root else { return };
+        let view = (self.content_fn)();
+        let changed = gesture(
+            &mut self.selection,
+            &mut self.text_input,
+            &view,
+            layout_root,
+            &self.renderer,
+            self.cursor_pos,
+            (self.offset_x, self.offset_y),
+        );
+        if changed {
+            self.request_redraw();
+        }
+    }
+
+    /// Runs an editing command on the focused field or editor.
+    fn edit(&mut self, command: Command) {
+        if self.text_input.execute(command, &self.renderer, self.config.clipboard.as_ref()) {
+            self.request_redraw();
+        }
+    }
+
+    fn request_redraw(&self) {
+        if let Some(ref window) = self.window {
+            window.0.request_redraw();
+        }
+    }
+
     fn handle_click(&self, x: f32, y: f32) {
         if let Some(ref layout_root) = self.layout_root {
             let view = (self.content_fn)();
@@ -296,48 +477,424 @@ where
     }
 
     fn test_click(&self, view: &View, node: &Node, x: f32, y: f32, offset_x: f32, offset_y: f32) {
-        // Adjust coordinates for content offset
-        let local_x = x - offset_x;
-        let local_y = y - offset_y;
-        
-        // Test if click is within this node's bounds
-        if local_x >= node.bounds.x &&
-           local_x <= node.bounds.x + node.bounds.width &&
-           local_y >= node.bounds.y &&
-           local_y <= node.bounds.y + node.bounds.height {
-            
-            // Check if this is a button
-            if let View::Button(button) = view {
-                if let Some(ref callback) = button.on_click {
-                    callback();
-                    // Request a redraw after button click to update UI
-                    if let Some(ref window) = self.window {
-                        window.0.request_redraw();
-                    }
-                    return; // Found a button, execute callback and stop
-                }
+        if let Some(callback) = button_at(view, node, x, y, offset_x, offset_y) {
+            callback();
+            // Request a redraw after button click to update UI
+            if let Some(ref window) = self.window {
+                window.0.request_redraw();
             }
-            
-            // Recursively check children
-            match view {
-                View::VStack(vstack) => {
-                    for (child_view, child_node) in vstack.children.iter().zip(node.children.iter()) {
-                        self.test_click(child_view, child_node, x, y, offset_x + node.bounds.x, offset_y + node.bounds.y);
-                    }
-                }
-                View::HStack(hstack) => {
-                    for (child_view, child_node) in hstack.children.iter().zip(node.children.iter()) {
-                        self.test_click(child_view, child_node, x, y, offset_x + node.bounds.x, offset_y + node.bounds.y);
-                    }
-                }
-                _ => {
-                    // For other views, check children if any
-                    for child_node in &node.children {
-                        // This is a simplified version - in a real implementation,
-                        // we'd need to track which child view corresponds to which node
-                    }
-                }
+        }
+    }
+}
+
+/// Returns the bounds `node` was laid out at, in logical window coordinates, and
+/// (`x`, `y`) mapped into the space it was laid out in. `offset_x`/`offset_y` is
+/// where the parent of `node` was laid out.
+fn locate(view: &View, node: &Node, x: f32, y: f32, offset_x: f32, offset_y: f32) -> Option<(Rectangle, f32, f32)> {
+    let bounds = Rectangle::new(offset_x + node.bounds.x, offset_y + node.bounds.y, node.bounds.width, node.bounds.height);
+
+    // Rotated, scaled or offset views are hit where they are drawn: map the point
+    // back into the space the view was laid out in (children share that space).
+    let effects = view.effects();
+    if effects.is_identity() {
+        return Some((bounds, x, y));
+    }
+    let point = effects.transformation(bounds).inverse()?.transform_point(Point::new(x, y));
+    Some((bounds, point.x, point.y))
+}
+
+fn contains(bounds: Rectangle, x: f32, y: f32) -> bool {
+    x >= bounds.x && x <= bounds.x + bounds.width && y >= bounds.y && y <= bounds.y + bounds.height
+}
+
+/// Returns whether (`x`, `y`) falls on the view: inside its bounds and, for a stack
+/// clipped to a shape, inside that shape, which also cuts off its children.
+fn hit(view: &View, bounds: Rectangle, x: f32, y: f32) -> bool {
+    contains(bounds, x, y)
+        && match view.clip_shape() {
+            None | Some(ShapeKind::Rectangle) => true,
+            Some(shape) => path_contains(&shape.path(bounds), Point::new(x, y)),
+        }
+}
+
+/// Returns the children of a stack; other views have none.
+fn children(view: &View) -> &[View] {
+    match view {
+        View::VStack(vstack) => &vstack.children,
+        View::HStack(hstack) => &hstack.children,
+        _ => &[],
+    }
+}
+
+/// Returns the click handler of the topmost button under (`x`, `y`), in logical window
+/// coordinates. `offset_x`/`offset_y` is where the parent of `node` was laid out.
+pub(crate) fn button_at(view: &View, node: &Node, x: f32, y: f32, offset_x: f32, offset_y: f32) -> Option<fn()> {
+    let (bounds, x, y) = locate(view, node, x, y, offset_x, offset_y)?;
+    if !hit(view, bounds, x, y) {
+        return None;
+    }
+
+    // Check if this is a button
+    if let View::Button(button) = view {
+        if let Some(callback) = button.on_click {
+            return Some(callback);
+        }
+    }
+
+    // Recursively check children, the last drawn (topmost) first
+    children(view)
+        .iter()
+        .zip(node.children.iter())
+        .rev()
+        .find_map(|(child_view, child_node)| button_at(child_view, child_node, x, y, bounds.x, bounds.y))
+}
+
+/// A view found in the tree, with its bounds and a point mapped into its space.
+type Located<'a> = (&'a View, Rectangle, f32, f32);
+
+/// Returns the topmost view under (`x`, `y`) that `accepts`, and pushes the child
+/// indices leading to it onto `path`.
+#[allow(clippy::too_many_arguments)]
+fn view_at<'a>(
+    view: &'a View,
+    node: &Node,
+    x: f32,
+    y: f32,
+    offset_x: f32,
+    offset_y: f32,
+    accepts: &dyn Fn(&View) -> bool,
+    path: &mut Vec<usize>,
+) -> Option<Located<'a>> {
+    let (bounds, x, y) = locate(view, node, x, y, offset_x, offset_y)?;
+    if !hit(view, bounds, x, y) {
+        return None;
+    }
+    if accepts(view) {
+        return Some((view, bounds, x, y));
+    }
+
+    for (index, (child_view, child_node)) in children(view).iter().zip(node.children.iter()).enumerate().rev() {
+        path.push(index);
+        if let Some(found) = view_at(child_view, child_node, x, y, bounds.x, bounds.y, accepts, path) {
+            return Some(found);
+        }
+        path.pop();
+    }
+    None
+}
+
+/// Returns the view at `path` with (`x`, `y`) mapped into its space, wherever the point is.
+fn view_at_path<'a>(view: &'a View, node: &Node, path: &[usize], x: f32, y: f32, offset_x: f32, offset_y: f32) -> Option<Located<'a>> {
+    let (bounds, x, y) = locate(view, node, x, y, offset_x, offset_y)?;
+    match path {
+        [] => Some((view, bounds, x, y)),
+        [index, rest @ ..] => {
+            view_at_path(children(view).get(*index)?, node.children.get(*index)?, rest, x, y, bounds.x, bounds.y)
+        }
+    }
+}
+
+fn view_at_path_mut<'a>(view: &'a mut View, path: &[usize]) -> Option<&'a mut View> {
+    match (view, path) {
+        (view, []) => Some(view),
+        (View::VStack(vstack), [index, rest @ ..]) => view_at_path_mut(vstack.children.get_mut(*index)?, rest),
+        (View::HStack(hstack), [index, rest @ ..]) => view_at_path_mut(hstack.children.get_mut(*index)?, rest),
+        _ => None,
+    }
+}
+
+/// Returns the layout direction of the view at `path`.
+fn direction_at(node: &Node, path: &[usize]) -> LayoutDirection {
+    match path {
+        [] => node.direction,
+        [index, rest @ ..] => node.children.get(*index).map_or(node.direction, |child| direction_at(child, rest)),
+    }
+}
+
+fn is_selectable(view: &View) -> bool {
+    matches!(view, View::Text(text) if text.selectable)
+}
+
+/// How far one notch of a mouse wheel scrolls, in logical pixels.
+const SCROLL_LINE: f32 = 40.0;
+
+/// How soon a second press on the same spot must follow the first to select a word.
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+/// The selection in a selectable text. Views are rebuilt every frame, so the text is
+/// remembered by its position in the tree: the child indices leading to it.
+#[derive(Debug, Default)]
+pub(crate) struct Selection {
+    /// Path to the text, and the byte offsets where the selection started and ends.
+    current: Option<(Vec<usize>, usize, usize)>,
+    dragging: bool,
+    last_press: Option<(Instant, Vec<usize>, usize)>,
+}
+
+impl Selection {
+    /// Starts selecting at a press of the mouse: a drag from the character boundary
+    /// under it, or the word under it on a double click. Returns whether the selection
+    /// changed.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn press(
+        &mut self,
+        view: &View,
+        node: &Node,
+        renderer: &Renderer,
+        x: f32,
+        y: f32,
+        offset_x: f32,
+        offset_y: f32,
+        now: Instant,
+    ) -> bool {
+        let mut path = Vec::new();
+        let Some((View::Text(text), bounds, x, y)) = view_at(view, node, x, y, offset_x, offset_y, &is_selectable, &mut path) else {
+            // A press anywhere else clears the selection
+            self.dragging = false;
+            self.last_press = None;
+            return self.current.take().is_some();
+        };
+        let primitive = text_primitive(text, bounds, direction_at(node, &path));
+        let offset = renderer.hit_text(&primitive, x - bounds.x, y - bounds.y);
+
+        let double_click = self.last_press.as_ref().is_some_and(|(time, last_path, last_offset)| {
+            now.duration_since(*time) < DOUBLE_CLICK && *last_path == path && *last_offset == offset
+        });
+        if double_click {
+            let word = word_at(&text.string, offset);
+            self.current = Some((path, word.start, word.end));
+            self.dragging = false;
+            self.last_press = None;
+        } else {
+            self.current = Some((path.clone(), offset, offset));
+            self.dragging = true;
+            self.last_press = Some((now, path, offset));
+        }
+        true
+    }
+
+    /// Extends the selection to the character boundary under the mouse while it is
+    /// dragged. Returns whether the selection changed.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn drag(&mut self, view: &View, node: &Node, renderer: &Renderer, x: f32, y: f32, offset_x: f32, offset_y: f32) -> bool {
+        if !self.dragging {
+            return false;
+        }
+        let Some((path, _, focus)) = &mut self.current else { return false };
+        let Some((View::Text(text), bounds, x, y)) = view_at_path(view, node, path, x, y, offset_x, offset_y) else {
+            return false;
+        };
+        let primitive = text_primitive(text, bounds, direction_at(node, path));
+        let offset = renderer.hit_text(&primitive, x - bounds.x, y - bounds.y);
+        std::mem::replace(focus, offset) != offset
+    }
+
+    pub(crate) fn release(&mut self) {
+        self.dragging = false;
+    }
+
+    pub(crate) fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Returns the path to the selected text and the selected byte range.
+    fn range(&self) -> Option<(&[usize], Range<usize>)> {
+        let (path, anchor, focus) = self.current.as_ref()?;
+        Some((path, *anchor.min(focus)..*anchor.max(focus)))
+    }
+
+    /// Marks the selected range on the text it belongs to, so it is drawn highlighted.
+    pub(crate) fn apply(&self, view: &mut View) {
+        if let Some((path, range)) = self.range() {
+            if let Some(View::Text(text)) = view_at_path_mut(view, path).filter(|view| is_selectable(view)) {
+                text.selection = Some(range);
             }
         }
     }
+
+    /// Returns the selected text, if the selection isn't empty.
+    pub(crate) fn selected_text(&self, view: &View) -> Option<String> {
+        let (path, range) = self.range()?;
+        let mut view = view;
+        for index in path {
+            view = children(view).get(*index)?;
+        }
+        match view {
+            View::Text(text) if text.selectable && !range.is_empty() => text.string.get(range).map(str::to_string),
+            _ => None,
+        }
+    }
+
+    /// Copies the selected text to `clipboard`. Returns whether there was any.
+    pub(crate) fn copy(&self, view: &View, clipboard: &dyn Clipboard) -> bool {
+        self.selected_text(view).map(|text| clipboard.write(text)).is_some()
+    }
+}
+
+/// Returns the word around `offset`, or the character after it when that isn't part
+/// of a word.
+fn word_at(content: &str, offset: usize) -> Range<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = content[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map_or(offset, |(index, _)| index);
+    let end = content[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_word(*c))
+        .map_or(content.len(), |(index, _)| offset + index);
+
+    if start == end {
+        let next = content[offset..].chars().next().map_or(0, char::len_utf8);
+        return offset..offset + next;
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::clipboard::MemoryClipboard;
+    use crate::layout::{layout, Limits};
+    use crate::layout::Size;
+    use crate::view::{Button, GeometryEffects, Text as TextView, UnitPoint, VStack};
+
+    /// Lays out a line that isn't selectable above one that is, and returns the
+    /// bounds of the selectable one.
+    fn selectable_text(renderer: &Renderer) -> (View, Node, Rectangle) {
+        let text = TextView::new("hello brave world").size(20.0).text_selection(true);
+        let view = View::from(VStack::new(vec![View::text("Not selectable"), View::from(text)]));
+        let node = layout(&view, Limits::loose(300.0, 100.0), renderer);
+        let bounds = node.children[1].bounds;
+        (view, node, bounds)
+    }
+
+    /// Double-clicks the middle of the selectable text.
+    fn double_click(selection: &mut Selection, view: &View, node: &Node, renderer: &Renderer, bounds: Rectangle) {
+        let (x, y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        let now = Instant::now();
+        selection.press(view, node, renderer, x, y, 0.0, 0.0, now);
+        selection.press(view, node, renderer, x, y, 0.0, 0.0, now + Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_clip_shapes_cut_off_hits_on_children() {
+        fn clicked() {}
+        let renderer = Renderer::with_bundled_fonts();
+        let stack = || VStack::new(vec![View::from(Button::new("Go").on_click(clicked).padding(40.0))]);
+        let corner = |view: &View| {
+            let node = layout(view, Limits::loose(300.0, 300.0), &renderer);
+            let bounds = node.children[0].bounds;
+            assert!(button_at(view, &node, bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0, 0.0, 0.0).is_some());
+            button_at(view, &node, bounds.x + 2.0, bounds.y + 2.0, 0.0, 0.0)
+        };
+
+        assert!(corner(&View::from(stack())).is_some());
+        assert!(corner(&View::from(stack().clipped())).is_some());
+        assert!(corner(&View::from(stack().clip_shape(ShapeKind::Circle))).is_none(), "the corner is outside the circle");
+    }
+
+    /// A button with a wide label turned upright, 60px inside a 240x240 window, with
+    /// its center and its size before turning.
+    fn upright_button(renderer: &Renderer) -> (View, Node, Point, Size) {
+        fn clicked() {}
+        let button = Button::new("A wide label").on_click(clicked).rotation_effect(std::f32::consts::FRAC_PI_2);
+        let view = View::from(VStack::new(vec![View::from(button)]).padding(60.0));
+        let node = layout(&view, Limits::loose(240.0, 240.0), renderer);
+        let bounds = node.children[0].bounds;
+        let center = Point::new(bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        (view, node, center, bounds.size())
+    }
+
+    #[test]
+    fn test_rotated_button_is_hit_where_it_appears() {
+        let renderer = Renderer::with_bundled_fonts();
+        let (view, node, center, size) = upright_button(&renderer);
+        assert!(size.width > size.height + 20.0, "the label makes the button wide: {size:?}");
+
+        // Turned upright, the button covers a vertical strip through its center.
+        assert!(button_at(&view, &node, center.x, center.y - size.width / 2.0 + 4.0, 0.0, 0.0).is_some());
+        assert!(button_at(&view, &node, center.x - size.width / 2.0 + 4.0, center.y, 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_offset_and_scaled_button_is_hit_where_it_appears() {
+        fn clicked() {}
+        let renderer = Renderer::with_bundled_fonts();
+        let moved = View::from(Button::new("Go").on_click(clicked).offset(100.0, 0.0).scale_effect(2.0, UnitPoint::TOP_LEADING));
+        let node = layout(&moved, Limits::loose(240.0, 240.0), &renderer);
+        let size = node.bounds;
+        assert!(button_at(&moved, &node, size.width / 2.0, size.height / 2.0, 0.0, 0.0).is_none());
+        assert!(button_at(&moved, &node, 100.0 + size.width * 1.5, size.height * 1.5, 0.0, 0.0).is_some());
+    }
+
+    #[test]
+    fn test_dragging_past_the_end_selects_everything() {
+        let renderer = Renderer::with_bundled_fonts();
+        let (view, node, bounds) = selectable_text(&renderer);
+        let y = bounds.y + bounds.height / 2.0;
+
+        let mut selection = Selection::default();
+        assert!(selection.press(&view, &node, &renderer, bounds.x + 1.0, y, 0.0, 0.0, Instant::now()));
+        assert_eq!(selection.selected_text(&view), None);
+        assert!(selection.drag(&view, &node, &renderer, bounds.x + bounds.width + 50.0, y, 0.0, 0.0));
+        selection.release();
+        assert_eq!(selection.selected_text(&view).as_deref(), Some("hello brave world"));
+    }
+
+    #[test]
+    fn test_double_click_selects_the_word_under_it() {
+        let renderer = Renderer::with_bundled_fonts();
+        let (view, node, bounds) = selectable_text(&renderer);
+
+        let mut selection = Selection::default();
+        double_click(&mut selection, &view, &node, &renderer, bounds);
+        assert!(!selection.is_dragging());
+        assert_eq!(selection.selected_text(&view).as_deref(), Some("brave"));
+    }
+
+    #[test]
+    fn test_copy_puts_the_selected_text_on_the_clipboard() {
+        let renderer = Renderer::with_bundled_fonts();
+        let (view, node, bounds) = selectable_text(&renderer);
+        let clipboard = MemoryClipboard::new();
+
+        let mut selection = Selection::default();
+        assert!(!selection.copy(&view, &clipboard));
+        double_click(&mut selection, &view, &node, &renderer, bounds);
+        assert!(selection.copy(&view, &clipboard));
+        assert_eq!(clipboard.read().as_deref(), Some("brave"));
+    }
+
+    #[test]
+    fn test_selection_is_highlighted_behind_the_glyphs() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        let (view, node, bounds) = selectable_text(&renderer);
+        let mut selection = Selection::default();
+        double_click(&mut selection, &view, &node, &renderer, bounds);
+
+        let mut highlighted = view.clone();
+        selection.apply(&mut highlighted);
+        let mut buffer = vec![0u32; 300 * 100];
+        renderer.draw(&highlighted, &node, &mut buffer, 300, 100, 0x00FF_FFFF, 0.0, 0.0);
+        let y = (bounds.y + bounds.height / 2.0) as usize;
+        let highlight = buffer[y * 300..(y + 1) * 300].iter().filter(|pixel| **pixel == 0xB5D6FF).count();
+        assert!(highlight > 10, "{highlight} highlighted pixels");
+    }
+
+    #[test]
+    fn test_pressing_text_that_is_not_selectable_clears_the_selection() {
+        let renderer = Renderer::with_bundled_fonts();
+        let (view, node, bounds) = selectable_text(&renderer);
+        let mut selection = Selection::default();
+        double_click(&mut selection, &view, &node, &renderer, bounds);
+
+        let plain = node.children[0].bounds;
+        assert!(selection.press(&view, &node, &renderer, plain.x + 2.0, plain.y + plain.height / 2.0, 0.0, 0.0, Instant::now()));
+        assert_eq!(selection.selected_text(&view), None);
+    }
 }
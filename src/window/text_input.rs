@@ -0,0 +1,1122 @@
+//! Editing the focused text field or text editor with a cosmic-text [`Editor`].
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use cosmic_text::{Action, Buffer, Change, Cursor, Edit, Editor, Metrics, Motion, Selection as EditorSelection, Shaping};
+use winit::event::KeyEvent;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use super::{direction_at, view_at, view_at_path, view_at_path_mut, word_at, DOUBLE_CLICK};
+use crate::clipboard::Clipboard;
+use crate::core::font::Font;
+use crate::core::text::{LineHeight, Text};
+use crate::layout::{LayoutDirection, Node, Rectangle};
+use crate::render::{attrs, Renderer, CARET_WIDTH, DEFAULT_FONT_SIZE};
+use crate::view::{Binding, Editing, TextEditor, TextField};
+use crate::View;
+
+/// How long the caret stays on, and then off.
+const BLINK: Duration = Duration::from_millis(500);
+
+/// How many edits can be undone.
+const UNDO_LIMIT: usize = 100;
+
+/// An editing command, decoded from a key press or an input method.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Command {
+    /// Type text at the cursor, replacing the selection.
+    Insert(String),
+    /// Move the cursor; `true` extends the selection.
+    Move(Motion, bool),
+    /// Delete the selection, or from the cursor to where the motion leads.
+    Delete(Motion),
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+    /// Return: submits a field, and starts a new line in an editor.
+    Submit,
+    /// Clear the selection.
+    Escape,
+    Undo,
+    Redo,
+}
+
+/// Decodes a key press into an editing command. Moves by word use Ctrl, or Alt as
+/// on macOS; Cmd moves to the ends of the line, or of the text.
+pub(crate) fn command(event: &KeyEvent, modifiers: ModifiersState) -> Option<Command> {
+    let extend = modifiers.shift_key();
+    let word = modifiers.control_key() || modifiers.alt_key();
+    let line = modifiers.super_key();
+    // Ctrl+Alt is AltGr on Windows, which types characters
+    let shortcut = (modifiers.control_key() && !modifiers.alt_key()) || modifiers.super_key();
+    let by = |character: Motion, word_motion: Motion, line_motion: Motion| match () {
+        _ if line => line_motion,
+        _ if word => word_motion,
+        _ => character,
+    };
+    let text_end = |motion: Motion, end: Motion| if modifiers.control_key() { end } else { motion };
+
+    let command = match &event.logical_key {
+        Key::Named(NamedKey::ArrowLeft) => Command::Move(by(Motion::Previous, Motion::PreviousWord, Motion::Home), extend),
+        Key::Named(NamedKey::ArrowRight) => Command::Move(by(Motion::Next, Motion::NextWord, Motion::End), extend),
+        Key::Named(NamedKey::ArrowUp) => Command::Move(by(Motion::Up, Motion::Up, Motion::BufferStart), extend),
+        Key::Named(NamedKey::ArrowDown) => Command::Move(by(Motion::Down, Motion::Down, Motion::BufferEnd), extend),
+        Key::Named(NamedKey::Home) => Command::Move(text_end(Motion::Home, Motion::BufferStart), extend),
+        Key::Named(NamedKey::End) => Command::Move(text_end(Motion::End, Motion::BufferEnd), extend),
+        Key::Named(NamedKey::PageUp) => Command::Move(Motion::PageUp, extend),
+        Key::Named(NamedKey::PageDown) => Command::Move(Motion::PageDown, extend),
+        Key::Named(NamedKey::Backspace) => Command::Delete(by(Motion::Previous, Motion::PreviousWord, Motion::Home)),
+        Key::Named(NamedKey::Delete) => Command::Delete(by(Motion::Next, Motion::NextWord, Motion::End)),
+        Key::Named(NamedKey::Enter) => Command::Submit,
+        Key::Named(NamedKey::Escape) => Command::Escape,
+        Key::Character(character) if shortcut => match character.to_lowercase().as_str() {
+            "a" => Command::SelectAll,
+            "c" => Command::Copy,
+            "x" => Command::Cut,
+            "v" => Command::Paste,
+            "z" if extend => Command::Redo,
+            "z" => Command::Undo,
+            "y" => Command::Redo,
+            _ => return None,
+        },
+        _ if shortcut => return None,
+        _ => {
+            let text: String = event.text.as_ref()?.chars().filter(|c| !c.is_control()).collect();
+            if text.is_empty() {
+                return None;
+            }
+            Command::Insert(text)
+        }
+    };
+    Some(command)
+}
+
+/// A view that takes the keyboard: a one-line field, which scrolls sideways, or a
+/// multi-line editor, which scrolls up and down.
+#[derive(Debug, Clone)]
+enum Editable {
+    Field(TextField),
+    Editor(TextEditor),
+}
+
+impl Editable {
+    /// Returns the field or editor `view` is, measured as if it wasn't scrolled.
+    fn from_view(view: &View) -> Option<Self> {
+        match view {
+            View::TextField(field) => Some(Self::Field(TextField { scroll: 0.0, ..field.clone() })),
+            View::TextEditor(editor) => Some(Self::Editor(TextEditor { scroll: 0.0, ..editor.clone() })),
+            _ => None,
+        }
+    }
+
+    fn binding(&self) -> &Binding<String> {
+        match self {
+            Self::Field(field) => &field.text,
+            Self::Editor(editor) => &editor.text,
+        }
+    }
+
+    fn font(&self) -> &Font {
+        match self {
+            Self::Field(field) => &field.font,
+            Self::Editor(editor) => &editor.font,
+        }
+    }
+
+    fn size(&self) -> f32 {
+        match self {
+            Self::Field(field) => field.size,
+            Self::Editor(editor) => editor.size,
+        }
+        .unwrap_or(DEFAULT_FONT_SIZE)
+    }
+
+    fn is_multiline(&self) -> bool {
+        matches!(self, Self::Editor(_))
+    }
+
+    fn is_secure(&self) -> bool {
+        matches!(self, Self::Field(field) if field.secure)
+    }
+
+    fn inner_bounds(&self, bounds: Rectangle) -> Rectangle {
+        match self {
+            Self::Field(field) => field.inner_bounds(bounds),
+            Self::Editor(editor) => editor.inner_bounds(bounds),
+        }
+    }
+
+    /// Moves a point in the view to where it is in the unscrolled content.
+    fn unscroll(&self, x: f32, y: f32, scroll: f32) -> (f32, f32) {
+        if self.is_multiline() {
+            (x, y + scroll)
+        } else {
+            (x + scroll, y)
+        }
+    }
+
+    /// Lays out `value` as the view draws it in `inner` and `direction`, unscrolled, to
+    /// hit-test, place the caret and scroll without shaping the text again.
+    fn shape(&self, renderer: &Renderer, value: &str, inner: Rectangle, direction: LayoutDirection) -> Shaped<'_> {
+        match self {
+            Self::Field(field) => Shaped::Field(field, renderer.field_text(field, value, inner, direction)),
+            Self::Editor(editor) => Shaped::Editor(editor, renderer.editor_paragraphs(editor, value, inner, direction)),
+        }
+    }
+}
+
+/// The text of a field, or the paragraphs of an editor, laid out once for an event
+/// or a frame.
+enum Shaped<'a> {
+    Field(&'a TextField, Text),
+    Editor(&'a TextEditor, Vec<(Range<usize>, Text)>),
+}
+
+impl Shaped<'_> {
+    /// Returns the byte offset of `value` closest to (`x`, `y`) in the unscrolled content.
+    fn hit(&self, renderer: &Renderer, value: &str, x: f32, y: f32) -> usize {
+        match self {
+            Self::Field(field, text) => field.value_offset(value, renderer.hit_text(text, x - text.bounds.x, y - text.bounds.y)),
+            Self::Editor(_, paragraphs) => {
+                let found = paragraphs.iter().find(|(_, text)| y < text.bounds.y + text.bounds.height);
+                let Some((range, text)) = found.or(paragraphs.last()) else { return 0 };
+                // Above or below the text, the first or last line is closest
+                let y = (y - text.bounds.y).clamp(0.0, text.bounds.height - 1.0);
+                range.start + renderer.hit_text(text, x - text.bounds.x, y).min(range.len())
+            }
+        }
+    }
+
+    /// Returns the caret in front of the byte offset `cursor` in the unscrolled
+    /// content, after any text being composed.
+    fn caret(&self, renderer: &Renderer, value: &str, inner: Rectangle, cursor: usize) -> Rectangle {
+        match self {
+            Self::Field(field, text) => {
+                let preedit = field.editing.as_ref().and_then(|editing| editing.preedit.as_ref());
+                renderer.caret_bounds(text, field.display_offset(value, cursor) + preedit.map_or(0, String::len))
+            }
+            Self::Editor(editor, paragraphs) => {
+                let preedit = editor.editing.as_ref().and_then(|editing| editing.preedit.as_ref());
+                paragraphs
+                    .iter()
+                    .find(|(range, _)| range.start <= cursor && cursor <= range.end)
+                    .map_or(Rectangle::new(inner.x, inner.y, 0.0, 0.0), |(range, text)| {
+                        renderer.caret_bounds(text, cursor - range.start + preedit.map_or(0, String::len))
+                    })
+            }
+        }
+    }
+
+    /// Returns how far the content reaches in the direction the view scrolls, and
+    /// how much of that is visible.
+    fn extent(&self, inner: Rectangle) -> (f32, f32) {
+        match self {
+            Self::Field(_, text) => (text.bounds.width, inner.width),
+            Self::Editor(_, paragraphs) => {
+                let bottom = paragraphs.last().map_or(inner.y, |(_, text)| text.bounds.y + text.bounds.height);
+                (bottom - inner.y, inner.height)
+            }
+        }
+    }
+
+    /// Returns `scroll` limited to what keeps the view filled with content.
+    fn clamp_scroll(&self, inner: Rectangle, scroll: f32) -> f32 {
+        let (extent, visible) = self.extent(inner);
+        scroll.min(extent - visible).max(0.0)
+    }
+}
+
+/// Returns where the window keeps the editing state of a text field or editor.
+fn editing_mut(view: &mut View) -> Option<&mut Option<Editing>> {
+    match view {
+        View::TextField(field) => Some(&mut field.editing),
+        View::TextEditor(editor) => Some(&mut editor.editing),
+        _ => None,
+    }
+}
+
+fn scroll_mut(view: &mut View) -> Option<&mut f32> {
+    match view {
+        View::TextField(field) => Some(&mut field.scroll),
+        View::TextEditor(editor) => Some(&mut editor.scroll),
+        _ => None,
+    }
+}
+
+/// Returns the byte offset of `cursor` in the text of `buffer`.
+fn offset_of(buffer: &Buffer, cursor: Cursor) -> usize {
+    let before: usize = buffer.lines[..cursor.line.min(buffer.lines.len())]
+        .iter()
+        .map(|line| line.text().len() + line.ending().as_str().len())
+        .sum();
+    before + cursor.index
+}
+
+/// Returns the cursor at the byte offset `offset` of the text of `buffer`.
+fn cursor_at(buffer: &Buffer, offset: usize) -> Cursor {
+    let mut start = 0;
+    for (index, line) in buffer.lines.iter().enumerate() {
+        let end = start + line.text().len();
+        if offset <= end || index + 1 == buffer.lines.len() {
+            return Cursor::new(index, offset.saturating_sub(start).min(line.text().len()));
+        }
+        start = end + line.ending().as_str().len();
+    }
+    Cursor::new(0, 0)
+}
+
+fn buffer_text(buffer: &Buffer) -> String {
+    buffer.lines.iter().flat_map(|line| [line.text(), line.ending().as_str()]).collect()
+}
+
+/// Which run of edits an edit continues, so typing a word undoes at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditKind {
+    Typing,
+    Deleting,
+}
+
+/// The text fields and editors of the window: the one being edited, and how far
+/// each is scrolled. Views are rebuilt every frame, so they are remembered by their
+/// position in the tree, like a [`Selection`](super::Selection).
+#[derive(Debug, Default)]
+pub(crate) struct TextInput {
+    focus: Option<Focus>,
+    scrolls: HashMap<Vec<usize>, f32>,
+}
+
+impl TextInput {
+    /// Focuses the text field or editor under (`x`, `y`) and places its cursor, or
+    /// clears the focus if there is none. Returns whether anything changed.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn press(
+        &mut self,
+        view: &View,
+        node: &Node,
+        renderer: &Renderer,
+        x: f32,
+        y: f32,
+        offset_x: f32,
+        offset_y: f32,
+        now: Instant,
+    ) -> bool {
+        let mut path = Vec::new();
+        let is_editable = |view: &View| matches!(view, View::TextField(_) | View::TextEditor(_));
+        let Some((view, bounds, x, y)) = view_at(view, node, x, y, offset_x, offset_y, &is_editable, &mut path) else {
+            return self.focus.take().is_some();
+        };
+        let Some(editable) = Editable::from_view(view) else { return false };
+        let scroll = self.scrolls.get(&path).copied().unwrap_or(0.0);
+        if self.focus.as_ref().is_none_or(|focus| focus.path != path) {
+            self.focus = Some(Focus::new(path, editable.clone(), renderer));
+        }
+        if let Some(focus) = &mut self.focus {
+            let inner = editable.inner_bounds(bounds);
+            let (x, y) = editable.unscroll(x, y, scroll);
+            focus.direction = direction_at(node, &focus.path);
+            focus.press(editable, renderer, inner, x, y, now);
+        }
+        true
+    }
+
+    /// Extends the selection to the mouse while it is dragged. Returns whether the
+    /// selection changed.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn drag(&mut self, view: &View, node: &Node, renderer: &Renderer, x: f32, y: f32, offset_x: f32, offset_y: f32) -> bool {
+        let Some(focus) = &mut self.focus else { return false };
+        let scroll = self.scrolls.get(&focus.path).copied().unwrap_or(0.0);
+        focus.drag(view, node, renderer, x, y, offset_x, offset_y, scroll)
+    }
+
+    pub(crate) fn release(&mut self) {
+        if let Some(focus) = &mut self.focus {
+            focus.anchor = None;
+        }
+    }
+
+    pub(crate) fn is_dragging(&self) -> bool {
+        self.focus.as_ref().is_some_and(|focus| focus.anchor.is_some())
+    }
+
+    pub(crate) fn is_focused(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Runs an editing command on the focused view. Returns whether it needs to be
+    /// redrawn.
+    pub(crate) fn execute(&mut self, command: Command, renderer: &Renderer, clipboard: &dyn Clipboard) -> bool {
+        self.focus.as_mut().is_some_and(|focus| focus.execute(command, renderer, clipboard))
+    }
+
+    /// Shows `preedit`, text an input method is composing, at the cursor.
+    pub(crate) fn set_preedit(&mut self, preedit: Option<String>) {
+        let Some(focus) = &mut self.focus else { return };
+        focus.preedit = preedit.filter(|preedit| !preedit.is_empty());
+        focus.blink = Instant::now();
+        focus.follow = true;
+    }
+
+    /// Scrolls the text editor under (`x`, `y`) by `delta` logical pixels, positive
+    /// towards the start. Returns whether it moved.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn scroll(&mut self, view: &View, node: &Node, renderer: &Renderer, x: f32, y: f32, offset_x: f32, offset_y: f32, delta: f32) -> bool {
+        let mut path = Vec::new();
+        let is_editor = |view: &View| matches!(view, View::TextEditor(_));
+        let Some((view, bounds, ..)) = view_at(view, node, x, y, offset_x, offset_y, &is_editor, &mut path) else {
+            return false;
+        };
+        let Some(editable) = Editable::from_view(view) else { return false };
+        let inner = editable.inner_bounds(bounds);
+        let value = editable.binding().get();
+        let shaped = editable.shape(renderer, &value, inner, direction_at(node, &path));
+        let scroll = self.scrolls.entry(path).or_default();
+        let before = *scroll;
+        *scroll = shaped.clamp_scroll(inner, before - delta);
+        *scroll != before
+    }
+
+    /// Hands the cursor, selection and caret to the focused view and the scroll
+    /// offsets to every field and editor, so they are drawn. Drops the focus if its
+    /// view is gone from the tree.
+    pub(crate) fn apply(&mut self, view: &mut View, node: &Node, renderer: &Renderer, offset_x: f32, offset_y: f32, now: Instant) {
+        if let Some(focus) = &mut self.focus {
+            let scroll = self.scrolls.entry(focus.path.clone()).or_default();
+            if !focus.apply(view, node, renderer, offset_x, offset_y, now, scroll) {
+                self.focus = None;
+            }
+        }
+
+        let focused = self.focus.as_ref().map(|focus| &focus.path);
+        self.scrolls.retain(|path, scroll| {
+            let Some((found, bounds, ..)) = view_at_path(view, node, path, 0.0, 0.0, offset_x, offset_y) else {
+                return false;
+            };
+            let Some(editable) = Editable::from_view(found) else { return false };
+            // The content may have shrunk since it was scrolled; the focused view was
+            // just clamped above
+            if focused != Some(path) {
+                let value = editable.binding().get();
+                let inner = editable.inner_bounds(bounds);
+                *scroll = editable.shape(renderer, &value, inner, direction_at(node, path)).clamp_scroll(inner, *scroll);
+            }
+            if let Some(view_scroll) = view_at_path_mut(view, path).and_then(scroll_mut) {
+                *view_scroll = *scroll;
+            }
+            true
+        });
+    }
+
+    /// Returns where the caret was last drawn, for placing input method popups.
+    pub(crate) fn caret(&self) -> Option<Rectangle> {
+        let focus = self.focus.as_ref()?;
+        let caret = focus.caret?;
+        let scroll = self.scrolls.get(&focus.path).copied().unwrap_or(0.0);
+        let (x, y) = if focus.editable.is_multiline() { (caret.x, caret.y - scroll) } else { (caret.x - scroll, caret.y) };
+        Some(Rectangle::new(x, y, CARET_WIDTH, caret.height))
+    }
+
+    /// Returns when the caret of the focused view next turns on or off.
+    pub(crate) fn next_blink(&self, now: Instant) -> Option<Instant> {
+        let focus = self.focus.as_ref()?;
+        let blinks = now.saturating_duration_since(focus.blink).as_millis() / BLINK.as_millis();
+        Some(focus.blink + BLINK * (blinks as u32 + 1))
+    }
+}
+
+/// The text field or editor being edited.
+#[derive(Debug)]
+struct Focus {
+    path: Vec<usize>,
+    editor: Editor<'static>,
+    /// The view as it was last drawn, without editing state or scroll.
+    editable: Editable,
+    /// Where its text went when it was last drawn, unscrolled.
+    inner: Rectangle,
+    /// The layout direction of the view when it was last drawn.
+    direction: LayoutDirection,
+    /// The text the editor and the binding last agreed on.
+    value: String,
+    /// Text being composed by an input method.
+    preedit: Option<String>,
+    /// When the caret last turned on.
+    blink: Instant,
+    /// Whether to scroll the caret into view when next drawn.
+    follow: bool,
+    /// Where a drag started, as a byte offset.
+    anchor: Option<usize>,
+    last_press: Option<(Instant, usize)>,
+    /// Where the caret was last drawn, in logical window coordinates, unscrolled.
+    caret: Option<Rectangle>,
+    /// Where moving up and down aims, kept across lines of different lengths.
+    column: Option<f32>,
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+    last_edit: Option<EditKind>,
+}
+
+impl Focus {
+    fn new(path: Vec<usize>, editable: Editable, renderer: &Renderer) -> Self {
+        let value = editable.binding().get();
+        let size = editable.size();
+        let mut font_system = renderer.font_system().write().unwrap();
+        let mut buffer = Buffer::new(&mut font_system, Metrics::new(size, LineHeight::default().resolve(size)));
+        // Shaped like the text cache shapes the view, so the editor moves by the same glyphs
+        let attrs = attrs(&font_system, editable.font());
+        buffer.set_text(&mut font_system, &value, &attrs, Shaping::Advanced, None);
+
+        Self {
+            path,
+            editor: Editor::new(buffer),
+            editable,
+            inner: Rectangle::new(0.0, 0.0, 0.0, 0.0),
+            direction: LayoutDirection::default(),
+            value,
+            preedit: None,
+            blink: Instant::now(),
+            follow: true,
+            anchor: None,
+            last_press: None,
+            caret: None,
+            column: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+        }
+    }
+
+    /// Places the cursor at a press in the view, or selects the word under it on a
+    /// double click.
+    fn press(&mut self, editable: Editable, renderer: &Renderer, inner: Rectangle, x: f32, y: f32, now: Instant) {
+        self.editable = editable;
+        self.inner = inner;
+        let offset = self.editable.shape(renderer, &self.value, inner, self.direction).hit(renderer, &self.value, x, y);
+        let double_click = self
+            .last_press
+            .is_some_and(|(time, last)| now.duration_since(time) < DOUBLE_CLICK && last == offset);
+
+        if double_click {
+            // Words of a secure field are not to be told apart
+            let word = if self.editable.is_secure() { 0..self.value.len() } else { word_at(&self.value, offset) };
+            self.select(word.start, word.end);
+            self.anchor = None;
+            self.last_press = None;
+        } else {
+            self.select(offset, offset);
+            self.anchor = Some(offset);
+            self.last_press = Some((now, offset));
+        }
+        self.preedit = None;
+        self.column = None;
+        self.last_edit = None;
+        self.blink = now;
+        self.follow = true;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn drag(&mut self, view: &View, node: &Node, renderer: &Renderer, x: f32, y: f32, offset_x: f32, offset_y: f32, scroll: f32) -> bool {
+        let Some(anchor) = self.anchor else { return false };
+        let Some((view, bounds, x, y)) = view_at_path(view, node, &self.path, x, y, offset_x, offset_y) else {
+            return false;
+        };
+        let Some(editable) = Editable::from_view(view) else { return false };
+        let inner = editable.inner_bounds(bounds);
+        let (x, y) = editable.unscroll(x, y, scroll);
+        let offset = editable.shape(renderer, &self.value, inner, direction_at(node, &self.path)).hit(renderer, &self.value, x, y);
+        let before = (self.editor.cursor(), self.editor.selection());
+        self.select(anchor, offset);
+        // Dragging past the edge scrolls along
+        self.follow = true;
+        before != (self.editor.cursor(), self.editor.selection())
+    }
+
+    /// Returns the byte offset of the cursor.
+    fn cursor(&self) -> usize {
+        let cursor = self.editor.cursor();
+        self.editor.with_buffer(|buffer| offset_of(buffer, cursor)).min(self.value.len())
+    }
+
+    /// Selects from `anchor` to `offset` and puts the cursor at `offset`.
+    fn select(&mut self, anchor: usize, offset: usize) {
+        let (anchor, cursor) = self.editor.with_buffer(|buffer| (cursor_at(buffer, anchor), cursor_at(buffer, offset)));
+        self.editor.set_cursor(cursor);
+        let selection = if anchor == cursor { EditorSelection::None } else { EditorSelection::Normal(anchor) };
+        self.editor.set_selection(selection);
+    }
+
+    /// Returns the selected byte range, if the selection isn't empty.
+    fn selection(&self) -> Option<std::ops::Range<usize>> {
+        let (start, end) = self.editor.selection_bounds()?;
+        let (start, end) = self.editor.with_buffer(|buffer| (offset_of(buffer, start), offset_of(buffer, end)));
+        (start != end).then_some(start..end)
+    }
+
+    /// Runs an editing command and writes the text back to the binding. Returns
+    /// whether the view needs to be redrawn.
+    fn execute(&mut self, command: Command, renderer: &Renderer, clipboard: &dyn Clipboard) -> bool {
+        let multiline = self.editable.is_multiline();
+        let secure = self.editable.is_secure();
+        let font_system = || renderer.font_system().write().unwrap();
+        let kind = match &command {
+            Command::Insert(_) => Some(EditKind::Typing),
+            Command::Delete(Motion::Previous) => Some(EditKind::Deleting),
+            _ => None,
+        };
+        let vertical = matches!(command, Command::Move(Motion::Up | Motion::Down | Motion::PageUp | Motion::PageDown, _));
+        if !vertical {
+            self.column = None;
+        }
+
+        match command {
+            Command::Undo => return self.undo(),
+            Command::Redo => return self.redo(),
+            Command::Copy | Command::Cut if secure => return false,
+            Command::Copy => {
+                if let Some(text) = self.editor.copy_selection() {
+                    clipboard.write(text);
+                }
+                return false;
+            }
+            Command::Submit if !multiline => {
+                if let Some(on_submit) = self.on_submit() {
+                    on_submit(&self.value);
+                }
+                return true;
+            }
+            // Editors move by the lines as they are wrapped on screen
+            Command::Move(motion @ (Motion::Up | Motion::Down | Motion::PageUp | Motion::PageDown | Motion::Home | Motion::End), extend)
+                if multiline =>
+            {
+                self.move_by_lines(renderer, motion, extend);
+                self.last_edit = None;
+                return self.finish();
+            }
+            _ => {}
+        }
+
+        self.editor.start_change();
+        match command {
+            Command::Insert(text) => {
+                // One line: pasted line breaks become spaces
+                let text = if multiline { text } else { text.replace(['\r', '\n'], " ") };
+                self.editor.insert_string(&text, None);
+                self.preedit = None;
+            }
+            Command::Submit => self.editor.insert_string("\n", None),
+            Command::Move(motion, extend) => {
+                let motion = match motion {
+                    // Words of a secure field are not to be told apart
+                    Motion::PreviousWord if secure => Motion::Home,
+                    Motion::NextWord if secure => Motion::End,
+                    // Up and down go to the ends of the line
+                    Motion::Up | Motion::PageUp => Motion::Home,
+                    Motion::Down | Motion::PageDown => Motion::End,
+                    motion => motion,
+                };
+                match self.selection() {
+                    _ if extend => {
+                        if self.editor.selection() == EditorSelection::None {
+                            self.editor.set_selection(EditorSelection::Normal(self.editor.cursor()));
+                        }
+                        self.editor.action(&mut font_system(), Action::Motion(motion));
+                    }
+                    // Moving by a character collapses the selection to that side
+                    Some(selection) if matches!(motion, Motion::Previous | Motion::Next) => {
+                        let offset = if motion == Motion::Previous { selection.start } else { selection.end };
+                        self.select(offset, offset);
+                    }
+                    _ => {
+                        self.editor.set_selection(EditorSelection::None);
+                        self.editor.action(&mut font_system(), Action::Motion(motion));
+                    }
+                }
+            }
+            Command::Delete(motion) => {
+                if self.selection().is_none() {
+                    let motion = match motion {
+                        Motion::PreviousWord if secure => Motion::Home,
+                        Motion::NextWord if secure => Motion::End,
+                        motion => motion,
+                    };
+                    self.editor.set_selection(EditorSelection::Normal(self.editor.cursor()));
+                    self.editor.action(&mut font_system(), Action::Motion(motion));
+                }
+                self.editor.delete_selection();
+            }
+            Command::SelectAll => {
+                self.select(0, self.value.len());
+            }
+            Command::Cut => {
+                if let Some(text) = self.editor.copy_selection() {
+                    clipboard.write(text);
+                    self.editor.delete_selection();
+                }
+            }
+            Command::Paste => {
+                if let Some(text) = clipboard.read() {
+                    let text = if multiline { text } else { text.replace(['\r', '\n'], " ") };
+                    self.editor.insert_string(&text, None);
+                }
+            }
+            Command::Escape => self.editor.action(&mut font_system(), Action::Escape),
+            Command::Copy | Command::Undo | Command::Redo => {}
+        }
+        let change = self.editor.finish_change();
+        self.record(change, kind);
+        self.finish()
+    }
+
+    fn on_submit(&self) -> Option<fn(&str)> {
+        match &self.editable {
+            Editable::Field(field) => field.on_submit,
+            Editable::Editor(_) => None,
+        }
+    }
+
+    /// Moves the cursor of an editor up or down by lines, or to the ends of the line
+    /// it is on, as they are wrapped on screen. Moves up and down keep to the column
+    /// where they started.
+    fn move_by_lines(&mut self, renderer: &Renderer, motion: Motion, extend: bool) {
+        let cursor = self.cursor();
+        let shaped = self.editable.shape(renderer, &self.value, self.inner, self.direction);
+        let caret = shaped.caret(renderer, &self.value, self.inner, cursor);
+        let middle = caret.y + caret.height / 2.0;
+        let (x, y) = match motion {
+            Motion::Home => (self.inner.x - 1.0, middle),
+            Motion::End => (self.inner.x + self.inner.width + 1.0, middle),
+            motion => {
+                let x = *self.column.get_or_insert(caret.x);
+                let distance = match motion {
+                    Motion::Up => -caret.height,
+                    Motion::Down => caret.height,
+                    Motion::PageUp => -self.inner.height.max(caret.height),
+                    _ => self.inner.height.max(caret.height),
+                };
+                (x, middle + distance)
+            }
+        };
+
+        // Past the first or last line, go to the start or end of the text
+        let (extent, _) = shaped.extent(self.inner);
+        let offset = if y < self.inner.y {
+            0
+        } else if y >= self.inner.y + extent {
+            self.value.len()
+        } else {
+            shaped.hit(renderer, &self.value, x, y)
+        };
+
+        let anchor = match self.editor.selection() {
+            EditorSelection::Normal(anchor) if extend => self.editor.with_buffer(|buffer| offset_of(buffer, anchor)),
+            _ if extend => cursor,
+            _ => offset,
+        };
+        self.select(anchor, offset);
+    }
+
+    /// Adds an edit to the undo history, joining it to the last one while the user
+    /// keeps typing or deleting.
+    fn record(&mut self, change: Option<Change>, kind: Option<EditKind>) {
+        let last_edit = std::mem::replace(&mut self.last_edit, kind);
+        let Some(change) = change.filter(|change| !change.items.is_empty()) else { return };
+        self.redo.clear();
+        match self.undo.last_mut() {
+            Some(last) if kind.is_some() && kind == last_edit => last.items.extend(change.items),
+            _ => {
+                self.undo.push(change);
+                if self.undo.len() > UNDO_LIMIT {
+                    self.undo.remove(0);
+                }
+            }
+        }
+    }
+
+    fn undo(&mut self) -> bool {
+        let Some(change) = self.undo.pop() else { return false };
+        let mut reverse = change.clone();
+        reverse.reverse();
+        self.editor.set_selection(EditorSelection::None);
+        self.editor.apply_change(&reverse);
+        self.redo.push(change);
+        self.last_edit = None;
+        self.finish()
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some(change) = self.redo.pop() else { return false };
+        self.editor.set_selection(EditorSelection::None);
+        self.editor.apply_change(&change);
+        self.undo.push(change);
+        self.last_edit = None;
+        self.finish()
+    }
+
+    /// Writes the text back to the binding after a command, and keeps the caret on
+    /// and in view. Returns `true` for a redraw.
+    fn finish(&mut self) -> bool {
+        self.blink = Instant::now();
+        self.follow = true;
+        let text = self.editor.with_buffer(buffer_text);
+        if text != self.value {
+            self.binding().set(text.clone());
+            self.value = text;
+        }
+        true
+    }
+
+    fn binding(&self) -> &Binding<String> {
+        self.editable.binding()
+    }
+
+    /// Hands the cursor, selection and caret to the view so they are drawn, and
+    /// scrolls to keep the cursor in view after it moved. Picks up changes the app
+    /// made to the value. Returns `false` if the view is gone from the tree.
+    #[allow(clippy::too_many_arguments)]
+    fn apply(&mut self, view: &mut View, node: &Node, renderer: &Renderer, offset_x: f32, offset_y: f32, now: Instant, scroll: &mut f32) -> bool {
+        let Some((found, bounds, ..)) = view_at_path(view, node, &self.path, 0.0, 0.0, offset_x, offset_y) else {
+            return false;
+        };
+        // The view may have been rebuilt with another binding or options
+        let Some(editable) = Editable::from_view(found).filter(|editable| editable.is_multiline() == self.editable.is_multiline()) else {
+            return false;
+        };
+        self.inner = editable.inner_bounds(bounds);
+        self.direction = direction_at(node, &self.path);
+        let restyled = editable.font() != self.editable.font() || editable.size() != self.editable.size();
+        self.editable = editable;
+
+        let value = self.binding().get();
+        if restyled || value != self.value {
+            let mut font_system = renderer.font_system().write().unwrap();
+            let size = self.editable.size();
+            let attrs = attrs(&font_system, self.editable.font());
+            self.editor.with_buffer_mut(|buffer| {
+                buffer.set_metrics(&mut font_system, Metrics::new(size, LineHeight::default().resolve(size)));
+                buffer.set_text(&mut font_system, &value, &attrs, Shaping::Advanced, None);
+            });
+        }
+        if value != self.value {
+            self.value = value;
+            self.select(self.value.len(), self.value.len());
+            self.preedit = None;
+            // Undoing would bring back text the app replaced
+            self.undo.clear();
+            self.redo.clear();
+            self.follow = true;
+        }
+
+        let cursor = self.cursor();
+        let elapsed = now.saturating_duration_since(self.blink).as_millis() / BLINK.as_millis();
+        let editing = Editing {
+            cursor,
+            selection: self.selection(),
+            preedit: self.preedit.clone(),
+            caret_visible: elapsed.is_multiple_of(2),
+        };
+        let mut shown = self.editable.clone();
+        match &mut shown {
+            Editable::Field(field) => field.editing = Some(editing.clone()),
+            Editable::Editor(editor) => editor.editing = Some(editing.clone()),
+        }
+        let shaped = shown.shape(renderer, &self.value, self.inner, self.direction);
+        let caret = shaped.caret(renderer, &self.value, self.inner, cursor);
+
+        // Scroll just enough to show the caret, and no further than the end of the text
+        if std::mem::take(&mut self.follow) {
+            let (start, end) = if shown.is_multiline() {
+                (caret.y - self.inner.y, caret.y + caret.height - self.inner.y)
+            } else {
+                (caret.x - self.inner.x, caret.x + CARET_WIDTH - self.inner.x)
+            };
+            let (_, visible) = shaped.extent(self.inner);
+            if end - *scroll > visible {
+                *scroll = end - visible;
+            }
+            if start < *scroll {
+                *scroll = start;
+            }
+            *scroll = shaped.clamp_scroll(self.inner, *scroll);
+        }
+        self.caret = Some(caret);
+
+        if let Some(view_editing) = view_at_path_mut(view, &self.path).and_then(editing_mut) {
+            *view_editing = Some(editing);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use cosmic_text::{Family, Weight};
+    use crate::clipboard::MemoryClipboard;
+    use crate::layout::{layout, Limits};
+    use crate::view::VStack;
+
+    /// A label above a field bound to `name`, like a form.
+    fn form(name: &Binding<String>, on_submit: fn(&str)) -> View {
+        let field = TextField::new(name).placeholder("Name").width(120.0).on_submit(on_submit);
+        View::from(VStack::new(vec![View::text("Label"), View::from(field)]))
+    }
+
+    /// Lays out a [`form`] and focuses its field with a click past the text.
+    fn focus_field(view: &View, renderer: &Renderer) -> (Node, TextInput, Rectangle) {
+        let node = layout(view, Limits::loose(200.0, 100.0), renderer);
+        let bounds = node.children[1].bounds;
+        let mut input = TextInput::default();
+        assert!(input.press(view, &node, renderer, bounds.x + bounds.width - 4.0, bounds.y + bounds.height / 2.0, 0.0, 0.0, Instant::now()));
+        (node, input, bounds)
+    }
+
+    #[test]
+    fn test_clicking_past_the_text_puts_the_cursor_at_the_end() {
+        let renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("hello"));
+        let (_, mut input, _) = focus_field(&form(&name, |_| {}), &renderer);
+        input.execute(Command::Insert(" brave world".into()), &renderer, &MemoryClipboard::new());
+        assert_eq!(name.get(), "hello brave world");
+    }
+
+    #[test]
+    fn test_moves_and_deletes_by_word_edit_the_binding() {
+        let renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("hello brave world"));
+        let (_, mut input, _) = focus_field(&form(&name, |_| {}), &renderer);
+        let clipboard = MemoryClipboard::new();
+        let mut run = |command| input.execute(command, &renderer, &clipboard);
+
+        run(Command::Move(Motion::PreviousWord, false));
+        run(Command::Move(Motion::PreviousWord, true));
+        run(Command::Delete(Motion::Previous));
+        assert_eq!(name.get(), "hello world");
+        run(Command::Delete(Motion::PreviousWord));
+        assert_eq!(name.get(), "world");
+    }
+
+    #[test]
+    fn test_cut_paste_and_submit() {
+        static SUBMITTED: Mutex<String> = Mutex::new(String::new());
+        fn submit(text: &str) {
+            *SUBMITTED.lock().unwrap() = text.to_string();
+        }
+
+        let renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("world"));
+        let (_, mut input, _) = focus_field(&form(&name, submit), &renderer);
+        let clipboard = MemoryClipboard::new();
+        let mut run = |command| input.execute(command, &renderer, &clipboard);
+
+        run(Command::SelectAll);
+        run(Command::Cut);
+        assert_eq!((name.get().as_str(), clipboard.read().as_deref()), ("", Some("world")));
+        // One line: pasted line breaks become spaces
+        clipboard.write("two\nlines".into());
+        run(Command::Paste);
+        run(Command::Submit);
+        assert_eq!(*SUBMITTED.lock().unwrap(), "two lines");
+    }
+
+    #[test]
+    fn test_double_click_selects_a_word() {
+        let renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("two lines"));
+        let view = form(&name, |_| {});
+        let (node, mut input, bounds) = focus_field(&view, &renderer);
+        let clipboard = MemoryClipboard::new();
+
+        let later = Instant::now() + Duration::from_secs(1);
+        let (x, y) = (bounds.x + 8.0, bounds.y + bounds.height / 2.0);
+        input.press(&view, &node, &renderer, x, y, 0.0, 0.0, later);
+        input.press(&view, &node, &renderer, x, y, 0.0, 0.0, later);
+        input.execute(Command::Copy, &renderer, &clipboard);
+        assert_eq!(clipboard.read().as_deref(), Some("two"));
+    }
+
+    #[test]
+    fn test_long_text_scrolls_to_keep_the_caret_in_view() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("hello"));
+        let (node, mut input, bounds) = focus_field(&form(&name, |_| {}), &renderer);
+
+        // The app changes the value; the cursor goes to its end
+        name.set("a long line of text that does not fit".into());
+        let mut view = form(&name, |_| {});
+        input.apply(&mut view, &node, &renderer, 0.0, 0.0, Instant::now());
+        let View::VStack(stack) = &view else { unreachable!() };
+        let View::TextField(drawn) = &stack.children[1] else { unreachable!() };
+        assert_eq!(drawn.editing.as_ref().unwrap().cursor, name.get().len());
+        assert!(drawn.scroll > 100.0, "scrolled by {}", drawn.scroll);
+        let caret = input.caret().unwrap();
+        assert!(caret.x <= bounds.x + bounds.width, "{caret:?} in {bounds:?}");
+
+        // The caret is drawn in the text color
+        let mut buffer = vec![0u32; 200 * 100];
+        renderer.draw(&view, &node, &mut buffer, 200, 100, 0x00FF_FFFF, 0.0, 0.0);
+        let (cx, cy) = (caret.x.floor() as usize, (caret.y + caret.height / 2.0) as usize);
+        assert!(buffer[cy * 200 + cx..cy * 200 + cx + 2].iter().any(|pixel| *pixel & 0xFF < 100), "caret at {cx},{cy}");
+    }
+
+    #[test]
+    fn test_secure_fields_show_bullets_and_keep_their_text_off_the_clipboard() {
+        let renderer = Renderer::with_bundled_fonts();
+        let secret = Binding::new(String::from("pässword"));
+        let secure = TextField::new(&secret).secure(true);
+        assert_eq!(secure.display(&secret.get()).0, "\u{2022}".repeat(8));
+        assert_eq!(secure.value_offset(&secret.get(), secure.display_offset(&secret.get(), 3)), 3);
+
+        let view = View::from(secure);
+        let node = layout(&view, Limits::loose(200.0, 100.0), &renderer);
+        let clipboard = MemoryClipboard::new();
+        clipboard.write("before".into());
+        let mut input = TextInput::default();
+        input.press(&view, &node, &renderer, 10.0, 10.0, 0.0, 0.0, Instant::now());
+        input.execute(Command::SelectAll, &renderer, &clipboard);
+        input.execute(Command::Copy, &renderer, &clipboard);
+        input.execute(Command::Cut, &renderer, &clipboard);
+        assert_eq!((clipboard.read().as_deref(), secret.get().as_str()), (Some("before"), "pässword"));
+    }
+
+    #[test]
+    fn test_focus_is_shaped_with_the_font_and_size_of_its_view() {
+        let renderer = Renderer::with_bundled_fonts();
+        let name = Binding::new(String::from("hello"));
+        let build = |font: Font, size: f32| View::from(TextField::new(&name).font(font).size(size).width(120.0));
+        let view = build(Font::monospace().bold(), 24.0);
+        let node = layout(&view, Limits::loose(200.0, 100.0), &renderer);
+
+        let mut input = TextInput::default();
+        let now = Instant::now();
+        assert!(input.press(&view, &node, &renderer, 10.0, 10.0, 0.0, 0.0, now));
+        let shaped_with = |input: &TextInput, size: f32, family: Family, weight: Weight| {
+            let focus = input.focus.as_ref().unwrap();
+            focus.editor.with_buffer(|buffer| {
+                let attrs = buffer.lines[0].attrs_list().defaults();
+                buffer.metrics().font_size == size && attrs.family == family && attrs.weight == weight
+            })
+        };
+        assert!(shaped_with(&input, 24.0, Family::Monospace, Weight::BOLD));
+
+        // A view rebuilt with another font shapes the buffer again.
+        let mut view = build(Font::family(crate::core::font::Family::Serif), 30.0);
+        input.apply(&mut view, &node, &renderer, 0.0, 0.0, now);
+        assert!(shaped_with(&input, 30.0, Family::Serif, Weight::NORMAL));
+    }
+
+    /// Five lines, the third long enough to wrap in a [`notes_editor`].
+    const NOTES: &str = "first\nsecond\na line long enough to wrap in the editor\nthree\nfour";
+
+    fn notes_editor(notes: &Binding<String>) -> View {
+        View::from(TextEditor::new(notes).frame(160.0, 60.0))
+    }
+
+    /// Lays out a [`notes_editor`] and focuses it with a click at the end of its first line.
+    fn focus_editor(view: &View, renderer: &Renderer) -> (Node, TextInput) {
+        let node = layout(view, Limits::loose(200.0, 200.0), renderer);
+        assert_eq!((node.bounds.width, node.bounds.height), (160.0, 60.0));
+        let mut input = TextInput::default();
+        assert!(input.press(view, &node, renderer, 150.0, 12.0, 0.0, 0.0, Instant::now()));
+        (node, input)
+    }
+
+    /// Hands the editing state to a rebuilt editor and returns how far it scrolled.
+    fn scroll_of(input: &mut TextInput, notes: &Binding<String>, node: &Node, renderer: &Renderer) -> (f32, View) {
+        let mut view = notes_editor(notes);
+        input.apply(&mut view, node, renderer, 0.0, 0.0, Instant::now());
+        let View::TextEditor(drawn) = &view else { unreachable!() };
+        (drawn.scroll, view)
+    }
+
+    #[test]
+    fn test_return_starts_a_new_line_and_arrows_move_between_lines() {
+        let renderer = Renderer::with_bundled_fonts();
+        let notes = Binding::new(String::from("first"));
+        let (_, mut input) = focus_editor(&notes_editor(&notes), &renderer);
+        let clipboard = MemoryClipboard::new();
+        let mut run = |command| input.execute(command, &renderer, &clipboard);
+
+        run(Command::Submit);
+        run(Command::Insert("second".into()));
+        assert_eq!(notes.get(), "first\nsecond");
+        run(Command::Move(Motion::Up, false));
+        run(Command::Move(Motion::Home, false));
+        run(Command::Insert(">".into()));
+        run(Command::Move(Motion::Down, false));
+        run(Command::Move(Motion::End, false));
+        run(Command::Insert("!".into()));
+        assert_eq!(notes.get(), ">first\nsecond!");
+    }
+
+    #[test]
+    fn test_each_run_of_typing_undoes_at_once() {
+        let renderer = Renderer::with_bundled_fonts();
+        let notes = Binding::new(String::from("first"));
+        let (_, mut input) = focus_editor(&notes_editor(&notes), &renderer);
+        let clipboard = MemoryClipboard::new();
+        let mut run = |command| input.execute(command, &renderer, &clipboard);
+
+        run(Command::Insert(" line".into()));
+        run(Command::Move(Motion::BufferStart, false));
+        run(Command::Insert(">".into()));
+        run(Command::Undo);
+        run(Command::Undo);
+        assert_eq!(notes.get(), "first");
+        run(Command::Redo);
+        assert_eq!(notes.get(), "first line");
+    }
+
+    #[test]
+    fn test_long_lines_wrap_at_the_width_of_the_editor() {
+        let renderer = Renderer::with_bundled_fonts();
+        let notes = Binding::new(String::from(NOTES));
+        let view = notes_editor(&notes);
+        let node = layout(&view, Limits::loose(200.0, 200.0), &renderer);
+        let View::TextEditor(editor) = &view else { unreachable!() };
+        let paragraphs = renderer.editor_paragraphs(editor, NOTES, editor.inner_bounds(node.bounds), LayoutDirection::LeftToRight);
+        assert_eq!(paragraphs.len(), 5);
+        assert!(paragraphs[2].1.bounds.height > paragraphs[1].1.bounds.height * 1.5);
+    }
+
+    #[test]
+    fn test_editor_scrolls_to_keep_the_caret_in_view_and_the_wheel_scrolls_it_back() {
+        let renderer = Renderer::with_bundled_fonts();
+        let notes = Binding::new(String::from(NOTES));
+        let view = notes_editor(&notes);
+        let (node, mut input) = focus_editor(&view, &renderer);
+        input.execute(Command::Move(Motion::BufferEnd, false), &renderer, &MemoryClipboard::new());
+
+        let (scrolled, view) = scroll_of(&mut input, &notes, &node, &renderer);
+        assert!(scrolled > 40.0, "scrolled by {scrolled}");
+        let View::TextEditor(editor) = &view else { unreachable!() };
+        let inner = editor.inner_bounds(node.bounds);
+        let caret = input.caret().unwrap();
+        assert!(caret.y + caret.height <= inner.y + inner.height + 0.5, "{caret:?} in {inner:?}");
+
+        assert!(input.scroll(&view, &node, &renderer, 80.0, 30.0, 0.0, 0.0, 1000.0));
+        assert_eq!(scroll_of(&mut input, &notes, &node, &renderer).0, 0.0);
+        assert!(input.scroll(&view, &node, &renderer, 80.0, 30.0, 0.0, 0.0, -1000.0));
+        assert_eq!(scroll_of(&mut input, &notes, &node, &renderer).0, scrolled);
+    }
+
+    #[test]
+    fn test_typing_only_lays_out_the_edited_paragraph_again() {
+        let mut renderer = Renderer::with_bundled_fonts();
+        let notes = Binding::new(String::from(NOTES));
+        let (node, mut input) = focus_editor(&notes_editor(&notes), &renderer);
+        let clipboard = MemoryClipboard::new();
+        let mut buffer = vec![0u32; 200 * 200];
+
+        let (_, view) = scroll_of(&mut input, &notes, &node, &renderer);
+        renderer.draw(&view, &node, &mut buffer, 200, 200, 0x00FF_FFFF, 0.0, 0.0);
+        let before = renderer.text_cache_stats().misses;
+        input.execute(Command::Insert("s".into()), &renderer, &clipboard);
+        let (_, view) = scroll_of(&mut input, &notes, &node, &renderer);
+        renderer.draw(&view, &node, &mut buffer, 200, 200, 0x00FF_FFFF, 0.0, 0.0);
+        let misses = renderer.text_cache_stats().misses - before;
+        assert!((1..=2).contains(&misses), "{misses} paragraphs shaped");
+    }
+}